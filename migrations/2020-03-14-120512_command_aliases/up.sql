@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE command_aliases (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    alias VARCHAR NOT NULL,
+    command_name VARCHAR NOT NULL,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, alias)
+)
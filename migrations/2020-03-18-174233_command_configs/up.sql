@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE command_configs (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    command_name VARCHAR NOT NULL,
+    config_name VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, command_name, config_name)
+)
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    name VARCHAR NOT NULL,
+    content VARCHAR NOT NULL,
+    owner VARCHAR NOT NULL,
+    uses INT NOT NULL DEFAULT 0,
+    creation_date TIMESTAMP NOT NULL,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, name)
+)
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE temp_operations
+    DROP COLUMN attempts,
+    DROP COLUMN last_error;
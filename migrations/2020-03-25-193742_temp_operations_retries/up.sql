@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE temp_operations
+    ADD COLUMN attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN last_error VARCHAR;
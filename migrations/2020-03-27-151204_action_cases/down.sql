@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE actions
+    DROP COLUMN case_id,
+    DROP COLUMN reason,
+    DROP COLUMN log_channel_id,
+    DROP COLUMN log_message_id;
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE actions
+    ADD COLUMN case_id INT,
+    ADD COLUMN reason VARCHAR,
+    ADD COLUMN log_channel_id VARCHAR,
+    ADD COLUMN log_message_id VARCHAR,
+    ADD UNIQUE (server_id, case_id);
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE warn_thresholds (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    warn_level INT NOT NULL,
+    action VARCHAR NOT NULL,
+    duration VARCHAR,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, warn_level)
+)
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE actions
+    ADD COLUMN expired BOOLEAN NOT NULL DEFAULT FALSE;
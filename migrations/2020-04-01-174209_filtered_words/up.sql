@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE filtered_words (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    pattern VARCHAR NOT NULL,
+    is_regex BOOLEAN NOT NULL DEFAULT FALSE,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, pattern, is_regex)
+)
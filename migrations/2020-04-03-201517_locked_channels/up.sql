@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE locked_channels (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    channel_id VARCHAR NOT NULL,
+    allow BIGINT,
+    deny BIGINT,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, channel_id)
+)
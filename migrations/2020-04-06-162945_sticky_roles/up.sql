@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE sticky_roles (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    user_id VARCHAR NOT NULL,
+    role_ids TEXT[] NOT NULL,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, user_id)
+)
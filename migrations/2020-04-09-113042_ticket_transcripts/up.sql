@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE ticket_transcripts (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    channel_id VARCHAR NOT NULL,
+    ticket_name VARCHAR NOT NULL,
+    opener_id VARCHAR,
+    text_content TEXT NOT NULL,
+    html_content TEXT NOT NULL,
+    creation_date TIMESTAMP NOT NULL,
+    FOREIGN KEY (server_id) REFERENCES servers(id)
+)
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE tickets (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    number INT NOT NULL,
+    channel_id VARCHAR NOT NULL,
+    opener_id VARCHAR NOT NULL,
+    claimed_by VARCHAR,
+    status INT NOT NULL,
+    subject VARCHAR,
+    creation_date TIMESTAMP NOT NULL,
+    solved_date TIMESTAMP,
+    closed_date TIMESTAMP,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, number)
+)
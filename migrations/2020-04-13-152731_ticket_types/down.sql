@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE tickets
+    DROP COLUMN type_id;
+
+DROP TABLE ticket_types;
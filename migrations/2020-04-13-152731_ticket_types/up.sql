@@ -0,0 +1,17 @@
+-- Your SQL goes here
+CREATE TABLE ticket_types (
+    id SERIAL PRIMARY KEY,
+    server_id INT NOT NULL references servers(id),
+    name VARCHAR NOT NULL,
+    emoji VARCHAR NOT NULL,
+    category_id VARCHAR NOT NULL,
+    support_role_ids TEXT[] NOT NULL,
+    name_pattern VARCHAR NOT NULL,
+    questions TEXT[] NOT NULL,
+    welcome TEXT,
+    FOREIGN KEY (server_id) REFERENCES servers(id),
+    UNIQUE (server_id, name)
+);
+
+ALTER TABLE tickets
+    ADD COLUMN type_id INT references ticket_types(id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE servers
+    DROP COLUMN last_case_id;
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE servers
+    ADD COLUMN last_case_id INT NOT NULL DEFAULT 0;
+
+UPDATE servers
+    SET last_case_id = COALESCE((SELECT MAX(case_id) FROM actions WHERE actions.server_id = servers.id), 0);
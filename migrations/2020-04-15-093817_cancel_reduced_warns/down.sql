@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+-- cancelled warns can't be told apart from the ones that expired on their own, so they stay expired
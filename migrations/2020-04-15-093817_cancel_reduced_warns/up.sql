@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- warn level is the number of active warns now, so every earlier reduction cancels one of the newest
+-- active warns of the user and levels stay as they were
+UPDATE actions SET expired = TRUE
+WHERE id IN (
+    SELECT id FROM (
+        SELECT warns.id,
+            ROW_NUMBER() OVER (PARTITION BY warns.server_id, warns.target ORDER BY warns.creation_date DESC) AS n,
+            (SELECT COUNT(*) FROM actions reduced
+                WHERE reduced.server_id = warns.server_id
+                AND reduced.target = warns.target
+                AND reduced.action_type = 7) AS reductions
+        FROM actions warns
+        WHERE warns.action_type = 6 AND NOT warns.expired
+    ) ranked
+    WHERE n <= reductions
+);
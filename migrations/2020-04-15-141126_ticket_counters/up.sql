@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE servers
+    ADD COLUMN last_ticket_number INT NOT NULL DEFAULT 0;
+
+UPDATE servers
+    SET last_ticket_number = COALESCE((SELECT MAX(number) FROM tickets WHERE tickets.server_id = servers.id), 0);
@@ -0,0 +1,281 @@
+use crate::command::{ArgOption, Command, CommandConfig, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::model::id::{GuildId, UserId};
+use serenity::prelude::Context;
+use crate::bot_modules::BotModule;
+use crate::bot_modules::moderation::warn_command::WarnCommand;
+use crate::utils::db::{ServerInfo, create_case, create_temp_operation, delete_temp_operations, get_special_entity_by_type, ActionType};
+use crate::utils::get_duration;
+use crate::utils::special_entities_tools::{send_to_mod_logs, log_case};
+use crate::database::models::SpecialEntityType;
+use crate::gateway::{DiscordGateway, Embed};
+use crate::handler::STATE;
+use super::AutomodModule;
+use super::rules::{Rule, caps_percent, has_invite, blocked_domain, is_zalgo, parse_ids};
+use chrono::{Duration, Utc};
+use log::error;
+use std::collections::HashMap;
+
+const ACTIONS: [&str; 4] = ["delete", "warn", "mute", "kick"];
+
+pub struct AutomodCommand;
+
+impl AutomodCommand {
+    pub fn command() -> Box<dyn Command> {
+        let mut builder = CommandBuilder::new("automod")
+            .desc("Automatic moderation of messages. Rules are changed with the `config` command, e.g. `config automod caps 80`.")
+            .perms(&["automod"])
+            .config(CommandConfig::new("spam-messages", ArgOption::Numeric, "5")
+                .desc("how many messages can be sent within `spam-time`. 0 turns the rule off."))
+            .config(CommandConfig::new("spam-time", ArgOption::Time, "5s")
+                .desc("time in which `spam-messages` are counted."))
+            .config(CommandConfig::new("duplicates", ArgOption::Numeric, "3")
+                .desc("how many times in a row the same message can be sent. 0 turns the rule off."))
+            .config(CommandConfig::new("mentions", ArgOption::Numeric, "5")
+                .desc("how many users and roles can be mentioned in one message. 0 turns the rule off."))
+            .config(CommandConfig::new("caps", ArgOption::Numeric, "70")
+                .desc("percentage of upper case letters allowed in a message. 0 turns the rule off."))
+            .config(CommandConfig::new("invites", ArgOption::Boolean, "true")
+                .desc("blocks invites to other Discord servers."))
+            .config(CommandConfig::new("blocked-domains", ArgOption::Text, "")
+                .desc("domains that can't be linked, separated by spaces. Subdomains are blocked too."))
+            .config(CommandConfig::new("zalgo", ArgOption::Boolean, "true")
+                .desc("blocks zalgo text."))
+            .config(CommandConfig::new("mute-time", ArgOption::Time, "10m")
+                .desc("how long users breaking a rule with `mute` action are muted."))
+            .config(CommandConfig::new("exempt-roles", ArgOption::Text, "")
+                .desc("roles that are not checked, separated by spaces."))
+            .config(CommandConfig::new("exempt-channels", ArgOption::Text, "")
+                .desc("channels that are not checked, separated by spaces."));
+
+        for rule in Rule::all().iter() {
+            let default = match rule {
+                Rule::Spam | Rule::Mentions => "mute",
+                _ => "delete",
+            };
+            builder = builder.config(CommandConfig::new(&rule.action_config(), ArgOption::Any, default)
+                .desc(&format!("what happens when `{}` rule is broken. The message is always deleted.", rule.name()))
+                .values(&ACTIONS));
+        }
+
+        builder
+            .route(Route::new("shows every rule and what happens when it is broken."))
+            .handler(|ctx, msg, info, _| AutomodCommand.show_rules(ctx, msg, info))
+            .build()
+    }
+
+    fn show_rules(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let config = AutomodConfig::load(info);
+        let mut description = String::new();
+        for rule in Rule::all().iter() {
+            let limit = match rule {
+                Rule::Spam => format!("{} messages in {}", config.value("spam-messages"), config.value("spam-time")),
+                Rule::Duplicates => format!("{} same messages", config.value("duplicates")),
+                Rule::Mentions => format!("{} mentions", config.value("mentions")),
+                Rule::Caps => format!("{}% of caps", config.value("caps")),
+                Rule::Invites => config.value("invites").to_string(),
+                Rule::Domains => config.value("blocked-domains").to_string(),
+                Rule::Zalgo => config.value("zalgo").to_string(),
+            };
+            description.push_str(&format!("**{}** ({}) - {}\n", rule.name(), limit, config.value(&rule.action_config())));
+        }
+
+        if !config.enabled {
+            description.push_str("\n*Automod module is disabled.*");
+        }
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Automod", &description, EMBED_REGULAR_COLOR));
+        Ok(())
+    }
+
+    /// Checks the message against every rule. Returns true when it broke one of them.
+    /// Exempt messages should be skipped with `AutomodConfig::is_checked` beforehand.
+    pub fn run(&self, ctx: &Context, msg: &Message, info: &ServerInfo, config: &AutomodConfig) -> bool {
+        let guild_id = match msg.guild_id {
+            Some(g) => g.0,
+            None => return false
+        };
+
+        let rule = match self.broken_rule(msg, config, guild_id) {
+            Some(r) => r,
+            None => return false
+        };
+
+        let action = config.value(&rule.action_config());
+        if let Err(why) = self.punish(ctx, msg, info, config, rule, action) {
+            error!("Automod could not {} user {}. Reason: {}", action, msg.author.id, why);
+        }
+        true
+    }
+
+    fn broken_rule(&self, msg: &Message, config: &AutomodConfig, guild_id: u64) -> Option<Rule> {
+        let now = Utc::now();
+        let (recent, duplicates) = {
+            let mut state = STATE.lock().unwrap();
+            state.message_history.push(guild_id, msg.author.id.0, &msg.content, now);
+
+            let spam_time = config.duration("spam-time").unwrap_or_else(Duration::zero);
+            (
+                state.message_history.count_since(guild_id, msg.author.id.0, now - spam_time),
+                state.message_history.duplicates(guild_id, msg.author.id.0)
+            )
+        };
+
+        let spam_messages = config.limit("spam-messages");
+        if spam_messages > 0 && recent > spam_messages {
+            return Some(Rule::Spam)
+        }
+
+        let max_duplicates = config.limit("duplicates");
+        if max_duplicates > 0 && duplicates > max_duplicates {
+            return Some(Rule::Duplicates)
+        }
+
+        let max_mentions = config.limit("mentions");
+        let mentions = msg.mentions.len() + msg.mention_roles.len() + if msg.mention_everyone { 1 } else { 0 };
+        if max_mentions > 0 && mentions > max_mentions {
+            return Some(Rule::Mentions)
+        }
+
+        let max_caps = config.limit("caps");
+        if max_caps > 0 && caps_percent(&msg.content).map_or(false, |p| p > max_caps) {
+            return Some(Rule::Caps)
+        }
+
+        if config.value("invites") == "true" && has_invite(&msg.content) {
+            return Some(Rule::Invites)
+        }
+
+        let blocked: Vec<String> = config.value("blocked-domains")
+            .split_whitespace()
+            .map(|d| d.trim_start_matches("www.").to_lowercase())
+            .collect();
+        if blocked_domain(&msg.content, &blocked).is_some() {
+            return Some(Rule::Domains)
+        }
+
+        if config.value("zalgo") == "true" && is_zalgo(&msg.content) {
+            return Some(Rule::Zalgo)
+        }
+
+        None
+    }
+
+    fn punish(&self, ctx: &Context, msg: &Message, info: &ServerInfo, config: &AutomodConfig, rule: Rule, action: &str) -> Result<(), String> {
+        ctx.delete_message(msg.channel_id.0, msg.id.0)?;
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Automod",
+            &format!("<@{}>, your message broke the `{}` rule!", msg.author.id, rule.name()),
+            EMBED_REGULAR_COLOR
+        ));
+
+        let guild_id = msg.guild_id.unwrap().0;
+        let user_id = msg.author.id.0;
+        let bot_id = ctx.cache.read().user.id;
+        let reason = format!("Automod rule `{}`", rule.name());
+        let (kind, action_message) = match action {
+            // warns expire, notify the user and reach thresholds like the ones given by moderators
+            "warn" => {
+                let member = match GuildId(guild_id).member(ctx, msg.author.id) {
+                    Ok(m) => m,
+                    Err(_) => return Err("Could not find the member".to_string())
+                };
+                WarnCommand.warn_user(ctx, info, bot_id.0, &member, reason, None)?;
+                WarnCommand.check_thresholds(ctx, info, &member)?;
+                return Ok(())
+            },
+            "mute" => {
+                let role_id = match get_special_entity_by_type(info, SpecialEntityType::MuteRole) {
+                    Some(r) => r.entity_id.parse::<u64>().unwrap(),
+                    None => return Err("There is no muted role".to_string())
+                };
+                ctx.add_role(guild_id, user_id, role_id)?;
+
+                // new mute replaces the old one together with its end, mutes are permanent when the time is not set
+                delete_temp_operations(info, &user_id.to_string(), ActionType::Mute);
+                match config.duration("mute-time") {
+                    Some(time) => {
+                        create_temp_operation(info, user_id.to_string(), Utc::now() + time, ActionType::Mute);
+                        (ActionType::Mute, format!("User {} has been temp-muted for {}", msg.author.name, config.value("mute-time")))
+                    },
+                    None => (ActionType::Mute, format!("User {} has been muted", msg.author.name))
+                }
+            },
+            "kick" => {
+                ctx.kick(guild_id, user_id)?;
+                (ActionType::Kick, format!("User {} has been kicked out", msg.author.name))
+            },
+            _ => {
+                send_to_mod_logs(ctx, info, "Automod", &format!(
+                    "Deleted message of {} in <#{}>. Rule: **{}**",
+                    msg.author.name,
+                    msg.channel_id.0,
+                    rule.name()
+                ));
+                return Ok(())
+            }
+        };
+
+        let case = create_case(
+            info,
+            bot_id.to_string(),
+            Some(user_id.to_string()),
+            kind,
+            action_message,
+            Some(reason)
+        );
+        log_case(ctx, info, &case);
+        Ok(())
+    }
+}
+
+/// Automod settings of the server. They are resolved once per message, looking them up with `get_config`
+/// builds every command of every module.
+pub struct AutomodConfig {
+    pub enabled: bool,
+    values: HashMap<String, String>,
+}
+
+impl AutomodConfig {
+    pub fn load(info: &ServerInfo) -> AutomodConfig {
+        let module: &dyn BotModule = &AutomodModule;
+        let command = AutomodCommand::command();
+        let values = command.config()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|c| Some((c.name.to_owned(), command.config_value(info, &c.name)?)))
+            .collect();
+
+        AutomodConfig {
+            enabled: info.server.is_some() && module.enabled(info),
+            values
+        }
+    }
+
+    /// Messages are checked when automod is enabled and neither the channel nor the author's roles are exempt.
+    pub fn is_checked(&self, ctx: &Context, guild_id: u64, channel_id: u64, user_id: u64) -> bool {
+        if !self.enabled || parse_ids(self.value("exempt-channels")).contains(&channel_id) {
+            return false
+        }
+
+        let roles = GuildId(guild_id).to_guild_cached(&ctx.cache)
+            .and_then(|g| g.read().members.get(&UserId(user_id)).map(|m| m.roles.to_owned()))
+            .unwrap_or_default();
+        let exempt_roles = parse_ids(self.value("exempt-roles"));
+        !roles.iter().any(|r| exempt_roles.contains(&r.0))
+    }
+
+    pub fn value(&self, name: &str) -> &str {
+        self.values.get(name).map_or("", |v| v.as_str())
+    }
+
+    fn limit(&self, name: &str) -> usize {
+        self.value(name).parse::<f64>().map_or(0, |n| n.max(0.0) as usize)
+    }
+
+    // empty value means there is no duration
+    fn duration(&self, name: &str) -> Option<Duration> {
+        get_duration(self.value(name)).ok()
+    }
+}
@@ -0,0 +1,157 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR, get_command_name, resolve_command_name};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, GuildId, MessageId};
+use serenity::model::user::User;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, get_filtered_word, create_filtered_word, delete_filtered_word};
+use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::bot_modules::moderation::warn_command::WarnCommand;
+use crate::gateway::{DiscordGateway, Embed};
+use super::word_filter::{WordPattern, parse_pattern, redact};
+use log::error;
+
+pub struct FilterCommand;
+
+impl FilterCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("filter")
+            .desc("Words that can't be used on the server. Messages containing them are deleted and their authors warned. \
+                Words are matched ignoring case, leetspeak and look-alike characters.")
+            .perms(&["filter"])
+            .route(Route::new("adds the word to the filter. Regexes are written between slashes, e.g. `/b(a|4)d/`.")
+                .literal("add")
+                .arg("<word...>", ArgOption::Any))
+            .route(Route::new("removes the word from the filter.")
+                .literal("remove")
+                .arg("<word...>", ArgOption::Any))
+            .route(Route::new("shows every filtered word."))
+            .usage()
+            .handler(|ctx, msg, info, args| {
+                if args.has("add") {
+                    FilterCommand.add(ctx, msg, args, info)
+                } else if args.has("remove") {
+                    FilterCommand.remove(ctx, msg, args, info)
+                } else {
+                    FilterCommand.show(ctx, msg, info)
+                }
+            })
+            .build()
+    }
+
+    fn add(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let (pattern, is_regex) = parse_pattern(&args.get_rest(msg, "word").unwrap());
+        WordPattern::new(&pattern, is_regex)?;
+        if get_filtered_word(info, &pattern, is_regex).is_some() {
+            return Err("The word is already filtered!".to_string())
+        }
+
+        create_filtered_word(info, pattern.to_owned(), is_regex);
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Filter - Done!",
+            &format!("`{}` has been added to the filter.", display_pattern(&pattern, is_regex)),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+
+    fn remove(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let (pattern, is_regex) = parse_pattern(&args.get_rest(msg, "word").unwrap());
+        let word = match get_filtered_word(info, &pattern, is_regex) {
+            Some(w) => w,
+            None => return Err("The word is not filtered!".to_string())
+        };
+
+        delete_filtered_word(&word);
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Filter - Done!",
+            &format!("`{}` has been removed from the filter.", display_pattern(&pattern, is_regex)),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+
+    fn show(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let words = info.filtered_words.clone().unwrap_or_default();
+        if words.is_empty() {
+            return Err("There are no filtered words!".to_string())
+        }
+
+        let description = words.iter()
+            .map(|w| format!("`{}`", display_pattern(&w.pattern, w.is_regex)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Filtered words", &description, EMBED_REGULAR_COLOR));
+        Ok(())
+    }
+
+    /// Checks new and edited messages. Returns true when the message contained a filtered word.
+    /// Exempt messages should be skipped with `AutomodConfig::is_checked` beforehand.
+    pub fn run(&self, ctx: &Context, info: &ServerInfo, channel_id: ChannelId, message_id: MessageId, author: &User, content: &str) -> bool {
+        let guild_id = match &info.server {
+            Some(s) => s.guildid.parse::<u64>().unwrap(),
+            None => return false
+        };
+        if author.bot || self.is_filter_command(info, content) {
+            return false
+        }
+
+        // invalid regexes can't be added, so skipping them here is fine
+        let patterns: Vec<WordPattern> = info.filtered_words.iter()
+            .flatten()
+            .filter_map(|w| WordPattern::new(&w.pattern, w.is_regex).ok())
+            .collect();
+        if !patterns.iter().any(|p| p.matches(content)) {
+            return false
+        }
+
+        if let Err(why) = self.punish(ctx, info, guild_id, channel_id, message_id, author) {
+            error!("Word filter could not punish user {}. Reason: {}", author.id, why);
+        }
+        send_to_mod_logs(ctx, info, "Word filter", &format!(
+            "Deleted message of {} in <#{}>:\n{}",
+            author.name,
+            channel_id.0,
+            redact(content, &patterns)
+        ));
+        true
+    }
+
+    fn punish(&self, ctx: &Context, info: &ServerInfo, guild_id: u64, channel_id: ChannelId, message_id: MessageId, author: &User) -> Result<(), String> {
+        ctx.delete_message(channel_id.0, message_id.0)?;
+        let _ = ctx.send_embed(channel_id.0, &Embed::new(
+            "Word filter",
+            &format!("<@{}>, your message contained a filtered word!", author.id),
+            EMBED_REGULAR_COLOR
+        ));
+
+        let member = match GuildId(guild_id).member(ctx, author.id) {
+            Ok(m) => m,
+            Err(_) => return Err("Could not find the member".to_string())
+        };
+        let bot_id = ctx.cache.read().user.id.0;
+        WarnCommand.warn_user(ctx, info, bot_id, &member, "Used a filtered word".to_string(), None)?;
+        WarnCommand.check_thresholds(ctx, info, &member)?;
+        Ok(())
+    }
+
+    // moderators adding a word would be warned for it otherwise
+    fn is_filter_command(&self, info: &ServerInfo, content: &str) -> bool {
+        let prefix = match &info.server {
+            Some(s) => s.prefix.to_owned(),
+            None => return false
+        };
+
+        get_command_name(content, &prefix)
+            .and_then(|name| resolve_command_name(&name, info))
+            .map_or(false, |name| name == "filter")
+    }
+}
+
+fn display_pattern(pattern: &str, is_regex: bool) -> String {
+    if is_regex {
+        format!("/{}/", pattern)
+    } else {
+        pattern.to_string()
+    }
+}
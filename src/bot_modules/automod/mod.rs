@@ -0,0 +1,26 @@
+use super::BotModule;
+use crate::command::Command;
+
+pub mod automod_command;
+pub mod filter_command;
+pub mod rules;
+pub mod word_filter;
+
+pub struct AutomodModule;
+
+impl BotModule for AutomodModule {
+    fn name(&self) -> String {
+        String::from("automod")
+    }
+
+    fn desc(&self) -> String {
+        String::from("Automatic moderation of spam, mentions, caps, links, zalgo and filtered words.")
+    }
+
+    fn commands(&self) -> Vec<Box<dyn Command>> {
+        vec![
+            automod_command::AutomodCommand::command(),
+            filter_command::FilterCommand::command(),
+        ]
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+
+// messages older than this are not needed by any rule
+const HISTORY_TIME: i64 = 60;
+const HISTORY_SIZE: usize = 20;
+// caps in short messages like "OK" are fine
+const CAPS_MIN_LETTERS: usize = 10;
+const ZALGO_MARKS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rule {
+    Spam,
+    Duplicates,
+    Mentions,
+    Caps,
+    Invites,
+    Domains,
+    Zalgo,
+}
+
+impl Rule {
+    pub fn all() -> [Rule; 7] {
+        [Rule::Spam, Rule::Duplicates, Rule::Mentions, Rule::Caps, Rule::Invites, Rule::Domains, Rule::Zalgo]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::Spam => "spam",
+            Rule::Duplicates => "duplicates",
+            Rule::Mentions => "mentions",
+            Rule::Caps => "caps",
+            Rule::Invites => "invites",
+            Rule::Domains => "domains",
+            Rule::Zalgo => "zalgo",
+        }
+    }
+
+    /// Name of the `CommandConfig` with the action taken when the rule is broken, e.g. `spam-action`.
+    pub fn action_config(&self) -> String {
+        format!("{}-action", self.name())
+    }
+}
+
+/// Recent messages of every member. Key is guild id and user id.
+#[derive(Default)]
+pub struct MessageHistory {
+    messages: HashMap<(u64, u64), VecDeque<(DateTime<Utc>, String)>>,
+}
+
+impl MessageHistory {
+    pub fn push(&mut self, guild_id: u64, user_id: u64, content: &str, now: DateTime<Utc>) {
+        let oldest = now - Duration::seconds(HISTORY_TIME);
+        self.messages.retain(|_, m| m.back().map_or(false, |(date, _)| *date > oldest));
+
+        let messages = self.messages.entry((guild_id, user_id)).or_default();
+        while messages.front().map_or(false, |(date, _)| *date <= oldest) || messages.len() >= HISTORY_SIZE {
+            messages.pop_front();
+        }
+        messages.push_back((now, content.to_string()));
+    }
+
+    /// Number of messages sent after `since`.
+    pub fn count_since(&self, guild_id: u64, user_id: u64, since: DateTime<Utc>) -> usize {
+        self.messages.get(&(guild_id, user_id))
+            .map_or(0, |m| m.iter().filter(|(date, _)| *date > since).count())
+    }
+
+    /// How many times in a row the last message has been sent.
+    pub fn duplicates(&self, guild_id: u64, user_id: u64) -> usize {
+        let messages = match self.messages.get(&(guild_id, user_id)) {
+            Some(m) => m,
+            None => return 0
+        };
+        let last = match messages.back() {
+            Some((_, content)) => content.to_lowercase(),
+            None => return 0
+        };
+
+        messages.iter().rev().take_while(|(_, content)| content.to_lowercase() == last).count()
+    }
+}
+
+/// Percentage of upper case letters. `None` for messages too short to tell.
+pub fn caps_percent(content: &str) -> Option<usize> {
+    let letters: Vec<char> = content.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() < CAPS_MIN_LETTERS {
+        return None
+    }
+
+    let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+    Some(upper * 100 / letters.len())
+}
+
+pub fn has_invite(content: &str) -> bool {
+    let content = content.to_lowercase();
+    ["discord.gg/", "discord.com/invite/", "discordapp.com/invite/"].iter().any(|i| content.contains(i))
+}
+
+/// Hosts of every link in the message, e.g. `example.com` from `https://www.example.com/page`.
+pub fn link_hosts(content: &str) -> Vec<String> {
+    content.split_whitespace()
+        .filter_map(|word| {
+            let word = word.trim_start_matches('<').to_lowercase();
+            let rest = if word.starts_with("https://") {
+                &word[8..]
+            } else if word.starts_with("http://") {
+                &word[7..]
+            } else {
+                return None
+            };
+
+            let host = rest.split(|c| c == '/' || c == '?' || c == '#' || c == '>').next()?;
+            // user info and port are not a part of the host
+            let host = host.rsplit('@').next()?.split(':').next()?;
+            Some(host.trim_start_matches("www.").to_string())
+        })
+        .filter(|h| !h.is_empty())
+        .collect()
+}
+
+/// First domain from `blocked` linked in the message. Subdomains are blocked too.
+pub fn blocked_domain(content: &str, blocked: &[String]) -> Option<String> {
+    link_hosts(content).into_iter().find(|host| {
+        blocked.iter().any(|d| host == d || host.ends_with(&format!(".{}", d)))
+    })
+}
+
+/// Text with many combining marks stacked on a single character.
+pub fn is_zalgo(content: &str) -> bool {
+    let mut marks = 0;
+    for c in content.chars() {
+        if is_combining_mark(c) {
+            marks += 1;
+            if marks >= ZALGO_MARKS {
+                return true
+            }
+        } else {
+            marks = 0;
+        }
+    }
+    false
+}
+
+fn is_combining_mark(c: char) -> bool {
+    match c as u32 {
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F => true,
+        _ => false,
+    }
+}
+
+/// Ids from a list of mentions or ids, e.g. `<@&123> 456` gives `[123, 456]`.
+pub fn parse_ids(list: &str) -> Vec<u64> {
+    list.split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|id| id.trim_matches(|c: char| !c.is_ascii_digit()).parse::<u64>().ok())
+        .collect()
+}
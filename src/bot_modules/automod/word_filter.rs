@@ -0,0 +1,145 @@
+use regex::{Regex, RegexBuilder};
+
+// user regexes are compiled for every message, so they can't be huge
+const REGEX_SIZE_LIMIT: usize = 1 << 16;
+const REDACTED: &str = "***";
+
+/// Word or `/regex/` from the server's filter list.
+pub enum WordPattern {
+    Word(String),
+    Regex(Regex),
+}
+
+impl WordPattern {
+    pub fn new(pattern: &str, is_regex: bool) -> Result<WordPattern, String> {
+        if is_regex {
+            return RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .size_limit(REGEX_SIZE_LIMIT)
+                .build()
+                .map(WordPattern::Regex)
+                .map_err(|_| format!("`{}` is not a valid regex!", pattern))
+        }
+
+        let word = normalize(pattern);
+        if word.is_empty() {
+            return Err("The word has to contain letters or digits!".to_string())
+        }
+        Ok(WordPattern::Word(word))
+    }
+
+    /// Words match whole words of the normalized text. Regexes are checked against the original text too.
+    pub fn matches(&self, content: &str) -> bool {
+        match self {
+            WordPattern::Word(word) => format!(" {} ", normalize(content)).contains(&format!(" {} ", word)),
+            WordPattern::Regex(regex) => regex.is_match(content) || regex.is_match(&normalize(content)),
+        }
+    }
+}
+
+/// Splits the filter entry into the pattern and whether it's a regex, e.g. `/b(a|4)d/`.
+pub fn parse_pattern(input: &str) -> (String, bool) {
+    let input = input.trim();
+    if input.len() > 2 && input.starts_with('/') && input.ends_with('/') {
+        (input[1..input.len() - 1].to_string(), true)
+    } else {
+        (input.to_lowercase(), false)
+    }
+}
+
+/// Lower case text with leetspeak and look-alike characters replaced by plain letters.
+/// Everything that is not a letter or digit becomes a single space.
+pub fn normalize(content: &str) -> String {
+    let mut normalized = String::new();
+    for word in content.split_whitespace() {
+        // `bad!` is not `badi`
+        let word = word.trim_end_matches(|c| ".,!?:;".contains(c));
+        for c in word.chars().flat_map(char::to_lowercase) {
+            if is_invisible(c) {
+                continue;
+            }
+            let c = leet(confusable(c));
+            normalized.push(if c.is_alphanumeric() { c } else { ' ' });
+        }
+        normalized.push(' ');
+    }
+
+    normalized.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Message with every filtered word replaced by `***`. When a match can't be pinned to single words
+/// (e.g. the message is matched only after normalization), the whole message is redacted.
+pub fn redact(content: &str, patterns: &[WordPattern]) -> String {
+    let mut redacted = content.to_string();
+    for pattern in patterns.iter() {
+        redacted = match pattern {
+            WordPattern::Regex(regex) => regex.replace_all(&redacted, REDACTED).into_owned(),
+            WordPattern::Word(_) => redacted.split(' ')
+                .map(|token| if pattern.matches(token) { REDACTED } else { token })
+                .collect::<Vec<&str>>()
+                .join(" "),
+        };
+    }
+
+    if patterns.iter().any(|p| p.matches(&redacted)) {
+        return REDACTED.to_string()
+    }
+    redacted
+}
+
+fn leet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' | '|' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' => 't',
+        _ => c,
+    }
+}
+
+fn confusable(c: char) -> char {
+    match c {
+        // cyrillic and greek letters that look like latin ones
+        'а' | 'α' => 'a',
+        'в' | 'β' => 'b',
+        'с' | 'ϲ' => 'c',
+        'е' | 'ε' => 'e',
+        'н' => 'h',
+        'і' | 'ι' => 'i',
+        'ј' => 'j',
+        'к' | 'κ' => 'k',
+        'м' => 'm',
+        'η' => 'n',
+        'о' | 'ο' => 'o',
+        'р' | 'ρ' => 'p',
+        'ѕ' => 's',
+        'т' | 'τ' => 't',
+        'υ' => 'u',
+        'ν' => 'v',
+        'ш' | 'ω' => 'w',
+        'х' | 'χ' => 'x',
+        'у' | 'γ' => 'y',
+        // accented latin letters
+        'à'..='å' => 'a',
+        'ç' => 'c',
+        'è'..='ë' => 'e',
+        'ì'..='ï' => 'i',
+        'ñ' => 'n',
+        'ò'..='ö' | 'ø' => 'o',
+        'ù'..='ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        // fullwidth letters and digits
+        'ａ'..='ｚ' | '０'..='９' => std::char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Zero-width characters, soft hyphens and combining marks hidden inside words.
+fn is_invisible(c: char) -> bool {
+    match c as u32 {
+        0x00AD | 0x200B..=0x200D | 0x2060 | 0xFEFF | 0x0300..=0x036F => true,
+        _ => false,
+    }
+}
@@ -1,37 +1,21 @@
-use crate::command::{Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR};
+use crate::command::{Command, EMBED_REGULAR_COLOR};
+use crate::command::builder::CommandBuilder;
 use crate::config::VERSION;
 use serenity::model::channel::Message;
 use serenity::prelude::Context;
-use crate::utils::db::ServerInfo;
 
 pub struct AboutCommand;
 
-impl Command for AboutCommand {
-    fn name(&self) -> String {
-        String::from("about")
+impl AboutCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("about")
+            .desc("Shows information about this bot.")
+            .use_in_dm(true)
+            .handler(|ctx, msg, _, _| AboutCommand.about(ctx, msg))
+            .build()
     }
 
-    fn desc(&self) -> String {
-        String::from("Shows information about this bot.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        true
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        None
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        None
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, _: &ServerInfo) -> Result<(), String> {
+    fn about(&self, ctx: &Context, msg: &Message) -> Result<(), String> {
         let _ = msg.channel_id.send_message(&ctx.http, |m| {
             m.embed(|e| {
                 e.title("About");
@@ -0,0 +1,110 @@
+use crate::command::{ArgOption, Command, EMBED_REGULAR_COLOR, resolve_command_name};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, create_db_alias, delete_db_alias, get_tag};
+
+pub struct AliasCommand;
+
+impl AliasCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("alias")
+            .desc("Custom command aliases. Alias runs provided command with the same permissions and restrictions.")
+            .perms(&["alias"])
+            .route(Route::new("creates alias for provided command.")
+                .literal("add")
+                .arg("<alias>", ArgOption::Text)
+                .arg("<command>", ArgOption::Text))
+            .route(Route::new("removes alias.")
+                .literal("remove")
+                .arg("<alias>", ArgOption::Text))
+            .route(Route::new("shows every alias created on this server.")
+                .literal("list"))
+            .usage()
+            .handler(|ctx, msg, info, args| match args.first() {
+                "add" => AliasCommand.add_alias(
+                    ctx,
+                    msg,
+                    info,
+                    args.get_str("alias").unwrap(),
+                    args.get_str("command").unwrap()
+                ),
+                "remove" => AliasCommand.remove_alias(ctx, msg, info, args.get_str("alias").unwrap()),
+                _ => AliasCommand.list_aliases(ctx, msg, info),
+            })
+            .build()
+    }
+
+    fn add_alias(&self, ctx: &Context, msg: &Message, info: &ServerInfo, alias: String, cmd_name: String) -> Result<(), String> {
+        let alias = alias.to_lowercase();
+        if let Some(used_by) = resolve_command_name(&alias, info) {
+            return Err(format!("`{}` is already used by `{}` command!", alias, used_by))
+        }
+        if get_tag(info, &alias).is_some() {
+            return Err(format!("`{}` is already used by a tag!", alias))
+        }
+
+        let cmd_name = match resolve_command_name(&cmd_name.to_lowercase(), info) {
+            Some(name) => name,
+            None => return Err("Command does not exist!".to_string())
+        };
+
+        create_db_alias(info, alias.to_owned(), cmd_name.to_owned());
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Alias has been created!");
+                e.description(format!("`{}` will now run `{}` command.", alias, cmd_name));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+
+        Ok(())
+    }
+
+    fn remove_alias(&self, ctx: &Context, msg: &Message, info: &ServerInfo, alias: String) -> Result<(), String> {
+        let alias = alias.to_lowercase();
+        if !delete_db_alias(info, &alias) {
+            return Err(format!("Alias `{}` does not exist!", alias))
+        }
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Alias has been removed!");
+                e.description(format!("Removed `{}` alias.", alias));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+
+        Ok(())
+    }
+
+    fn list_aliases(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let mut aliases_message = String::new();
+        if let Some(aliases) = &info.aliases {
+            for a in aliases.iter() {
+                aliases_message.push_str(&format!("**{}** -> {}\n", a.alias, a.command_name));
+            }
+        }
+
+        if aliases_message.is_empty() {
+            aliases_message = String::from("This server does not have any aliases.");
+        }
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Aliases");
+                e.description(aliases_message);
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use crate::command::{get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR, find_command, is_command_protected};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR, find_command, is_command_protected, resolve_command_name};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::command::cooldown::{CooldownBucket, MAX_COOLDOWN};
 use crate::database::get_db_con;
 use crate::database::models::*;
 use crate::database::schema::commands::disabled_channels;
@@ -6,24 +8,63 @@ use crate::database::schema::*;
 use diesel::prelude::*;
 use serenity::model::channel::{Message, ChannelType};
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, get_db_command_by_name};
-use crate::utils::object_finding::get_channel_from_id;
+use crate::utils::db::{ServerInfo, get_db_command_by_name, set_command_config, get_tag};
+use crate::utils::object_finding::get_channel_from_arg;
 
 pub struct CmdCommand;
 
 impl CmdCommand {
-   fn change_command(&self, ctx: &Context, msg: &Message, args: Vec<String>, info: &ServerInfo, is_channel: bool) -> Result<(), String> {
-       find_command(&args[0], info)?;
-       if is_command_protected(&args[0])? {
-           return Err("Command is protected. It can't be modified!".to_string())
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("command")
+            .desc("Managing tool for commands.")
+            .aliases(&["cmd"])
+            .perms(&["command"])
+            .route(Route::new("sets cooldown in seconds for provided bucket. Use 0 to turn it off.")
+                .arg("<command name>", ArgOption::Any)
+                .literal("cooldown")
+                .arg("<user/channel/guild>", ArgOption::Text)
+                .arg("<seconds>", ArgOption::Numeric))
+            .route(Route::new("allows you to enable/disable command for provided channel. \
+            If you want to enable/disable command for every channel then use`every-channel` in `<channel>`.")
+                .arg("<command name>", ArgOption::Any)
+                .arg("<enable/disable>", ArgOption::Boolean)
+                .arg("<channel>", ArgOption::Channel))
+            .route(Route::new("allows you to enable/disable command or tag for this channel.")
+                .arg("<command name>", ArgOption::Any)
+                .arg("<enable/disable>", ArgOption::Boolean))
+            .route(Route::new("shows information about provided command or tag.")
+                .arg("<command name>", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| {
+                if args.has("cooldown") {
+                    CmdCommand.set_cooldown(ctx, msg, args, info)
+                } else if args.has("enable/disable") {
+                    CmdCommand.change_command(ctx, msg, args, info)
+                } else {
+                    CmdCommand.get_cmd_info(ctx, msg, args.get_str("command name").unwrap(), info)
+                }
+            })
+            .build()
+    }
+
+   fn change_command(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+       let cmd_name = args.get_str("command name").unwrap();
+       let cmd_name = resolve_command_name(&cmd_name, info).unwrap_or(cmd_name);
+       let enable = args.get_bool("enable/disable").unwrap();
+       // tags can be disabled like any other command
+       if get_tag(info, &cmd_name).is_none() {
+           find_command(&cmd_name, info)?;
+           if is_command_protected(&cmd_name)? {
+               return Err("Command is protected. It can't be modified!".to_string())
+           }
        }
 
        // If channel is empty then enable/disable for every channel
-       let channel = if is_channel {
-           if args[2] == "every-channel" {
+       let channel = if let Some(ch) = args.get_str("channel") {
+           if ch == "every-channel" {
                String::new()
            } else {
-               match get_channel_from_id(ctx, msg, get_args(msg.to_owned(), true), 3)? {
+               match get_channel_from_arg(ctx, msg, &args, "channel")? {
                    Some(ch) => ch.id.0.to_string(),
                    None => return Ok(())
                }
@@ -32,7 +73,7 @@ impl CmdCommand {
            msg.channel_id.0.to_string()
        };
 
-       let mut cmd = match get_db_command_by_name(info, args[0].to_string()) {
+       let mut cmd = match get_db_command_by_name(info, cmd_name.to_owned()) {
            Some(c) => c,
            None => return Err("Could not find command in the database!".to_string())
        };
@@ -40,7 +81,7 @@ impl CmdCommand {
        if channel.is_empty() {
            match ctx.http.get_channels(msg.guild_id.unwrap().0) {
                Ok(ch) => {
-                   if args[1] == "disable" {
+                   if !enable {
                        cmd.disabled_channels = ch.iter()
                            .filter(|c| c.kind == ChannelType::Text)
                            .map(|c| c.id.to_string())
@@ -52,9 +93,9 @@ impl CmdCommand {
                Err(_) => return Err("Could not retrieve guild channels!".to_string())
            };
        } else {
-           if args[1] == "disable" && !cmd.disabled_channels.contains(&channel) {
+           if !enable && !cmd.disabled_channels.contains(&channel) {
                cmd.disabled_channels.push(channel)
-           } else if args[1] == "enable" {
+           } else if enable {
                for (i, c) in cmd.disabled_channels.iter().enumerate() {
                    if c == &channel {
                        cmd.disabled_channels.remove(i);
@@ -72,7 +113,7 @@ impl CmdCommand {
        let _ = msg.channel_id.send_message(&ctx.http, |m| {
            m.embed(|e| {
                e.title("Commands management");
-               e.description(format!("Command **{}** has been {}d", args[0], args[1]));
+               e.description(format!("Command **{}** has been {}", cmd_name, if enable { "enabled" } else { "disabled" }));
                e.color(EMBED_REGULAR_COLOR);
                e
            });
@@ -81,11 +122,45 @@ impl CmdCommand {
        Ok(())
    }
 
-    fn get_cmd_info(&self, ctx: &Context, msg: &Message, cmd_name: String, info: &ServerInfo) -> Result<(), String> {
+    fn set_cooldown(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let cmd_name = args.get_str("command name").unwrap();
+        let cmd_name = resolve_command_name(&cmd_name, info).unwrap_or(cmd_name);
         find_command(&cmd_name, info)?;
         if is_command_protected(&cmd_name)? {
-            return Err("Command is protected. Enabled in every channel by default!".to_string())
+            return Err("Command is protected. It can't be modified!".to_string())
         }
+
+        let bucket = CooldownBucket::from_name(&args.get_str("user/channel/guild").unwrap()).unwrap();
+        let seconds = args.get_num("seconds").unwrap();
+        if seconds < 0.0 || seconds.fract() != 0.0 || seconds > MAX_COOLDOWN as f64 {
+            return Err(format!("Cooldown has to be a whole number of seconds, at most {}!", MAX_COOLDOWN))
+        }
+
+        set_command_config(info, &cmd_name, &bucket.config_name(), (seconds as i64).to_string());
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Commands management");
+                e.description(format!("Command **{}** now has {}s {} cooldown", cmd_name, seconds as i64, bucket.name()));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn get_cmd_info(&self, ctx: &Context, msg: &Message, cmd_name: String, info: &ServerInfo) -> Result<(), String> {
+        let cmd_name = resolve_command_name(&cmd_name, info).unwrap_or(cmd_name);
+        let command = if get_tag(info, &cmd_name).is_some() {
+            None
+        } else {
+            let command = find_command(&cmd_name, info)?;
+            if is_command_protected(&cmd_name)? {
+                return Err("Command is protected. Enabled in every channel by default!".to_string())
+            }
+            Some(command)
+        };
         let cmd = match get_db_command_by_name(info, cmd_name) {
             Some(c) => c,
             None => return Err("Could not get this command from the database".to_string())
@@ -94,10 +169,22 @@ impl CmdCommand {
         let mut channels_message = String::new();
         cmd.disabled_channels.iter().for_each(|c| channels_message.push_str(&format!("- <#{}>\n", c)));
 
+        let mut cooldowns_message = String::new();
+        if let Some(command) = command {
+            command.active_cooldowns(info).iter().for_each(|c| cooldowns_message.push_str(&format!("- {}: {}s\n", c.bucket.name(), c.seconds)));
+        }
+        if cooldowns_message.is_empty() {
+            cooldowns_message = String::from("No cooldowns.\n");
+        }
+
         let _ = msg.channel_id.send_message(&ctx.http, |m| {
            m.embed(|e| {
                e.title(format!("{} details", cmd.command_name));
-               e.description(format!("Command is disabled in those channels:\n{}", channels_message));
+               e.description(format!(
+                   "Command is disabled in those channels:\n{}\n**Cooldowns:**\n{}",
+                   channels_message,
+                   cooldowns_message
+               ));
                e.color(EMBED_REGULAR_COLOR);
                e
            });
@@ -106,91 +193,3 @@ impl CmdCommand {
         Ok(())
     }
 }
-
-impl Command for CmdCommand {
-    fn name(&self) -> String {
-        String::from("command")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Managing tool for commands.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: String::from("<command name>"),
-                desc: Some(String::from("allows you to enable/disable command for provided channel. \
-                If you want to enable/disable command for every channel then use`every-channel` in `<channel>`.")),
-                option: Some(ArgOption::Any),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("<enable/disable>"),
-                    desc: None,
-                    option: Some(ArgOption::Text),
-                    next: Some(Box::new(CommandArg {
-                        name: String::from("<channel>"),
-                        desc: None,
-                        option: Some(ArgOption::Channel),
-                        next: None,
-                    })),
-                })),
-            },
-            CommandArg {
-                name: String::from("<command name>"),
-                desc: Some(String::from("allows you to enable/disable command for this channel.")),
-                option: Some(ArgOption::Any),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("<enable/disable>"),
-                    desc: None,
-                    option: Some(ArgOption::Text),
-                    next: None,
-                })),
-            },
-            CommandArg {
-                name: String::from("<command name>"),
-                desc: Some(String::from("shows information about provided command.")),
-                option: Some(ArgOption::Any),
-                next: None,
-            },
-            CommandArg {
-                name: String::from(""),
-                desc: Some(String::from("shows usage information.")),
-                option: None,
-                next: None,
-            },
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["command".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => match routes {
-                Some(path) => {
-                    match path.len() {
-                        2 => self.change_command(ctx, msg, args, info, false),
-                        3 => self.change_command(ctx, msg, args, info, true),
-                        _ => self.get_cmd_info(ctx, msg, args[0].to_owned(), info)
-                    }
-                }
-                None => {
-                    let help_cmd = super::help_command::HelpCommand{};
-                    help_cmd.show_cmd_details(ctx, msg, info, self.name())
-                },
-            },
-            Err(why) => return Err(why),
-        }
-    }
-}
-
@@ -0,0 +1,150 @@
+use crate::command::{ArgOption, Command, CommandConfig, ParsedArgs, EMBED_REGULAR_COLOR, find_command, is_command_protected, resolve_command_name};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, set_command_config, delete_command_config};
+
+pub struct ConfigCommand;
+
+impl ConfigCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("config")
+            .desc("Settings of commands for this server.")
+            .perms(&["config"])
+            .route(Route::new("changes the setting. Use `reset` as a value to bring back the default one.")
+                .arg("<command>", ArgOption::Any)
+                .arg("<key>", ArgOption::Any)
+                .arg("<value...>", ArgOption::Any))
+            .route(Route::new("shows details about the setting.")
+                .arg("<command>", ArgOption::Any)
+                .arg("<key>", ArgOption::Any))
+            .route(Route::new("shows every setting of the command.")
+                .arg("<command>", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| ConfigCommand.configure(ctx, msg, info, args))
+            .build()
+    }
+
+    fn configure(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let cmd_name = args.get_str("command").unwrap().to_lowercase();
+        let cmd_name = resolve_command_name(&cmd_name, info).unwrap_or(cmd_name);
+        let command = find_command(&cmd_name, info)?;
+
+        // every setting with its current value
+        let configs: Vec<(CommandConfig, String)> = command.config()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| {
+                let value = command.config_value(info, &c.name).unwrap_or_default();
+                (c, value)
+            })
+            .collect();
+
+        let key = match args.get_str("key") {
+            Some(k) => k.to_lowercase(),
+            None => return self.show_configs(ctx, msg, &cmd_name, &configs),
+        };
+
+        let (config, value) = match configs.iter().find(|(c, _)| c.name == key) {
+            Some(c) => c,
+            None => return Err(format!("Command `{}` does not have `{}` setting!", cmd_name, key))
+        };
+
+        match args.get_rest(msg, "value") {
+            Some(new_value) => self.set_config(ctx, msg, info, &cmd_name, config, &new_value),
+            None => self.show_config(ctx, msg, &cmd_name, config, value),
+        }
+    }
+
+    fn set_config(&self, ctx: &Context, msg: &Message, info: &ServerInfo, cmd_name: &str, config: &CommandConfig, value: &str) -> Result<(), String> {
+        if is_command_protected(cmd_name)? {
+            return Err("Command is protected. It can't be modified!".to_string())
+        }
+
+        let description = if value == "reset" {
+            delete_command_config(info, cmd_name, &config.name);
+            format!("`{}` of **{}** has been reset to {}", config.name, cmd_name, display_value(&config.default))
+        } else {
+            let value = config.parse(value)?;
+            set_command_config(info, cmd_name, &config.name, value.to_owned());
+            format!("`{}` of **{}** has been set to {}", config.name, cmd_name, display_value(&value))
+        };
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Config");
+                e.description(description);
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn show_config(&self, ctx: &Context, msg: &Message, cmd_name: &str, config: &CommandConfig, value: &str) -> Result<(), String> {
+        let allowed_values = if config.values.is_empty() {
+            option_name(&config.option).to_string()
+        } else {
+            config.values.join(", ")
+        };
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(format!("Config - {} {}", cmd_name, config.name));
+                e.description(format!(
+                    "**Description:** {}\n\
+                     **Allowed values:** {}\n\
+                     **Default:** {}\n\
+                     **Current:** {}",
+                    config.desc,
+                    allowed_values,
+                    display_value(&config.default),
+                    display_value(value)
+                ));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn show_configs(&self, ctx: &Context, msg: &Message, cmd_name: &str, configs: &Vec<(CommandConfig, String)>) -> Result<(), String> {
+        let mut configs_message = String::new();
+        for (c, value) in configs.iter() {
+            configs_message.push_str(&format!("**{}** = {} - {}\n", c.name, display_value(value), c.desc));
+        }
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(format!("Config - {}", cmd_name));
+                e.description(configs_message);
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+}
+
+fn display_value(value: &str) -> String {
+    if value.is_empty() {
+        String::from("*not set*")
+    } else {
+        format!("`{}`", value)
+    }
+}
+
+fn option_name(option: &ArgOption) -> &str {
+    match option {
+        ArgOption::Numeric => "number",
+        ArgOption::Boolean => "yes/no",
+        ArgOption::Role => "role",
+        ArgOption::Channel | ArgOption::ChannelId => "channel",
+        ArgOption::User | ArgOption::UserId => "user",
+        ArgOption::Time => "time, e.g. `30m` or `2d`",
+        ArgOption::Text | ArgOption::Any => "text",
+    }
+}
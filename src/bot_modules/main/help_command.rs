@@ -1,7 +1,6 @@
 use crate::bot_modules::get_modules;
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR, resolve_command_name};
+use crate::command::builder::{CommandBuilder, Route};
 use crate::config::{DEFAULT_PREFIX, DEV_MODULE};
 use serenity::model::channel::Message;
 use serenity::prelude::Context;
@@ -12,6 +11,38 @@ use crate::utils::db::ServerInfo;
 pub struct HelpCommand;
 
 impl HelpCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("help")
+            .desc("Shows this help message.")
+            .use_in_dm(true)
+            .route(Route::new("shows commands from enabled and disabled modules.")
+                .literal("all"))
+            .route(Route::new("shows commands from enabled and disabled modules for given page.")
+                .literal("all")
+                .arg("<page>", ArgOption::Numeric))
+            .route(Route::new("shows 10 first commands of given page.")
+                .arg("<page>", ArgOption::Numeric))
+            .route(Route::new("shows details about command.")
+                .arg("<command>", ArgOption::Any))
+            .route(Route::new("shows enabled commands from enabled modules."))
+            .handler(|ctx, msg, info, args| HelpCommand.help(ctx, msg, info, args))
+            .build()
+    }
+
+    fn help(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let all = args.first() == "all";
+        if let Some(page) = args.get_str("page") {
+            match page.parse::<usize>() {
+                Ok(p) => self.show_help(ctx, msg, info, all, p),
+                Err(_) => Err(String::from("Invalid page number!")),
+            }
+        } else if let Some(cmd_name) = args.get_str("command") {
+            self.show_cmd_details(ctx, msg, info, cmd_name)
+        } else {
+            self.show_help(ctx, msg, info, all, 1)
+        }
+    }
+
     fn show_help(&self, ctx: &Context, msg: &Message, info: &ServerInfo, all: bool, page: usize) -> Result<(), String> {
         if page == 0 {
             return Err(String::from("Page does not exist!"));
@@ -99,6 +130,7 @@ impl HelpCommand {
         } else {
             DEFAULT_PREFIX.to_string()
         };
+        let cmd_name = resolve_command_name(&cmd_name.to_lowercase(), info).unwrap_or(cmd_name);
 
         for m in get_modules() {
             if m.name() == DEV_MODULE && !check_if_dev(msg) {
@@ -106,24 +138,23 @@ impl HelpCommand {
             }
             for c in m.commands() {
                 if c.name() == cmd_name {
+                    let mut aliases = c.aliases();
+                    if let Some(server_aliases) = &info.aliases {
+                        server_aliases.iter()
+                            .filter(|a| a.command_name == cmd_name)
+                            .for_each(|a| aliases.push(a.alias.to_owned()));
+                    }
+                    let aliases_message = if aliases.is_empty() {
+                        String::new()
+                    } else {
+                        format!("**Aliases:** {}\n", aliases.join(", "))
+                    };
+
                     let mut args_message = String::new();
                     if let Some(_args) = c.args() {
                         args_message = String::from("**Arguments:**\n");
                         for a in c.args().unwrap().iter() {
-                            let mut next_arg = a.next.as_ref();
-                            let mut options_message = a.name.to_owned();
-                            while next_arg.is_some() {
-                                let na = next_arg.unwrap();
-                                options_message.push_str(&format!(" {}", na.name));
-                                next_arg = na.next.as_ref();
-                            }
-                            args_message.push_str(&format!(
-                                "**{}{} {}** - {}\n",
-                                prefix,
-                                c.name(),
-                                options_message,
-                                a.desc.as_ref().unwrap_or(&String::new())
-                            ));
+                            args_message.push_str(&a.help_line(&prefix, &c.name()));
                         }
                     }
 
@@ -143,12 +174,14 @@ impl HelpCommand {
                             e.title("Help - Command details");
                             e.description(format!(
                                 "**Name: ** {}\n\
+                                 {}\
                                  **Description:** {}\n\
                                  **Can be used in DM:** {}\n\n\
                                  {}\n\
                                  {}\
                                  ",
                                 c.name(),
+                                aliases_message,
                                 c.desc(),
                                 c.use_in_dm(),
                                 args_message,
@@ -166,109 +199,3 @@ impl HelpCommand {
         Err(String::from("Command not found!"))
     }
 }
-
-impl Command for HelpCommand {
-    fn name(&self) -> String {
-        String::from("help")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Shows this help message.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        true
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: String::from("all"),
-                desc: Some(String::from(
-                    "shows commands from enabled and disabled modules.",
-                )),
-                option: Some(ArgOption::Numeric),
-                next: None,
-            },
-            CommandArg {
-                name: String::from("all"),
-                desc: Some(String::from(
-                    "shows commands from enabled and disabled modules for given page.",
-                )),
-                option: Some(ArgOption::Numeric),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("<page>"),
-                    desc: None,
-                    option: Some(ArgOption::Numeric),
-                    next: None,
-                })),
-            },
-            CommandArg {
-                name: String::from("<page>"),
-                desc: Some(String::from("shows 10 first commands of given page.")),
-                option: Some(ArgOption::Numeric),
-                next: None,
-            },
-            CommandArg {
-                name: String::from("<command>"),
-                desc: Some(String::from("shows details about command.")),
-                option: Some(ArgOption::Any),
-                next: None,
-            },
-            CommandArg {
-                name: String::from(""),
-                desc: Some(String::from("shows enabled commands from enabled modules.")),
-                option: None,
-                next: None,
-            },
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        None
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => match routes {
-                Some(path) => {
-                    match path.len() {
-                        1 => {
-                            if path[0].name == "all" {
-                                return self.show_help(ctx, msg, info, true, 1);
-                            } else {
-                                match args[0].parse::<usize>() {
-                                    Ok(p) => {
-                                        return self.show_help(ctx, msg, info, false, p)
-                                    }
-                                    Err(_) => {
-                                        return self.show_cmd_details(ctx, msg, info, args[0].to_owned(),)
-                                    }
-                                }
-                            }
-                        }
-                        2 => {
-                            if path[0].name == "all" {
-                                match args[1].parse::<usize>() {
-                                    Ok(p) => {
-                                        return self.show_help(ctx, msg, info, true, p)
-                                    }
-                                    Err(_) => return Err(String::from("Invalid page number!")),
-                                }
-                            }
-                        }
-                        _ => return Err(String::from("Too many args!")),
-                    }
-                }
-                None => return self.show_help(ctx, msg, info, false, 1),
-            },
-            Err(why) => return Err(why),
-        }
-        Ok(())
-    }
-}
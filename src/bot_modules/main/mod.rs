@@ -1,5 +1,7 @@
 mod about_command;
+mod alias_command;
 mod cmd_command;
+mod config_command;
 pub mod help_command;
 mod modules_command;
 mod perms_command;
@@ -22,13 +24,15 @@ impl BotModule for MainModule {
 
     fn commands(&self) -> Vec<Box<dyn Command>> {
         vec![
-            Box::new(help_command::HelpCommand {}),
-            Box::new(about_command::AboutCommand {}),
-            Box::new(modules_command::ModulesCommand {}),
-            Box::new(prefix_command::PrefixCommand {}),
-            Box::new(perms_command::PermsCommand {}),
-            Box::new(cmd_command::CmdCommand {}),
-            Box::new(setup_command::SetupCommand {}),
+            help_command::HelpCommand::command(),
+            about_command::AboutCommand::command(),
+            modules_command::ModulesCommand::command(),
+            prefix_command::PrefixCommand::command(),
+            perms_command::PermsCommand::command(),
+            cmd_command::CmdCommand::command(),
+            alias_command::AliasCommand::command(),
+            config_command::ConfigCommand::command(),
+            setup_command::SetupCommand::command(),
         ]
     }
 }
@@ -1,7 +1,6 @@
 use super::super::*;
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use crate::config::DEV_MODULE;
 use crate::database::get_db_con;
 use crate::database::models::*;
@@ -15,6 +14,39 @@ use serenity::prelude::Context;
 pub struct ModulesCommand;
 
 impl ModulesCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("modules")
+            .desc("Managing tool for modules.")
+            .perms(&["modules"])
+            .route(Route::new("shows every command available in provided module.")
+                .arg("<module name>", ArgOption::Any)
+                .literal("commands"))
+            .route(Route::new("allows you to enable/disable module.")
+                .arg("<module name>", ArgOption::Any)
+                .arg("<enable/disable>", ArgOption::Boolean))
+            .route(Route::new("shows information about provided module.")
+                .arg("<module name>", ArgOption::Any))
+            .route(Route::new("shows information about every module."))
+            .handler(|ctx, msg, info, args| ModulesCommand.modules(ctx, msg, info, args))
+            .build()
+    }
+
+    fn modules(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let module_name = match args.get_str("module name") {
+            Some(m) => m,
+            None => return self.show_modules(ctx, msg),
+        };
+
+        if args.has("commands") {
+            let server = info.server.as_ref().unwrap();
+            self.module_commands(ctx, msg, &module_name, &server.prefix)
+        } else if args.has("enable/disable") {
+            self.enable_module(ctx, msg, &args, info)
+        } else {
+            self.show_module_details(ctx, msg, &module_name, info)
+        }
+    }
+
     fn show_modules(&self, ctx: &Context, msg: &Message) -> Result<(), String> {
         let mut modules_str = String::new();
         for m in get_modules().iter() {
@@ -39,13 +71,13 @@ impl ModulesCommand {
         Ok(())
     }
 
-    fn show_module_details(&self, ctx: &Context, msg: &Message, args: &Vec<String>, info: &ServerInfo) -> Result<(), String> {
-        if args[0] == DEV_MODULE && !check_if_dev(msg) {
+    fn show_module_details(&self, ctx: &Context, msg: &Message, module_name: &str, info: &ServerInfo) -> Result<(), String> {
+        if module_name == DEV_MODULE && !check_if_dev(msg) {
             return Err(String::from(
                 "This module is available only for developers!",
             ));
         }
-        let module = find_module(&args[0])?;
+        let module = find_module(module_name)?;
         let _ = msg.channel_id.send_message(&ctx.http, |m| {
             m.embed(|e| {
                 e.title(format!("Module - {}", module.name()));
@@ -67,13 +99,13 @@ impl ModulesCommand {
         Ok(())
     }
 
-    fn module_commands(&self, ctx: &Context, msg: &Message, args: &Vec<String>, prefix: &str) -> Result<(), String> {
-        if args[0] == DEV_MODULE && !check_if_dev(msg) {
+    fn module_commands(&self, ctx: &Context, msg: &Message, module_name: &str, prefix: &str) -> Result<(), String> {
+        if module_name == DEV_MODULE && !check_if_dev(msg) {
             return Err(String::from(
                 "This module is available only for developers!",
             ));
         }
-        let module = find_module(&args[0])?;
+        let module = find_module(module_name)?;
         let mut commands_str = String::new();
         for m in module.commands().iter() {
             commands_str += &format!("**{}{}** - {}\n", prefix, m.name(), m.desc());
@@ -91,14 +123,16 @@ impl ModulesCommand {
         Ok(())
     }
 
-    fn enable_module(&self, ctx: &Context, msg: &Message, args: &Vec<String>, info: &ServerInfo)-> Result<(), String> {
-        if args[0] == DEV_MODULE && !check_if_dev(msg) {
+    fn enable_module(&self, ctx: &Context, msg: &Message, args: &ParsedArgs, info: &ServerInfo)-> Result<(), String> {
+        let module_name = args.get_str("module name").unwrap();
+        let enable = args.get_bool("enable/disable").unwrap();
+        if module_name == DEV_MODULE && !check_if_dev(msg) {
             return Err(String::from(
                 "This module is available only for developers!",
             ));
         }
-        let _ = find_module(&args[0])?;
-        if PROTECTED_MODULES.contains(&args[0].as_str()) {
+        let _ = find_module(&module_name)?;
+        if PROTECTED_MODULES.contains(&module_name.as_str()) {
             return Err(String::from(
                 "This module is protected. It means that it can't be enabled or disabled.",
             ));
@@ -106,11 +140,11 @@ impl ModulesCommand {
 
         let db = get_db_con().get().expect("Could not get db pool!");
         let mut server = info.server.clone().unwrap();
-        if args[1] == "enable" && !server.enabledmodules.contains(&args[0]) {
-            server.enabledmodules.push(args[0].to_owned())
-        } else if args[1] == "disable" {
+        if enable && !server.enabledmodules.contains(&module_name) {
+            server.enabledmodules.push(module_name.to_owned())
+        } else if !enable {
             for (i, m) in server.enabledmodules.iter().enumerate() {
-                if m == &args[0] {
+                if m == &module_name {
                     server.enabledmodules.remove(i);
                     break;
                 }
@@ -125,7 +159,7 @@ impl ModulesCommand {
         let _ = msg.channel_id.send_message(&ctx.http, |m| {
             m.embed(|e| {
                 e.title("Module management");
-                e.description(format!("Module **{}** has been {}d", args[0], args[1]));
+                e.description(format!("Module **{}** has been {}", module_name, if enable { "enabled" } else { "disabled" }));
                 e.color(EMBED_REGULAR_COLOR);
                 e
             });
@@ -134,89 +168,3 @@ impl ModulesCommand {
         Ok(())
     }
 }
-
-impl Command for ModulesCommand {
-    fn name(&self) -> String {
-        String::from("modules")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Managing tool for modules.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: String::from("<module name>"),
-                desc: Some(String::from(
-                    "shows every command available in provided module.",
-                )),
-                option: Some(ArgOption::Any),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("commands"),
-                    desc: None,
-                    option: None,
-                    next: None,
-                })),
-            },
-            CommandArg {
-                name: String::from("<module name>"),
-                desc: Some(String::from("allows you to enable/disable module.")),
-                option: Some(ArgOption::Any),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("<enable/disable>"),
-                    desc: None,
-                    option: Some(ArgOption::Text),
-                    next: None,
-                })),
-            },
-            CommandArg {
-                name: String::from("<module name>"),
-                desc: Some(String::from("shows information about provided module.")),
-                option: Some(ArgOption::Any),
-                next: None,
-            },
-            CommandArg {
-                name: String::from(""),
-                desc: Some(String::from("shows information about every module.")),
-                option: None,
-                next: None,
-            },
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["modules".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => match routes {
-                Some(path) => {
-                    match path.len() {
-                        1 => return self.show_module_details(ctx, msg, &args, info),
-                        2 => {
-                            if args[1] == "commands" {
-                                let server = info.server.as_ref().unwrap();
-                                return self.module_commands(ctx, msg, &args, &server.prefix);
-                            }
-                            return self.enable_module(ctx, msg, &args, info);
-                        }
-                        _ => return Err(String::from("Too many args!")),
-                    }
-                }
-                None => return self.show_modules(ctx, msg),
-            },
-            Err(why) => return Err(why),
-        }
-    }
-}
@@ -1,6 +1,5 @@
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use crate::database::get_db_con;
 use crate::database::models::Role;
 use crate::database::schema::roles;
@@ -9,7 +8,7 @@ use serenity::model::channel::Message;
 use serenity::prelude::Context;
 use crate::database::schema::roles::columns::perms;
 use crate::utils::db::{ServerInfo, get_db_role_by_id};
-use crate::utils::object_finding::get_role_from_id;
+use crate::utils::object_finding::get_role_from_arg;
 use crate::utils::perms::{get_module_perms, perms_exists};
 
 pub struct PermsCommand;
@@ -21,8 +20,37 @@ enum PermModifyOption {
 }
 
 impl PermsCommand {
-    fn get_role_perms(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let role = if let Some(r) = get_role_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("perms")
+            .desc("Permission management. You can provide permissions you want to add/remove/set to role or \
+            just type module name and it will add/remove/set every permission from that module.")
+            .perms(&["perms"])
+            .route(Route::new("adds permissions to role")
+                .literal("add")
+                .arg("<role>", ArgOption::Role)
+                .arg("<permissions or modules...>", ArgOption::Text))
+            .route(Route::new("removes permissions from role")
+                .literal("remove")
+                .arg("<role>", ArgOption::Role)
+                .arg("<permissions or modules...>", ArgOption::Text))
+            .route(Route::new("sets permissions for role")
+                .literal("set")
+                .arg("<role>", ArgOption::Role)
+                .arg("<permissions or modules...>", ArgOption::Text))
+            .route(Route::new("shows role's permissions")
+                .arg("<role>", ArgOption::Role))
+            .usage()
+            .handler(|ctx, msg, info, args| match args.first() {
+                "add" => PermsCommand.modify_perm(ctx, msg, args, PermModifyOption::Add, info),
+                "remove" => PermsCommand.modify_perm(ctx, msg, args, PermModifyOption::Remove, info),
+                "<role>" => PermsCommand.get_role_perms(ctx, msg, args, info),
+                _ => PermsCommand.modify_perm(ctx, msg, args, PermModifyOption::Set, info),
+            })
+            .build()
+    }
+
+    fn get_role_perms(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let role = if let Some(r) = get_role_from_arg(ctx, msg, &args, "role")? {
             r
         } else {
             return Ok(())
@@ -49,8 +77,8 @@ impl PermsCommand {
         Ok(())
     }
 
-    fn modify_perm (&self, ctx: &Context, msg: &Message, args: Vec<String>, modify_option: PermModifyOption, info: &ServerInfo) -> Result<(), String> {
-        let mut perms_to_modify = args[2..].to_vec();
+    fn modify_perm (&self, ctx: &Context, msg: &Message, args: ParsedArgs, modify_option: PermModifyOption, info: &ServerInfo) -> Result<(), String> {
+        let mut perms_to_modify = args.get_list("permissions or modules").unwrap();
         if !perms_exists(&perms_to_modify) {
             let mut modules_perms = Vec::new();
             for p in perms_to_modify.iter() {
@@ -64,7 +92,7 @@ impl PermsCommand {
             perms_to_modify = modules_perms;
         }
 
-        let role = if let Some(r) = get_role_from_id(ctx, msg, get_args(msg.to_owned(), true), 2)? {
+        let role = if let Some(r) = get_role_from_arg(ctx, msg, &args, "role")? {
             r
         } else {
             return Ok(())
@@ -106,115 +134,3 @@ impl PermsCommand {
         Ok(())
     }
 }
-
-impl Command for PermsCommand {
-    fn name(&self) -> String {
-        String::from("perms")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Permission management. You can provide permissions you want to add/remove/set to role or \
-        just type module name and it will add/remove/set every permission from that module.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "add".to_string(),
-                desc: Some("adds permissions to role".to_string()),
-                option: None,
-                next: Some(Box::new(CommandArg {
-                    name: "<role>".to_string(),
-                    desc: None,
-                    option: Some(ArgOption::Role),
-                    next: Some(Box::new(CommandArg{
-                        name: "<permissions or modules...>".to_string(),
-                        desc: None,
-                        option: Some(ArgOption::Text),
-                        next: None
-                    })),
-                })),
-            },
-            CommandArg {
-                name: "remove".to_string(),
-                desc: Some("removes permissions from role".to_string()),
-                option: None,
-                next: Some(Box::new(CommandArg {
-                    name: "<role>".to_string(),
-                    desc: None,
-                    option: Some(ArgOption::Role),
-                    next: Some(Box::new(CommandArg{
-                        name: "<permissions or modules...>".to_string(),
-                        desc: None,
-                        option: Some(ArgOption::Text),
-                        next: None
-                    })),
-                })),
-            },
-            CommandArg {
-                name: "set".to_string(),
-                desc: Some("sets permissions for role".to_string()),
-                option: None,
-                next: Some(Box::new(CommandArg {
-                    name: "<role>".to_string(),
-                    desc: None,
-                    option: Some(ArgOption::Role),
-                    next: Some(Box::new(CommandArg{
-                        name: "<permissions or modules...>".to_string(),
-                        desc: None,
-                        option: Some(ArgOption::Text),
-                        next: None
-                    })),
-                })),
-            },
-            CommandArg {
-                name: "<role>".to_string(),
-                desc: Some("shows role's permissions".to_string()),
-                option: Some(ArgOption::Role),
-                next: None
-            },
-            CommandArg{
-                name: "".to_string(),
-                desc: Some("shows usage information".to_string()),
-                option: None,
-                next: None
-            }
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["perms".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(path) => {
-                        match path[0].name.as_str() {
-                            "add" => self.modify_perm(ctx, msg, args, PermModifyOption::Add, info)?,
-                            "remove" => self.modify_perm(ctx, msg, args, PermModifyOption::Remove, info)?,
-                            "<role>" => self.get_role_perms(ctx, msg, info)?,
-                            _ => self.modify_perm(ctx, msg, args, PermModifyOption::Set, info)?
-                        }
-                    }
-                    None => {
-                        let help_cmd = super::help_command::HelpCommand{};
-                        help_cmd.show_cmd_details(ctx, msg, info, self.name())?;
-                    }
-                }
-            }
-            Err(why) => return Err(why),
-        }
-        Ok(())
-    }
-}
@@ -1,6 +1,5 @@
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use crate::database::get_db_con;
 use crate::database::models::Server;
 use crate::database::schema::servers;
@@ -13,6 +12,21 @@ use crate::utils::db::ServerInfo;
 pub struct PrefixCommand;
 
 impl PrefixCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("prefix")
+            .desc("Prefix manager.")
+            .perms(&["prefix"])
+            .route(Route::new("sets custom bot prefix for server.")
+                .literal("set")
+                .arg("<prefix>", ArgOption::Text))
+            .route(Route::new("shows bot prefix."))
+            .handler(|ctx, msg, info, args| match args.get_str("prefix") {
+                Some(new_prefix) => PrefixCommand.set_prefix(ctx, msg, info, &new_prefix),
+                None => PrefixCommand.show_prefix(ctx, msg, info),
+            })
+            .build()
+    }
+
     fn show_prefix(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
         let _ = msg.channel_id.send_message(&ctx.http, |m| {
             m.embed(|e| {
@@ -55,67 +69,3 @@ impl PrefixCommand {
         Ok(())
     }
 }
-
-impl Command for PrefixCommand {
-    fn name(&self) -> String {
-        String::from("prefix")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Prefix manager.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: String::from("set"),
-                desc: Some(String::from("sets custom bot prefix for server.")),
-                option: Some(ArgOption::Numeric),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("<prefix>"),
-                    desc: None,
-                    option: Some(ArgOption::Text),
-                    next: None,
-                })),
-            },
-            CommandArg {
-                name: String::from(""),
-                desc: Some(String::from("shows bot prefix.")),
-                option: None,
-                next: None,
-            },
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["prefix".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(),false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(path) => {
-                        if path.len() == 0 {
-                            self.show_prefix(ctx, msg, info)?;
-                        } else {
-                            self.set_prefix(ctx, msg, info, &args[1])?;
-                        }
-                    }
-                    None => return self.show_prefix(ctx, msg, info),
-                }
-            }
-            Err(why) => return Err(why),
-        }
-        Ok(())
-    }
-}
@@ -1,40 +1,144 @@
-use crate::command::{get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use crate::database::models::*;
 use serenity::model::channel::{Message, ChannelType, PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::id::{ChannelId, RoleId};
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, get_db_roles, create_special_entity};
+use crate::utils::db::{ServerInfo, get_db_roles, create_special_entity, get_special_entity_by_type, get_ticket_types, get_ticket_type,
+    create_ticket_type};
 use crate::bot_modules::BotModule;
 use serenity::model::Permissions;
-use crate::utils::object_finding::get_role_from_id;
+use crate::utils::object_finding::{get_role_from_arg, get_channel_from_arg};
 use crate::bot_modules::moderation::ModerationModule;
+use crate::bot_modules::tickets::new_command::{NewTicketCommand, default_ticket_type};
+use crate::bot_modules::tickets::ticket_type_command::{DEFAULT_NAME_PATTERN, TYPE_EMOJIS, next_emoji, valid_type_name};
+use crate::gateway::{DiscordGateway, Embed};
 
 pub struct SetupCommand;
 
 impl SetupCommand {
-    fn create_tickets(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: Vec<String>) -> Result<(), String> {
-        let role_id = if args.len() > 1 {
-            match get_role_from_id(ctx, msg, get_args(msg.clone(), true), 2)? {
-                Some(r) => r.id,
-                None => return Ok(())
-            }
-        } else {
-            // create role
-            let result = msg.guild_id.unwrap().create_role(ctx.http.clone(), |r| {
-                r.name("Support");
-                r.mentionable(true);
-                r.colour(2682408);
-                r.hoist(true);
-                r
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("setup")
+            .desc("Setup tool.")
+            .perms(&["setup"])
+            .route(Route::new("creates role used for mute command.")
+                .literal("muted-role")
+                .arg("[name]", ArgOption::Any))
+            .route(Route::new("creates channel used for moderation logging.")
+                .literal("modlogs-channel")
+                .arg("[name]", ArgOption::Any))
+            .route(Route::new("creates tickets category and support role (unless provided)")
+                .literal("tickets")
+                .arg("[support-role]", ArgOption::Role))
+            .route(Route::new("creates a ticket type with its own category and support role (unless provided). \
+                Its tickets are opened with `new <name>`, use `tickettype` to change it.")
+                .literal("ticket-type")
+                .arg("<name>", ArgOption::Any)
+                .arg("[support-role]", ArgOption::Role))
+            .route(Route::new("posts a message that opens a ticket for everyone who reacts to it. \
+                It's posted in the current channel unless `[channel]` is provided. A new panel replaces the old one, \
+                so post it again after adding ticket types.")
+                .literal("ticket-panel")
+                .arg("[channel]", ArgOption::Channel))
+            .usage()
+            .handler(|ctx, msg, info, args| match args.first() {
+                "modlogs-channel" => SetupCommand.create_mod_logs(ctx, msg, info, args),
+                "muted-role" => SetupCommand.create_mute_role(ctx, msg, info, args),
+                "tickets" => SetupCommand.create_tickets(ctx, msg, info, args),
+                "ticket-type" => SetupCommand.add_ticket_type(ctx, msg, info, args),
+                "ticket-panel" => SetupCommand.create_ticket_panel(ctx, msg, info, args),
+                _ => Err("Not implemented".to_string()),
+            })
+            .build()
+    }
+
+    fn create_tickets(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let role_id = match self.support_role(ctx, msg, &args, "Support")? {
+            Some(r) => r,
+            None => return Ok(())
+        };
+
+        let category_id = self.tickets_category(ctx, msg, "Tickets", role_id)?;
+        create_special_entity(info, category_id.to_string(), SpecialEntityType::TicketsCategory);
+
+        let _ = msg.channel_id.send_message(ctx.http.clone(), |m| {
+            m.embed(|e| {
+                e.title("Setup - Done!");
+                e.description("Tickets has been setup!");
+                e.color(EMBED_REGULAR_COLOR);
+                e
             });
+            m
+        });
+        Ok(())
+    }
 
-            match result {
-                Ok(r) => r.id,
-                Err(_) => return Err("Could not create a new role!".to_string())
-            }
+    fn add_ticket_type(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let name = args.get_str("name").unwrap().to_lowercase();
+        if !valid_type_name(&name) {
+            return Err("Name of the ticket type can have only letters, digits and `-`, up to 32 characters!".to_string())
+        }
+        if get_ticket_type(info, &name).is_some() {
+            return Err(format!("Ticket type `{}` already exists!", name))
+        }
+        let emoji = match next_emoji(&get_ticket_types(info)) {
+            Some(e) => e,
+            None => return Err(format!("There can't be more than {} ticket types!", TYPE_EMOJIS.len()))
         };
 
+        let role_id = match self.support_role(ctx, msg, &args, &format!("{} support", name))? {
+            Some(r) => r,
+            None => return Ok(())
+        };
+        let category_id = self.tickets_category(ctx, msg, &format!("{} tickets", name), role_id)?;
+        create_ticket_type(&NewTicketType {
+            server_id: info.server.as_ref().unwrap().id,
+            name: name.to_owned(),
+            emoji: emoji.to_string(),
+            category_id: category_id.to_string(),
+            support_role_ids: vec![role_id.to_string()],
+            name_pattern: DEFAULT_NAME_PATTERN.to_string(),
+            questions: Vec::new(),
+            welcome: None,
+        });
+
+        let prefix = &info.server.as_ref().unwrap().prefix;
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Setup - Done!",
+            &format!(
+                "Ticket type `{}` has been setup! Its tickets are opened with `{}new {}` or {} on the ticket panel. \
+                    Use `{}tickettype` to change it.",
+                name, prefix, name, emoji, prefix
+            ),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+
+    /// Role provided in `[support-role]` or a new one with the name. `None` when the provided role was not found.
+    fn support_role(&self, ctx: &Context, msg: &Message, args: &ParsedArgs, name: &str) -> Result<Option<RoleId>, String> {
+        if args.has("support-role") {
+            return Ok(get_role_from_arg(ctx, msg, args, "support-role")?.map(|r| r.id))
+        }
+
+        let result = msg.guild_id.unwrap().create_role(ctx.http.clone(), |r| {
+            r.name(name);
+            r.mentionable(true);
+            r.colour(2682408);
+            r.hoist(true);
+            r
+        });
+
+        match result {
+            Ok(r) => Ok(Some(r.id)),
+            Err(_) => Err("Could not create a new role!".to_string())
+        }
+    }
+
+    /// Category that only the support role can see.
+    fn tickets_category(&self, ctx: &Context, msg: &Message, name: &str, role_id: RoleId) -> Result<ChannelId, String> {
         let result = msg.guild_id.unwrap().create_channel(ctx.http.clone(), |ch| {
-            ch.name("Tickets");
+            ch.name(name);
             ch.topic("Tickets category. Made with UtterBot!");
             ch.kind(ChannelType::Category);
             let mut perms = Permissions::SEND_MESSAGES;
@@ -56,29 +160,44 @@ impl SetupCommand {
         });
 
         match result {
-            Ok(c) => create_special_entity(info, c.id.to_string(), SpecialEntityType::TicketsCategory),
-            Err(_) => return Err("Could not create tickets category!".to_string())
+            Ok(c) => Ok(c.id),
+            Err(_) => Err("Could not create tickets category!".to_string())
         }
-
-        let _ = msg.channel_id.send_message(ctx.http.clone(), |m| {
-            m.embed(|e| {
-                e.title("Setup - Done!");
-                e.description("Tickets has been setup!");
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
-        });
-        Ok(())
     }
 
-    fn create_mute_role(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: Vec<String>) -> Result<(), String> {
-        let name = if args.len() > 1 {
-            args[1].to_owned()
+    fn create_ticket_panel(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let prefix = &info.server.as_ref().unwrap().prefix;
+        let mut ticket_types = get_ticket_types(info);
+        if let Some(category) = get_special_entity_by_type(info, SpecialEntityType::TicketsCategory) {
+            ticket_types.insert(0, default_ticket_type(category.entity_id.parse::<u64>().unwrap()));
+        }
+        if ticket_types.is_empty() {
+            return Err(format!("There is nothing to open. Please use `{0}setup tickets` or `{0}setup ticket-type <name>` first!", prefix))
+        }
+
+        let channel_id = if args.has("channel") {
+            match get_channel_from_arg(ctx, msg, &args, "channel")? {
+                Some(ch) => ch.id.0,
+                None => return Ok(())
+            }
         } else {
-            "muted".to_string()
+            msg.channel_id.0
         };
 
+        let panel_id = NewTicketCommand.post_panel(ctx, channel_id, prefix, &ticket_types)?;
+        create_special_entity(info, panel_id.to_string(), SpecialEntityType::TicketPanel);
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Setup - Done!",
+            &format!("Ticket panel has been posted in <#{}>!", channel_id),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+
+    fn create_mute_role(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let name = args.get_str("name").unwrap_or("muted".to_string());
+
         match msg.guild(&ctx.cache) {
             Some(g) => {
                 let result = g.read().create_role(ctx.http.clone(), |r| {
@@ -109,12 +228,8 @@ impl SetupCommand {
         Ok(())
     }
 
-    fn create_mod_logs(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: Vec<String>) -> Result<(), String> {
-        let name = if args.len() > 1 {
-            args[1].to_owned()
-        } else {
-          "mod-logs".to_string()
-        };
+    fn create_mod_logs(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let name = args.get_str("name").unwrap_or("mod-logs".to_string());
 
         let mod_module = ModerationModule{};
 
@@ -196,93 +311,3 @@ impl SetupCommand {
         Ok(())
     }
 }
-
-impl Command for SetupCommand {
-    fn name(&self) -> String {
-        String::from("setup")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Setup tool.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: String::from("muted-role"),
-                desc: Some(String::from("creates role used for mute command.")),
-                option: Some(ArgOption::Any),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("[name]"),
-                    desc: None,
-                    option: Some(ArgOption::Any),
-                    next: None,
-                })),
-            },
-            CommandArg {
-                name: String::from("modlogs-channel"),
-                desc: Some(String::from("creates channel used for moderation logging.")),
-                option: Some(ArgOption::Any),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("[name]"),
-                    desc: None,
-                    option: Some(ArgOption::Any),
-                    next: None,
-                })),
-            },
-            CommandArg {
-                name: String::from("tickets"),
-                desc: Some(String::from("creates tickets category and support role (unless provided)")),
-                option: Some(ArgOption::Any),
-                next: Some(Box::new(CommandArg {
-                    name: String::from("[support-role]"),
-                    desc: None,
-                    option: Some(ArgOption::Role),
-                    next: None
-                })),
-            },
-            CommandArg {
-                name: String::from(""),
-                desc: Some(String::from("shows usage information.")),
-                option: None,
-                next: None,
-            },
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["setup".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => match routes {
-                Some(path) => {
-                    match path[0].name.as_str() {
-                        "modlogs-channel" => self.create_mod_logs(ctx, msg, info, args)?,
-                        "muted-role" => self.create_mute_role(ctx, msg, info, args)?,
-                        "tickets" => self.create_tickets(ctx, msg, info, args)?,
-                        _ => return Err("Not implemented".to_string())
-                    }
-
-                    Ok(())
-                }
-                None => {
-                    let help_cmd = super::help_command::HelpCommand{};
-                    help_cmd.show_cmd_details(ctx, msg, info, self.name())
-                },
-            },
-            Err(why) => return Err(why),
-        }
-    }
-}
-
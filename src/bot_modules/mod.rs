@@ -1,6 +1,8 @@
+pub mod automod;
 mod dev;
 pub mod main;
-mod moderation;
+pub mod moderation;
+pub mod tags;
 pub mod tickets;
 
 use crate::command::Command;
@@ -30,6 +32,8 @@ pub fn get_modules() -> Vec<Box<dyn BotModule>> {
         Box::new(main::MainModule {}),
         Box::new(moderation::ModerationModule {}),
         Box::new(tickets::TicketsModule {}),
+        Box::new(tags::TagsModule {}),
+        Box::new(automod::AutomodModule {}),
         Box::new(dev::DevModule {}),
     ]
 }
@@ -0,0 +1,67 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, get_case, delete_case};
+use crate::utils::special_entities_tools::{case_embed, update_case_log};
+use crate::gateway::{DiscordGateway, Embed};
+use crate::database::models::Action;
+
+pub struct CaseCommand;
+
+impl CaseCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("case")
+            .desc("Moderation cases. Every ban, kick, mute and warn gets its own number.")
+            .perms(&["case"])
+            .route(Route::new("deletes the case. Deleted warns do not count into the warn level anymore.")
+                .literal("delete")
+                .arg("<number>", ArgOption::Numeric))
+            .route(Route::new("shows the case")
+                .arg("<number>", ArgOption::Numeric))
+            .usage()
+            .handler(|ctx, msg, info, args| {
+                let case = find_case(info, &args)?;
+                if args.has("delete") {
+                    CaseCommand.delete(ctx, msg, &case)
+                } else {
+                    CaseCommand.show(ctx, msg, &case)
+                }
+            })
+            .build()
+    }
+
+    fn show(&self, ctx: &Context, msg: &Message, case: &Action) -> Result<(), String> {
+        let mut embed = case_embed(case);
+        if let Some(target) = &case.target {
+            embed.description.push_str(&format!("\n**User:** <@{}>", target));
+        }
+        embed.description.push_str(&format!("\n**Date:** {}", case.creation_date.format("%Y-%m-%d %H:%M UTC")));
+        if case.expired {
+            embed.description.push_str("\n*This warn has expired.*");
+        }
+
+        let _ = ctx.send_embed(msg.channel_id.0, &embed);
+        Ok(())
+    }
+
+    fn delete(&self, ctx: &Context, msg: &Message, case: &Action) -> Result<(), String> {
+        delete_case(case);
+        update_case_log(ctx, case, true);
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Case - Done!",
+            &format!("Case #{} has been deleted!", case.case_id.unwrap()),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+}
+
+pub fn find_case(info: &ServerInfo, args: &ParsedArgs) -> Result<Action, String> {
+    let number = args.get_num("number").unwrap() as i32;
+    match get_case(info, number) {
+        Some(case) => Ok(case),
+        None => Err(format!("Case #{} does not exist!", number))
+    }
+}
@@ -1,18 +1,34 @@
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, CommandConfig, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::command::cooldown::CooldownBucket;
 use serenity::model::channel::Message;
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType};
-use crate::bot_modules::main::help_command;
+use crate::utils::db::{ServerInfo, create_case, ActionType};
 use crate::utils::object_finding::{get_member_from_id, FindObject};
-use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::utils::special_entities_tools::{log_case, case_description};
+use crate::gateway::{DiscordGateway, Embed};
+use super::notify_command::{Notice, NotifyCommand};
 
 pub struct KickCommand;
 
 impl KickCommand {
-    fn kick(&self, ctx: &Context, msg: &Message, args: Vec<String>, info: &ServerInfo) -> Result<(), String> {
-        let member = match get_member_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("kick")
+            .desc("Kicks user from your server.")
+            .perms(&["kick"])
+            .cooldown(CooldownBucket::User, 3)
+            .config(CommandConfig::new("dm-user", ArgOption::Boolean, "false")
+                .desc("tells the kicked user about the kick in direct message. Its content is set up by `notify` command."))
+            .route(Route::new("kicks the user")
+                .arg("<user>", ArgOption::User)
+                .arg("[reason...]", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| KickCommand.kick(ctx, msg, args, info))
+            .build()
+    }
+
+    fn kick(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
             Some(m) => m,
             None => return Ok(())
         };
@@ -24,95 +40,37 @@ impl KickCommand {
             return Err("Really?".to_string())
         }
 
-        let action_msg = if args.len() > 1 {
-            format!("User has been kicked out! Reason {}.", args[1..].join(" "))
-        } else {
-            String::from("User has been kicked out!")
-        };
+        let reason = args.get_rest(msg, "reason");
+        let action_msg = format!("User {} has been kicked out", member.display_name());
 
-        match &ctx.http.kick_member(msg.guild_id.unwrap().0, member.get_id()) {
-            Ok(_) => create_action(
+        // user can't be messaged after leaving the server
+        NotifyCommand.notify(ctx, info, "kick", msg.guild_id.unwrap().0, member.user_id().0, &Notice {
+            title: "Kick!",
+            action: "kicked",
+            reason: reason.as_ref().map(|r| r.as_str()),
+            duration: None,
+            moderator_id: msg.author.id.0,
+        });
+
+        let case = match ctx.kick(msg.guild_id.unwrap().0, member.get_id()) {
+            Ok(_) => create_case(
                 info,
                 msg.author.id.to_string(),
                 Some(member.get_id().to_string()),
                 ActionType::Kick,
-                action_msg.to_owned()
+                action_msg,
+                reason
             ),
             Err(_) => return Err("Could not kick the user. Check permissions!".to_string())
-        }
+        };
 
-        let _ = msg.channel_id.send_message(&ctx.http, |m| {
-            m.embed(|e| {
-                e.title("Kick - Done!");
-                e.description(&action_msg);
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
-        });
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            &format!("Kick - Done! Case #{}", case.case_id.unwrap()),
+            &case_description(&case),
+            EMBED_REGULAR_COLOR
+        ));
 
-        send_to_mod_logs(ctx, info, "Kick", &action_msg);
+        log_case(ctx, info, &case);
         Ok(())
     }
 }
-
-impl Command for KickCommand {
-    fn name(&self) -> String {
-        String::from("kick")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Kicks user from your server.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "<user>".to_string(),
-                desc: Some("kicks the user".to_string()),
-                option: Some(ArgOption::User),
-                next: Some(Box::new(CommandArg{
-                    name: "[reason...]".to_string(),
-                    desc: None,
-                    option: Some(ArgOption::Any),
-                    next: None
-                }))
-            },
-            CommandArg {
-                name: "".to_string(),
-                desc: Some("shows usage information".to_string()),
-                option: None,
-                next: None
-            }
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["kick".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(_) => self.kick(ctx, msg, args, info)?,
-                    None => {
-                        let help_cmd = help_command::HelpCommand {};
-                        help_cmd.show_cmd_details(ctx, msg, info, self.name())?;
-                    }
-                }
-            }
-            Err(why) => return Err(why),
-        }
-        Ok(())
-    }
-}
\ No newline at end of file
@@ -1,129 +1,127 @@
-use crate::command::{get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR, EMBED_QUESTION_COLOR};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR, EMBED_QUESTION_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use serenity::model::channel::{Message, PermissionOverwrite, PermissionOverwriteType};
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType};
-use crate::utils::object_finding::get_channel_from_id;
+use crate::utils::db::{ServerInfo, create_action, create_temp_operation, delete_temp_operations, ActionType};
+use crate::utils::object_finding::get_channel_from_arg;
 use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::database::models::TempOperation;
+use crate::scheduler::{register_handler, guild_id, target_id};
+use crate::gateway::{DiscordGateway, Embed};
+use super::unlock_command::UnLockCommand;
 use serenity::model::Permissions;
-use serenity::model::misc::Mentionable;
 use serenity::model::id::RoleId;
 
 pub struct LockCommand;
 
 impl LockCommand {
-    fn lock(&self, ctx: &Context, msg: &Message, current_channel: bool, info: &ServerInfo) -> Result<(), String> {
-        let channel = if current_channel{
-            let g = msg.guild(&ctx.cache).unwrap();
-            let g = g.read();
-            match g.channels(&ctx.http) {
-                Ok(ch) => ch.get(&msg.channel_id).unwrap().clone(),
-                Err(_) => return Err("Could not retrieve the channels".to_string())
-            }
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("lock")
+            .desc("Locks down the channels.")
+            .perms(&["lock"])
+            .route(Route::new("locks down the provided channel. It has to be a mention or an ID.")
+                .arg("<channel>", ArgOption::ChannelId)
+                .arg("[time]", ArgOption::Time)
+                .arg("[reason...]", ArgOption::Any))
+            .route(Route::new("locks down the current channel and unlocks it after `<time>`. \
+                You create `<time>` by adding to desired time: `m` \
+                for minutes, `h` for hours, `d` for days behind the `time`. \nExample: `30m`.")
+                .arg("<time>", ArgOption::Time)
+                .arg("[reason...]", ArgOption::Any))
+            // a reason that doesn't start with a time, or no arguments at all, locks the current channel until `unlock`
+            .route(Route::new("locks down the current channel until `unlock`.")
+                .arg("[reason...]", ArgOption::Any))
+            .handler(|ctx, msg, info, args| LockCommand.lock(ctx, msg, args, info))
+            .init(|_| register_handler(ActionType::ChannelLock, |gateway, info, op| LockCommand.expire(gateway, info, op)))
+            .build()
+    }
+
+    fn lock(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let current_channel = !args.has("channel");
+        let channel_id = if current_channel {
+            msg.channel_id.0
         } else {
-            match get_channel_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
-                Some(ch) => ch,
+            match get_channel_from_arg(ctx, msg, &args, "channel")? {
+                Some(ch) => ch.id.0,
                 None => return Ok(())
             }
         };
 
-        let role_id: RoleId = msg.guild_id.unwrap().0.into();
-        let mut perm = PermissionOverwrite{
-            allow: Permissions::empty(),
-            deny: Permissions::SEND_MESSAGES,
-            kind: PermissionOverwriteType::Role(msg.guild_id.unwrap().0.into())
-        };
-
-        for p in channel.permission_overwrites.iter() {
-            if p.kind == PermissionOverwriteType::Role(role_id) {
-                perm.allow = p.allow;
-                perm.deny = p.deny;
-                perm.allow.remove(Permissions::SEND_MESSAGES);
-                perm.deny.insert(Permissions::SEND_MESSAGES);
-                break
-            }
+        if self.lock_channel(ctx, msg.guild_id.unwrap().0, channel_id).is_err() {
+            return Err("Could not lock the channel. Check permissions!".to_string())
         }
 
-        let action_msg = format!("Channel **{}** has been locked by {}", channel.name, msg.author.name);
-        match channel.create_permission(&ctx.http, &perm) {
-            Ok(_) => create_action(
-                info,
-                msg.author.id.to_string(),
-                Some(channel.to_string()),
-                ActionType::ChannelLock,
-                action_msg.to_owned()
-            ),
-            Err(_) => return Err("Could not lock the channel. Check permissions!".to_string())
+        // locking again replaces the old unlock time
+        delete_temp_operations(info, &channel_id.to_string(), ActionType::ChannelLock);
+        let mut action_msg = format!("Channel <#{}> has been locked by {}", channel_id, msg.author.name);
+        if let Some(end_date) = args.get_time("time") {
+            create_temp_operation(info, channel_id.to_string(), end_date, ActionType::ChannelLock);
+            action_msg.push_str(&format!(" for {}", args.get_str("time").unwrap()));
         }
+        if let Some(reason) = args.get_rest(msg, "reason") {
+            action_msg.push_str(&format!(". Reason: {}", reason));
+        }
+        create_action(info, msg.author.id.to_string(), Some(channel_id.to_string()), ActionType::ChannelLock, action_msg.to_owned());
 
-        let _ = channel.send_message(ctx.clone().http, |m| {
-            m.embed(|e| {
-                e.title("Locked!");
-                e.description("Channel has been locked!");
-                e.color(EMBED_QUESTION_COLOR);
-                e
-            });
-            m
-        });
-
+        let _ = ctx.send_embed(channel_id, &Embed::new("Locked!", "Channel has been locked!", EMBED_QUESTION_COLOR));
         if !current_channel {
-            let _ = msg.channel_id.send_message(ctx.clone().http, |m| {
-                m.embed(|e| {
-                    e.title("Locked!");
-                    e.description(format!("Successfully locked {}!", channel.mention()));
-                    e.color(EMBED_REGULAR_COLOR);
-                    e
-                });
-                m
-            });
+            let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+                "Locked!",
+                &format!("Successfully locked <#{}>!", channel_id),
+                EMBED_REGULAR_COLOR
+            ));
         } else {
-            let _ = msg.delete(ctx.clone().http);
+            let _ = ctx.delete_message(msg.channel_id.0, msg.id.0);
         }
 
         send_to_mod_logs(ctx, info, "Lock", &action_msg);
         Ok(())
     }
-}
 
-impl Command for LockCommand {
-    fn name(&self) -> String {
-        String::from("lock")
-    }
+    fn expire(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, op: &TempOperation) -> Result<(), String> {
+        let guild_id = guild_id(info)?;
+        let channel_id = target_id(op)?;
+        // channel is gone, so there is nothing to unlock
+        if gateway.channel(channel_id).is_none() {
+            return Ok(())
+        }
 
-    fn desc(&self) -> String {
-        String::from("Locks down the channels.")
+        UnLockCommand.unlock_channel(gateway, guild_id, channel_id)?;
+        let _ = gateway.send_embed(channel_id, &Embed::new("Unlocked!", "Channel has been unlocked!", EMBED_QUESTION_COLOR));
+        send_to_mod_logs(gateway, info, "Unlock", &format!("Channel <#{}> has been unlocked, lock time is over", channel_id));
+        Ok(())
     }
 
-    fn use_in_dm(&self) -> bool {
-        false
-    }
+    /// Denies sending messages in the channel. Other permissions of `@everyone` are kept.
+    fn lock_channel(&self, gateway: &dyn DiscordGateway, guild_id: u64, channel_id: u64) -> Result<(), String> {
+        let overwrites = match gateway.channel(channel_id) {
+            Some(ch) => ch.overwrites,
+            None => return Err("Could not find the channel".to_string())
+        };
 
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "[channel]".to_string(),
-                desc: Some("locks down the current channel. If channel will be provided it'll be used instead.".to_string()),
-                option: Some(ArgOption::Channel),
-                next: None
-            }
-        ])
+        gateway.set_permission(channel_id, &send_messages_overwrite(guild_id, &overwrites, false))
     }
+}
 
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["lock".to_string()])
-    }
+/// `@everyone` overwrite from `overwrites` with sending messages allowed or denied.
+pub fn send_messages_overwrite(guild_id: u64, overwrites: &[PermissionOverwrite], allowed: bool) -> PermissionOverwrite {
+    let kind = PermissionOverwriteType::Role(RoleId(guild_id));
+    let mut perm = PermissionOverwrite {
+        allow: Permissions::empty(),
+        deny: Permissions::empty(),
+        kind
+    };
 
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
+    if let Some(p) = overwrites.iter().find(|o| o.kind == kind) {
+        perm.allow = p.allow;
+        perm.deny = p.deny;
     }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                self.lock(ctx, msg, routes.is_none(), info)?;
-            }
-            Err(why) => return Err(why),
-        }
-        Ok(())
+    if allowed {
+        perm.allow.insert(Permissions::SEND_MESSAGES);
+        perm.deny.remove(Permissions::SEND_MESSAGES);
+    } else {
+        perm.allow.remove(Permissions::SEND_MESSAGES);
+        perm.deny.insert(Permissions::SEND_MESSAGES);
     }
-}
\ No newline at end of file
+    perm
+}
@@ -0,0 +1,244 @@
+use crate::command::{ArgOption, Command, CommandConfig, ParsedArgs, EMBED_REGULAR_COLOR, get_config, get_config_duration};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::{ChannelType, Message, PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::guild::Member;
+use serenity::model::id::RoleId;
+use serenity::model::Permissions;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, create_action, create_case, create_temp_operation, delete_temp_operations, get_locked_channels, create_locked_channels,
+    delete_locked_channels, ActionType};
+use crate::utils::special_entities_tools::{send_to_mod_logs, log_case};
+use crate::database::models::{LockedChannel, NewLockedChannel};
+use crate::scheduler::{register_handler, guild_id};
+use crate::gateway::{DiscordGateway, Embed};
+use crate::handler::STATE;
+use crate::bot_modules::BotModule;
+use super::ModerationModule;
+use super::purge_command::message_date;
+use super::raid::Raid;
+use super::lock_command::send_messages_overwrite;
+use chrono::{DateTime, Duration, Utc};
+
+const RAID_ACTIONS: [&str; 4] = ["alert", "lockdown", "kick", "ban"];
+
+pub struct LockdownCommand;
+
+impl LockdownCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("lockdown")
+            .desc("Locks every text channel of the server. Raid protection is set up here as well.")
+            .perms(&["lockdown"])
+            .config(CommandConfig::new("raid-joins", ArgOption::Numeric, "0")
+                .desc("how many users joining within `raid-time` start a raid. 0 turns raid protection off."))
+            .config(CommandConfig::new("raid-time", ArgOption::Time, "10s")
+                .desc("time in which `raid-joins` are counted."))
+            .config(CommandConfig::new("raid-account-age", ArgOption::Time, "")
+                .desc("only accounts younger than this are counted. Every account is counted when it's not set."))
+            .config(CommandConfig::new("raid-action", ArgOption::Any, "lockdown")
+                .desc("what happens when a raid starts. `kick` and `ban` punish every user of the raid. Mod logs are always alerted.")
+                .values(&RAID_ACTIONS))
+            .config(CommandConfig::new("raid-lockdown-time", ArgOption::Time, "30m")
+                .desc("how long lockdown started by raid protection lasts. It lasts until `unlockdown` when it's not set."))
+            .route(Route::new("locks every text channel and unlocks them after `<time>`. \
+                You create `<time>` by adding to desired time: `m` \
+                for minutes, `h` for hours, `d` for days behind the `time`. \nExample: `30m`.")
+                .arg("<time>", ArgOption::Time)
+                .arg("[reason...]", ArgOption::Any))
+            // a reason that doesn't start with a time, or no arguments at all, locks until `unlockdown`
+            .route(Route::new("locks every text channel until `unlockdown`.")
+                .arg("[reason...]", ArgOption::Any))
+            .handler(|ctx, msg, info, args| LockdownCommand.lockdown(ctx, msg, args, info))
+            .init(|_| register_handler(ActionType::Lockdown, |gateway, info, _| LockdownCommand.expire(gateway, info)))
+            .build()
+    }
+
+    fn lockdown(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let count = self.start(ctx, info, msg.guild_id.unwrap().0, args.get_time("time"))?;
+
+        let mut action_message = format!("Server has been locked down by {}", msg.author.name);
+        if let Some(time) = args.get_str("time") {
+            action_message.push_str(&format!(" for {}", time));
+        }
+        if let Some(reason) = args.get_rest(msg, "reason") {
+            action_message.push_str(&format!(". Reason: {}", reason));
+        }
+        create_action(info, msg.author.id.to_string(), None, ActionType::Lockdown, action_message.to_owned());
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Lockdown - Done!",
+            &format!("Locked {} channels!", count),
+            EMBED_REGULAR_COLOR
+        ));
+        send_to_mod_logs(ctx, info, "Lockdown", &action_message);
+        Ok(())
+    }
+
+    /// Locks every text channel and remembers their permissions. Returns the number of locked channels.
+    pub fn start(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, guild_id: u64, end_date: Option<DateTime<Utc>>) -> Result<usize, String> {
+        if !get_locked_channels(info).is_empty() {
+            return Err("The server is already locked down!".to_string())
+        }
+
+        let locked = lock_channels(gateway, info, guild_id)?;
+        create_locked_channels(&locked);
+        if let Some(end_date) = end_date {
+            create_temp_operation(info, guild_id.to_string(), end_date, ActionType::Lockdown);
+        }
+        Ok(locked.len())
+    }
+
+    /// Gives every locked channel its permissions from before the lockdown. Returns the number of unlocked channels.
+    pub fn end(&self, gateway: &dyn DiscordGateway, info: &ServerInfo) -> Result<usize, String> {
+        let locked = get_locked_channels(info);
+        if locked.is_empty() {
+            return Err("The server is not locked down!".to_string())
+        }
+
+        let guild_id = guild_id(info)?;
+        let count = restore_channels(gateway, guild_id, &locked);
+        delete_locked_channels(info);
+        delete_temp_operations(info, &guild_id.to_string(), ActionType::Lockdown);
+        Ok(count)
+    }
+
+    // server could have been unlocked by hand already
+    fn expire(&self, gateway: &dyn DiscordGateway, info: &ServerInfo) -> Result<(), String> {
+        if let Ok(count) = self.end(gateway, info) {
+            send_to_mod_logs(gateway, info, "Lockdown", &format!("Lockdown has ended. Unlocked {} channels.", count));
+        }
+        Ok(())
+    }
+
+    /// Counts the join and acts when it's a part of a raid.
+    pub fn check_raid(&self, ctx: &Context, info: &ServerInfo, guild_id: u64, member: &Member) {
+        let module: &dyn BotModule = &ModerationModule;
+        let limit = config(info, "raid-joins").parse::<f64>().map_or(0, |n| n.max(0.0) as usize);
+        let user_id = member.user_id().0;
+        if !module.enabled(info) || limit == 0 || member.user.read().bot {
+            return
+        }
+
+        let now = Utc::now();
+        if let Some(age) = get_config_duration(info, "lockdown", "raid-account-age") {
+            if now.naive_utc() - message_date(user_id) > age {
+                return
+            }
+        }
+
+        let time = get_config_duration(info, "lockdown", "raid-time").unwrap_or_else(Duration::zero);
+        let raid = STATE.lock().unwrap().join_history.push(guild_id, user_id, now, limit, time);
+        let action = config(info, "raid-action");
+        let wave = match raid {
+            Some(Raid::Started(wave)) => wave,
+            Some(Raid::Continues) => {
+                if action == "kick" || action == "ban" {
+                    self.punish_raiders(ctx, info, guild_id, &[user_id], &action);
+                }
+                return
+            },
+            None => return
+        };
+
+        let mut alert = format!("Raid detected! {} users joined within {}.", wave.len(), config(info, "raid-time"));
+        match action.as_str() {
+            "lockdown" => {
+                let end_date = get_config_duration(info, "lockdown", "raid-lockdown-time").map(|d| now + d);
+                match self.start(ctx, info, guild_id, end_date) {
+                    Ok(count) => {
+                        let bot_id = ctx.cache.read().user.id;
+                        create_action(info, bot_id.to_string(), None, ActionType::Lockdown, "Server has been locked down by raid protection".to_string());
+                        alert.push_str(&format!(" Locked {} channels.", count));
+                    },
+                    Err(why) => alert.push_str(&format!(" Lockdown failed: {}", why)),
+                }
+            },
+            "kick" | "ban" => {
+                let count = self.punish_raiders(ctx, info, guild_id, &wave, &action);
+                alert.push_str(&format!(" Punished {} of them with {}. Users joining during the raid are punished too.", count, action));
+            },
+            _ => {}
+        }
+        send_to_mod_logs(ctx, info, "Raid protection", &alert);
+    }
+
+    /// Kicks or bans every user. Returns the number of punished users.
+    fn punish_raiders(&self, ctx: &Context, info: &ServerInfo, guild_id: u64, users: &[u64], action: &str) -> usize {
+        let bot_id = ctx.cache.read().user.id;
+        let mut count = 0;
+        for user_id in users.iter() {
+            let (kind, result, done) = if action == "ban" {
+                (ActionType::Ban, ctx.ban(guild_id, *user_id, "Raid protection"), "banned")
+            } else {
+                (ActionType::Kick, ctx.kick(guild_id, *user_id), "kicked out")
+            };
+
+            if result.is_ok() {
+                count += 1;
+                // raid bans are permanent, an earlier temp-ban must not lift them
+                if action == "ban" {
+                    delete_temp_operations(info, &user_id.to_string(), ActionType::Ban);
+                }
+                let case = create_case(
+                    info,
+                    bot_id.to_string(),
+                    Some(user_id.to_string()),
+                    kind,
+                    format!("User <@{}> has been {}", user_id, done),
+                    Some("Raid protection".to_string())
+                );
+                log_case(ctx, info, &case);
+            }
+        }
+        count
+    }
+}
+
+/// Denies sending messages in every text channel. Returns `@everyone` permissions of locked channels from before the lock.
+pub fn lock_channels(gateway: &dyn DiscordGateway, info: &ServerInfo, guild_id: u64) -> Result<Vec<NewLockedChannel>, String> {
+    let everyone = PermissionOverwriteType::Role(RoleId(guild_id));
+    let server_id = info.server.as_ref().unwrap().id;
+
+    let mut locked = Vec::new();
+    for channel in gateway.channels(guild_id).iter().filter(|ch| ch.kind == ChannelType::Text) {
+        let previous = channel.overwrites.iter().find(|o| o.kind == everyone);
+        if gateway.set_permission(channel.id, &send_messages_overwrite(guild_id, &channel.overwrites, false)).is_ok() {
+            locked.push(NewLockedChannel {
+                server_id,
+                channel_id: channel.id.to_string(),
+                allow: previous.map(|p| p.allow.bits() as i64),
+                deny: previous.map(|p| p.deny.bits() as i64),
+            });
+        }
+    }
+
+    if locked.is_empty() {
+        return Err("Could not lock any channel. Check permissions!".to_string())
+    }
+    Ok(locked)
+}
+
+/// Sets permissions of the channels back. Overwrites that didn't exist before the lock are removed.
+/// Returns the number of unlocked channels.
+pub fn restore_channels(gateway: &dyn DiscordGateway, guild_id: u64, locked: &[LockedChannel]) -> usize {
+    let everyone = PermissionOverwriteType::Role(RoleId(guild_id));
+    let mut count = 0;
+    for channel in locked.iter() {
+        let channel_id = channel.channel_id.parse::<u64>().unwrap();
+        let result = match (channel.allow, channel.deny) {
+            (Some(allow), Some(deny)) => gateway.set_permission(channel_id, &PermissionOverwrite {
+                allow: Permissions::from_bits_truncate(allow as u64),
+                deny: Permissions::from_bits_truncate(deny as u64),
+                kind: everyone
+            }),
+            _ => gateway.remove_permission(channel_id, everyone),
+        };
+        if result.is_ok() {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn config(info: &ServerInfo, name: &str) -> String {
+    get_config(info, "lockdown", name).unwrap_or_default()
+}
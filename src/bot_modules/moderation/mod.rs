@@ -1,15 +1,25 @@
 use super::BotModule;
 use crate::command::Command;
 
-mod ban_command;
+pub mod ban_command;
 mod unban_command;
 mod kick_command;
-mod mute_command;
+pub mod mute_command;
 mod unmute_command;
-mod warn_command;
+pub mod warn_command;
 mod modtools_command;
 mod lock_command;
 mod unlock_command;
+mod case_command;
+mod reason_command;
+mod thresholds_command;
+pub mod purge_command;
+pub mod lockdown_command;
+mod unlockdown_command;
+pub mod raid;
+pub mod slowmode_command;
+pub mod sticky_roles_command;
+pub mod notify_command;
 
 pub struct ModerationModule;
 
@@ -24,15 +34,24 @@ impl BotModule for ModerationModule {
 
     fn commands(&self) -> Vec<Box<dyn Command>> {
         vec![
-            Box::new(ban_command::BanCommand {}),
-            Box::new(unban_command::UnBanCommand {}),
-            Box::new(kick_command::KickCommand {}),
-            Box::new(mute_command::MuteCommand {}),
-            Box::new(unmute_command::UnMuteCommand {}),
-            Box::new(warn_command::WarnCommand {}),
-            Box::new(modtools_command::ModToolsCommand {}),
-            Box::new(lock_command::LockCommand {}),
-            Box::new(unlock_command::UnLockCommand {}),
+            ban_command::BanCommand::command(),
+            unban_command::UnBanCommand::command(),
+            kick_command::KickCommand::command(),
+            mute_command::MuteCommand::command(),
+            unmute_command::UnMuteCommand::command(),
+            warn_command::WarnCommand::command(),
+            modtools_command::ModToolsCommand::command(),
+            lock_command::LockCommand::command(),
+            unlock_command::UnLockCommand::command(),
+            case_command::CaseCommand::command(),
+            reason_command::ReasonCommand::command(),
+            thresholds_command::ThresholdsCommand::command(),
+            purge_command::PurgeCommand::command(),
+            lockdown_command::LockdownCommand::command(),
+            unlockdown_command::UnLockdownCommand::command(),
+            slowmode_command::SlowmodeCommand::command(),
+            sticky_roles_command::StickyRolesCommand::command(),
+            notify_command::NotifyCommand::command(),
         ]
     }
 }
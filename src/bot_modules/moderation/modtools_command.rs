@@ -1,30 +1,55 @@
-use crate::command::{get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use serenity::model::channel::Message;
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType, get_actions_by_kind, get_user_warn_lvl};
+use crate::utils::db::{ServerInfo, create_action, ActionType, get_actions_by_kind, reduce_warn};
 use crate::utils::object_finding::{get_member_from_id, FindObject};
-use crate::bot_modules::main::help_command;
 
 pub struct ModToolsCommand;
 
 impl ModToolsCommand {
-    fn reduce_warns(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let member = match get_member_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("modtools")
+            .desc("Moderation tools. Check what kind of mischief someone did.")
+            .perms(&["modtools"])
+            .route(Route::new("reduces warn level")
+                .arg("<user>", ArgOption::User)
+                .literal("reduce-warns"))
+            .route(Route::new("shows report about a user")
+                .arg("<user>", ArgOption::User)
+                .arg("[warns/bans/mutes/kicks]", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| {
+                if args.has("reduce-warns") {
+                    ModToolsCommand.reduce_warns(ctx, msg, args, info)
+                } else {
+                    ModToolsCommand.show_report(ctx, msg, args, info)
+                }
+            })
+            .build()
+    }
+
+    fn reduce_warns(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
             Some(m) => m,
             None => return Ok(())
         };
 
-        let lvl = get_user_warn_lvl(info, &member.get_id().to_string());
-        if lvl < 1 {
-            return Err("User already has the lowest possible warn level!".to_string())
-        }
+        let warn = match reduce_warn(info, &member.get_id().to_string()) {
+            Some(w) => w,
+            None => return Err("User already has the lowest possible warn level!".to_string())
+        };
 
+        let mut action_msg = format!("Warn level reduced by **{}**", msg.author.name);
+        if let Some(case) = warn.case_id {
+            action_msg.push_str(&format!(", warn of case #{} has been cancelled", case));
+        }
         create_action(
             info,
             msg.author.id.to_string(),
             Some(member.get_id().to_string()),
             ActionType::ReducedWarn,
-            format!("Warn level reduced by **{}**", msg.author.name)
+            action_msg
         );
 
         let _ = msg.channel_id.send_message(&ctx.http, |m| {
@@ -49,12 +74,19 @@ impl ModToolsCommand {
                 }
                 let mut report_msg = String::new();
                 for (i, a) in actions.iter().enumerate() {
-                    let mut reason = a.clone().message;
+                    // actions created before case numbers have the reason inside the message
+                    let mut reason = a.reason.clone().unwrap_or_else(|| a.message.to_owned());
                     let temp_reasons: Vec<&str> = reason.split(". Reason:").collect();
                     if temp_reasons.len() > 1 {
                         reason = temp_reasons[1..].join(". ")
                     }
-                    report_msg.push_str(&format!("**{}.** {}\n", actions.len() - i, reason))
+                    if a.expired {
+                        reason.push_str(" *(expired)*");
+                    }
+                    match a.case_id {
+                        Some(case) => report_msg.push_str(&format!("**{}.** {} (case #{})\n", actions.len() - i, reason, case)),
+                        None => report_msg.push_str(&format!("**{}.** {}\n", actions.len() - i, reason))
+                    }
                 }
                 fields.push((title, report_msg))
             },
@@ -64,8 +96,8 @@ impl ModToolsCommand {
     }
 
     #[allow(unused_must_use)]
-    fn show_report(&self, ctx: &Context, msg: &Message, args: Vec<String>, info: &ServerInfo) -> Result<(), String> {
-        let member = match get_member_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
+    fn show_report(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
             Some(m) => m,
             None => return Ok(())
         };
@@ -73,13 +105,13 @@ impl ModToolsCommand {
         let user_id = &member.get_id().to_string();
         let report_message = format!("**Warns level:** {}\n", get_user_warn_lvl(info, &member.get_id().to_string()));
         let mut fields: Vec<(&str, String)> = Vec::new();
-        if args.len() == 2 {
-            match args[1].as_str() {
+        if let Some(kind) = args.get_str("warns/bans/mutes/kicks") {
+            match kind.as_str() {
                 "warns" => self.gather_info("Warns", vec![ActionType::Warn, ActionType::ReducedWarn], user_id, &mut fields, info)?,
                 "bans" => self.gather_info("Bans", vec![ActionType::Ban, ActionType::UnBan], user_id, &mut fields, info)?,
                 "mutes" => self.gather_info("Mutes", vec![ActionType::Mute, ActionType::UnMute], user_id, &mut fields, info)?,
                 "kicks" => self.gather_info("Kicks", vec![ActionType::Kick], user_id, &mut fields, info)?,
-                _ => return Err(format!("Type `{}` does not exist!", args[1]))
+                _ => return Err(format!("Type `{}` does not exist!", kind))
             }
         } else {
             self.gather_info("Warns", vec![ActionType::Warn, ActionType::ReducedWarn], user_id, &mut fields, info);
@@ -108,81 +140,3 @@ impl ModToolsCommand {
         Ok(())
     }
 }
-
-impl Command for ModToolsCommand {
-    fn name(&self) -> String {
-        String::from("modtools")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Moderation tools. Check what kind of mischief someone did.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "<user>".to_string(),
-                desc: Some("reduces warn level".to_string()),
-                option: Some(ArgOption::User),
-                next: Some(Box::new(CommandArg {
-                    name: "reduce-warns".to_string(),
-                    desc: None,
-                    option: None,
-                    next: None
-                }))
-            },
-            CommandArg {
-                name: "<user>".to_string(),
-                desc: Some("shows report about a user".to_string()),
-                option: Some(ArgOption::User),
-                next: Some(Box::new(CommandArg {
-                    name: "[warns/bans/mutes/kicks]".to_string(),
-                    desc: None,
-                    option: Some(ArgOption::Any),
-                    next: None
-                }))
-            },
-            CommandArg {
-                name: "".to_string(),
-                desc: Some("shows usage information".to_string()),
-                option: None,
-                next: None
-            }
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["modtools".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(path) => {
-                        if path.len() == 2 && path[1].name == "reduce-warns" {
-                            self.reduce_warns(ctx, msg, info)?;
-                        } else {
-                            self.show_report(ctx, msg, args, info)?;
-                        }
-                    },
-                    None => {
-                        let help_cmd = help_command::HelpCommand {};
-                        help_cmd.show_cmd_details(ctx, msg, info, self.name())?;
-                    }
-                }
-            }
-            Err(why) => return Err(why),
-        }
-        Ok(())
-    }
-}
@@ -1,30 +1,44 @@
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, CommandConfig, ParsedArgs, EMBED_REGULAR_COLOR, get_config};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::command::cooldown::CooldownBucket;
 use serenity::model::channel::Message;
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType, create_temp_operation, get_special_entity_by_type};
+use crate::utils::db::{ServerInfo, create_action, create_case, ActionType, create_temp_operation, delete_temp_operations, get_special_entity_by_type, is_user_muted};
 use crate::utils::object_finding::get_member_from_id;
-use crate::bot_modules::main::help_command;
+use crate::database::models::{TempOperation, SpecialEntityType};
+use crate::scheduler::{register_handler, guild_id, target_id};
+use crate::utils::special_entities_tools::{log_case, case_description, send_to_mod_logs};
+use crate::gateway::{DiscordGateway, Embed};
 use crate::utils::get_time;
-use std::thread;
-use crate::database::get_db_con;
-use std::sync::Mutex;
-use std::time::Duration;
-use crate::database::models::{Server, TempOperation, SpecialEntityType, SpecialEntity};
-use crate::database::schema::{servers, temp_operations};
-use crate::database::schema::temp_operations::columns::{id, action_type};
-use crate::diesel::{RunQueryDsl, BelongingToDsl, ExpressionMethods, QueryDsl, GroupedBy};
-use chrono::Utc;
-use log::error;
-use crate::utils::special_entities_tools::send_to_mod_logs;
 use crate::config::DEFAULT_PREFIX;
-use crate::database::schema::special_entities::columns::entity_type;
+use super::notify_command::{Notice, NotifyCommand};
 
 pub struct MuteCommand;
 
 impl MuteCommand {
-    fn mute(&self, ctx: &Context, msg: &Message, path: Vec<CommandArg>, args: Vec<String>, info: &ServerInfo) -> Result<(), String> {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("mute")
+            .desc("Mute system.")
+            .aliases(&["tempmute"])
+            .perms(&["mute"])
+            .cooldown(CooldownBucket::User, 3)
+            .config(CommandConfig::new("default-time", ArgOption::Time, "")
+                .desc("mute time used when `[time]` is not provided. Mutes are permanent when it is not set."))
+            .config(CommandConfig::new("dm-user", ArgOption::Boolean, "false")
+                .desc("tells the muted user about the mute in direct message. Its content is set up by `notify` command."))
+            .route(Route::new("mutes user. If `[time]` is provided then user will be temp-muted. \
+                You create `[time]` by adding to desired time: `m` \
+                for minutes, `h` for hours, `d` for days behind the `time`. \nExample: `2d`.")
+                .arg("<user>", ArgOption::User)
+                .arg("[time]", ArgOption::Time)
+                .arg("[reason...]", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| MuteCommand.mute(ctx, msg, args, info))
+            .init(|_| register_handler(ActionType::Mute, |gateway, info, op| MuteCommand.expire(gateway, info, op)))
+            .build()
+    }
+
+    fn mute(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
         let prefix = if let Some(s) = info.server.clone() {
             s.prefix
         } else {
@@ -36,7 +50,7 @@ impl MuteCommand {
             None => return Err(format!("There is no muted role. Please use `{}setup muted-role`!", prefix))
         };
 
-        let mut member = match get_member_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
             Some(m) => m,
             None => return Ok(())
         };
@@ -49,183 +63,125 @@ impl MuteCommand {
             return Err("Let's keep talking!".to_string())
         }
 
-        let mut reason = String::new();
-        let mut is_temp = false;
-
-        if path.len() > 1 {
-            if path[1].name == "[time]" {
-                is_temp = true;
-                if path.len() > 2 {
-                    reason = args[2..].join(" ");
-                }
-            } else {
-                reason = args[1..].join(" ");
-            }
-        }
-
-        let reason_action_msg = if !reason.is_empty() {
-            format!(". Reason: {}.", reason)
-        } else {
-            "!".to_string()
+        let reason = args.get_rest(msg, "reason").unwrap_or_default();
+        // explicit time wins over the server's default one
+        let time = match args.get_str("time") {
+            Some(t) => Some(t),
+            None => get_config(info, "mute", "default-time").filter(|t| !t.is_empty())
+        };
+        let end_date = match &time {
+            Some(t) => Some(get_time(t)?),
+            None => None
         };
 
-        let action_message = if is_temp {
-            format!("User {} has been temp-muted for {}{}",
-                    member.display_name(),
-                    &args[1],
-                    reason_action_msg
-            )
+        let action_message = if let Some(t) = &time {
+            format!("User {} has been temp-muted for {}", member.display_name(), t)
         } else {
-            format!("User {} has been muted{}", member.display_name(), reason_action_msg)
+            format!("User {} has been muted", member.display_name())
         };
 
-        match member.add_role(&ctx.http, mute_role_id.parse::<u64>().unwrap()) {
-            Ok(_) => create_action(
+        let case = match ctx.add_role(msg.guild_id.unwrap().0, member.user_id().0, mute_role_id.parse::<u64>().unwrap()) {
+            Ok(_) => create_case(
                 info,
                 msg.author.id.to_string(),
                 Some(member.user_id().to_string()),
                 ActionType::Mute,
-                action_message.to_owned()
+                action_message,
+                Some(reason).filter(|r| !r.is_empty())
             ),
             Err(_) => return Err("Could not mute the user. Check permissions!".to_string())
-        }
+        };
+
+        NotifyCommand.notify(ctx, info, "mute", msg.guild_id.unwrap().0, member.user_id().0, &Notice {
+            title: "Mute!",
+            action: "muted",
+            reason: case.reason.as_ref().map(|r| r.as_str()),
+            duration: time.as_ref().map(|t| t.as_str()),
+            moderator_id: msg.author.id.0,
+        });
 
-        if is_temp {
+        // new mute replaces the old one together with its end
+        delete_temp_operations(info, &member.user_id().to_string(), ActionType::Mute);
+        if let Some(end_date) = end_date {
             create_temp_operation(
                 info,
                 member.user_id().to_string(),
-                get_time(&args[1])?,
+                end_date,
                 ActionType::Mute
             );
         }
 
-        let _ = msg.channel_id.send_message(&ctx.http, |m| {
-            m.embed(|e| {
-                e.title("Mute - Done!");
-                e.description(&action_message);
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
-        });
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            &format!("Mute - Done! Case #{}", case.case_id.unwrap()),
+            &case_description(&case),
+            EMBED_REGULAR_COLOR
+        ));
 
-        send_to_mod_logs(ctx, info, "Mute", &action_message);
+        log_case(ctx, info, &case);
         Ok(())
     }
-}
-
-impl Command for MuteCommand {
-    fn name(&self) -> String {
-        String::from("mute")
-    }
 
-    fn desc(&self) -> String {
-        String::from("Mute system.")
+    // unmute is recorded, so the user isn't muted again when they rejoin
+    fn expire(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, op: &TempOperation) -> Result<(), String> {
+        self.unmute(gateway, info, op)?;
+        create_action(
+            info,
+            gateway.bot_id().to_string(),
+            Some(op.target_id.to_owned()),
+            ActionType::UnMute,
+            format!("User <@{}> has been unmuted, mute time is over", op.target_id)
+        );
+
+        NotifyCommand.notify(gateway, info, "unmute", guild_id(info)?, target_id(op)?, &Notice {
+            title: "Unmute!",
+            action: "unmuted",
+            reason: Some("Mute time is over"),
+            duration: None,
+            moderator_id: gateway.bot_id(),
+        });
+        Ok(())
     }
 
-    fn use_in_dm(&self) -> bool {
-        false
-    }
+    /// Gives the muted role back to the member who left while being muted. Returns true when the member was muted.
+    pub fn reapply(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, guild_id: u64, user_id: u64) -> Result<bool, String> {
+        let role_id = match get_special_entity_by_type(info, SpecialEntityType::MuteRole) {
+            Some(r) => r.entity_id.parse::<u64>().map_err(|_| "Invalid muted role id".to_string())?,
+            None => return Ok(false)
+        };
+        if !is_user_muted(info, &user_id.to_string()) {
+            return Ok(false)
+        }
 
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "<user>".to_string(),
-                desc: Some("mutes user. If `[time]` is provided then user will be temp-muted. \
-                You create `[time]` by adding to desired time: `m` \
-                for minutes, `h` for hours, `d` for days behind the `time`. \nExample: `2d`.".to_string()),
-                option: Some(ArgOption::User),
-                next: Some(Box::new(CommandArg {
-                    name: "[time]".to_string(),
-                    desc: None,
-                    option: Some(ArgOption::Time),
-                    next: Some(Box::new(CommandArg {
-                        name: "[reason...]".to_string(),
-                        desc: None,
-                        option: Some(ArgOption::Any),
-                        next: None,
-                    })),
-                }))
-            },
-            CommandArg {
-                name: "".to_string(),
-                desc: Some("shows usage information".to_string()),
-                option: None,
-                next: None
-            }
-        ])
+        if gateway.add_role(guild_id, user_id, role_id).is_err() {
+            return Err("Could not give the muted role back".to_string())
+        }
+        send_to_mod_logs(gateway, info, "Mute", &format!("User <@{}> rejoined while being muted. Muted role has been given back.", user_id));
+        Ok(true)
     }
 
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["mute".to_string()])
-    }
+    pub fn unmute(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, op: &TempOperation) -> Result<(), String> {
+        let role_id = match get_special_entity_by_type(info, SpecialEntityType::MuteRole) {
+            Some(r) => r.entity_id.parse::<u64>().map_err(|_| "Invalid muted role id".to_string())?,
+            None => return Ok(())
+        };
 
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
+        let guild_id = guild_id(info)?;
+        let user_id = target_id(op)?;
 
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(path) => self.mute(ctx, msg, path, args, info)?,
-                    None => {
-                        let help_cmd = help_command::HelpCommand {};
-                        help_cmd.show_cmd_details(ctx, msg, info, self.name())?;
-                    }
-                }
+        // nothing to undo when muted role is gone or was already taken away.
+        // Members who are not cached are unmuted anyway
+        if !gateway.roles(guild_id).iter().any(|r| r.id == role_id) {
+            return Ok(())
+        }
+        if let Some(member) = gateway.members(guild_id).iter().find(|m| m.id == user_id) {
+            if !member.roles.contains(&role_id) {
+                return Ok(())
             }
-            Err(why) => return Err(why),
         }
-        Ok(())
-    }
 
-    fn init(&self, ctx: &Context) {
-        let ctx = Mutex::new(ctx.clone());
-        thread::spawn(move || {
-            let db = get_db_con().get().expect("Could not get db pool!");
-            loop {
-                thread::sleep(Duration::from_secs(5));
-                let servers = servers::dsl::servers
-                    .load::<Server>(&db)
-                    .expect("Could not load servers!");
-
-                let unmutes = TempOperation::belonging_to(&servers)
-                    .filter(action_type.eq(ActionType::Mute as i32))
-                    .load::<TempOperation>(&db)
-                    .expect("Could not load temp bans and mutes")
-                    .grouped_by(&servers);
-
-                let muted_roles: Vec<SpecialEntity> = SpecialEntity::belonging_to(&servers)
-                    .filter(entity_type.eq(SpecialEntityType::MuteRole as i32))
-                    .load::<SpecialEntity>(&db)
-                    .expect("Could not load temp bans and mutes");
-
-                let data = servers.into_iter().zip(unmutes).collect::<Vec<_>>();
-
-                for v in data {
-                    for m in v.1.iter() {
-                        if m.end_date < Utc::now().naive_utc() {
-                            let guild_id = v.0.guildid.parse::<u64>().unwrap();
-                            let user_id = m.target_id.parse::<u64>().unwrap();
-                            let role_id = muted_roles.iter().find(|r| r.server_id == v.0.id);
-                            match role_id {
-                                Some (r) => {
-                                    let r_id = r.entity_id.parse::<u64>().unwrap();
-                                    match &ctx.lock().unwrap().http.remove_member_role(guild_id, user_id, r_id) {
-                                        Ok(_) => {/* send dm */},
-                                        Err(_) => error!("Could not unmute user")
-                                    }
-                                }
-                                None => {}
-                            }
-                            let _ = diesel::delete(temp_operations::table.filter(id.eq(m.id)))
-                                .execute(&db);
-                        }
-                    }
-                }
-            }
-        });
+        match gateway.remove_role(guild_id, user_id, role_id) {
+            Ok(_) => Ok(()),
+            Err(_) => Err("Could not unmute user".to_string())
+        }
     }
 }
@@ -0,0 +1,104 @@
+use crate::command::{ArgOption, Command, CommandConfig, EMBED_ERROR_COLOR, get_config, get_config_bool};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::ServerInfo;
+use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::utils::send_dm;
+use crate::gateway::{DiscordGateway, Embed};
+
+const DEFAULT_TEMPLATE: &str = "You have been {action} on **{server}**.\n**Reason:** {reason}\n**Duration:** {duration}\n\
+    **Moderator:** {moderator}\n{appeal}";
+
+/// Punishment the user is told about.
+pub struct Notice<'a> {
+    pub title: &'a str,
+    /// Past tense of the punishment, e.g. `banned`.
+    pub action: &'a str,
+    pub reason: Option<&'a str>,
+    pub duration: Option<&'a str>,
+    pub moderator_id: u64,
+}
+
+pub struct NotifyCommand;
+
+impl NotifyCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("notify")
+            .desc("Direct messages sent to punished users. They are turned on by `dm-user` setting of every punishment command.")
+            .perms(&["notify"])
+            .config(CommandConfig::new("template", ArgOption::Text, DEFAULT_TEMPLATE)
+                .desc("content of the message. Placeholders: `{server}`, `{action}`, `{reason}`, `{duration}`, `{moderator}`, `{appeal}`. \
+                    Lines with a placeholder that has no value are left out."))
+            .config(CommandConfig::new("show-moderator", ArgOption::Boolean, "false")
+                .desc("tells who punished the user. `{moderator}` is empty when it's turned off."))
+            .config(CommandConfig::new("appeal", ArgOption::Text, "")
+                .desc("how punished users can appeal, for example a link to a form."))
+            .route(Route::new("shows how the message looks."))
+            .handler(|ctx, msg, info, _| NotifyCommand.preview(ctx, msg, info))
+            .build()
+    }
+
+    fn preview(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let notice = Notice {
+            title: "Ban!",
+            action: "banned",
+            reason: Some("Breaking the rules"),
+            duration: Some("2d"),
+            moderator_id: msg.author.id.0,
+        };
+
+        let content = self.content(ctx, info, msg.guild_id.unwrap().0, &notice);
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(notice.title, &content, EMBED_ERROR_COLOR));
+        Ok(())
+    }
+
+    /// Sends the notice when `dm-user` setting of the command is on. Mod logs are told when the user can't be messaged.
+    /// Returns true when the message was sent.
+    pub fn notify(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, cmd_name: &str, guild_id: u64, user_id: u64, notice: &Notice) -> bool {
+        if !get_config_bool(info, cmd_name, "dm-user") {
+            return false
+        }
+
+        if send_dm(gateway, user_id, notice.title, &self.content(gateway, info, guild_id, notice)) {
+            return true
+        }
+        send_to_mod_logs(gateway, info, "Direct message", &format!(
+            "Could not tell <@{}> that they have been {}. Their direct messages are closed.",
+            user_id,
+            notice.action
+        ));
+        false
+    }
+
+    fn content(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, guild_id: u64, notice: &Notice) -> String {
+        let server = gateway.guild_name(guild_id).unwrap_or_else(|| "the server".to_string());
+        let template = get_config(info, "notify", "template")
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string());
+        let moderator = if get_config_bool(info, "notify", "show-moderator") {
+            Some(format!("<@{}>", notice.moderator_id))
+        } else {
+            None
+        };
+        let appeal = get_config(info, "notify", "appeal").filter(|a| !a.is_empty());
+
+        fill_notice(&template, &[
+            ("server", Some(server.as_str())),
+            ("action", Some(notice.action)),
+            ("reason", notice.reason),
+            ("duration", notice.duration),
+            ("moderator", moderator.as_ref().map(|m| m.as_str())),
+            ("appeal", appeal.as_ref().map(|a| a.as_str())),
+        ])
+    }
+}
+
+/// Fills `{name}` placeholders of the template. Lines with a placeholder that has no value are left out.
+pub fn fill_notice(template: &str, values: &[(&str, Option<&str>)]) -> String {
+    template.lines()
+        .filter(|line| values.iter().all(|(name, value)| value.is_some() || !line.contains(&format!("{{{}}}", name))))
+        .map(|line| values.iter().fold(line.to_string(), |l, (name, value)| l.replace(&format!("{{{}}}", name), value.unwrap_or_default())))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -0,0 +1,211 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::command::cooldown::CooldownBucket;
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, create_action, ActionType};
+use crate::utils::object_finding::get_member_from_id;
+use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::gateway::{DiscordGateway, Embed, MessageInfo};
+use chrono::{Duration, NaiveDateTime, Utc};
+
+const MAX_PURGE: usize = 500;
+// messages are fetched page by page, so the search has to stop somewhere when filters match only a few
+const MAX_SCANNED: usize = 2000;
+const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+
+pub enum PurgeFilter {
+    All,
+    User(u64),
+    Bots,
+    Contains(String),
+    Links,
+    Attachments,
+    Embeds,
+    Before(u64),
+    After(u64),
+}
+
+impl PurgeFilter {
+    fn matches(&self, msg: &MessageInfo) -> bool {
+        match self {
+            PurgeFilter::User(id) => msg.author_id == *id,
+            PurgeFilter::Bots => msg.bot,
+            PurgeFilter::Contains(text) => msg.content.to_lowercase().contains(&text.to_lowercase()),
+            PurgeFilter::Links => msg.content.contains("http://") || msg.content.contains("https://"),
+            PurgeFilter::Attachments => !msg.attachments.is_empty(),
+            PurgeFilter::Embeds => !msg.embeds.is_empty(),
+            PurgeFilter::All | PurgeFilter::Before(_) | PurgeFilter::After(_) => true,
+        }
+    }
+}
+
+pub struct PurgeCommand;
+
+impl PurgeCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("purge")
+            .desc("Deletes many messages at once.")
+            .aliases(&["clear"])
+            .perms(&["purge"])
+            .cooldown(CooldownBucket::Channel, 5)
+            .route(Route::new("deletes messages of the user")
+                .arg("<count>", ArgOption::Numeric)
+                .literal("from")
+                .arg("<user>", ArgOption::User))
+            .route(Route::new("deletes messages containing the text")
+                .arg("<count>", ArgOption::Numeric)
+                .literal("contains")
+                .arg("<text...>", ArgOption::Any))
+            .route(Route::new("deletes messages sent before the message")
+                .arg("<count>", ArgOption::Numeric)
+                .literal("before")
+                .arg("<messageID>", ArgOption::Any))
+            .route(Route::new("deletes messages sent after the message")
+                .arg("<count>", ArgOption::Numeric)
+                .literal("after")
+                .arg("<messageID>", ArgOption::Any))
+            .route(Route::new("deletes the last `<count>` messages")
+                .arg("<count>", ArgOption::Numeric))
+            .route(Route::new("deletes messages of bots, with links, with attachments or with embeds")
+                .arg("<count>", ArgOption::Numeric)
+                .arg("<bots/links/attachments/embeds>", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| PurgeCommand.run(ctx, msg, args, info))
+            .build()
+    }
+
+    fn run(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let count = args.get_num("count").unwrap();
+        if count < 1.0 || count > MAX_PURGE as f64 {
+            return Err(format!("You can purge from 1 to {} messages!", MAX_PURGE))
+        }
+
+        let filter = if args.has("from") {
+            match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
+                Some(m) => PurgeFilter::User(m.user_id().0),
+                None => return Ok(())
+            }
+        } else if args.has("contains") {
+            PurgeFilter::Contains(args.get_rest(msg, "text").unwrap())
+        } else if args.has("before") || args.has("after") {
+            let id = match args.get_str("messageID").unwrap().parse::<u64>() {
+                Ok(id) => id,
+                Err(_) => return Err("Invalid message ID!".to_string())
+            };
+            if args.has("before") { PurgeFilter::Before(id) } else { PurgeFilter::After(id) }
+        } else {
+            match args.get_str("bots/links/attachments/embeds").as_ref().map(|s| s.as_str()) {
+                Some("bots") => PurgeFilter::Bots,
+                Some("links") => PurgeFilter::Links,
+                Some("attachments") => PurgeFilter::Attachments,
+                Some("embeds") => PurgeFilter::Embeds,
+                _ => PurgeFilter::All,
+            }
+        };
+
+        let _ = ctx.delete_message(msg.channel_id.0, msg.id.0);
+        let deleted = self.purge(ctx, msg.channel_id.0, msg.id.0, count as usize, &filter, Utc::now().naive_utc())?;
+        if deleted.is_empty() {
+            return Err("There are no messages to purge!".to_string())
+        }
+
+        let action_message = format!("{} purged {} messages in <#{}>", msg.author.name, deleted.len(), msg.channel_id.0);
+        create_action(
+            info,
+            msg.author.id.to_string(),
+            Some(msg.channel_id.to_string()),
+            ActionType::Purge,
+            action_message.to_owned()
+        );
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Purge - Done!",
+            &format!("Deleted {} messages!", deleted.len()),
+            EMBED_REGULAR_COLOR
+        ));
+        send_to_mod_logs(ctx, info, "Purge", &format!("{}\n\n{}", action_message, purge_breakdown(&deleted)));
+
+        Ok(())
+    }
+
+    /// Deletes up to `count` matching messages sent before `before`. Returns the deleted messages.
+    pub fn purge(&self, gateway: &dyn DiscordGateway, channel_id: u64, before: u64, count: usize, filter: &PurgeFilter, now: NaiveDateTime)
+        -> Result<Vec<MessageInfo>, String> {
+        let mut before = match filter {
+            PurgeFilter::Before(id) => *id,
+            _ => before,
+        };
+
+        let mut found: Vec<MessageInfo> = Vec::new();
+        let mut scanned = 0;
+        'pages: while found.len() < count && scanned < MAX_SCANNED {
+            let page = gateway.messages(channel_id, before, 100)?;
+            if page.is_empty() {
+                break;
+            }
+            scanned += page.len();
+            before = page.last().unwrap().id;
+
+            for m in page.into_iter() {
+                if let PurgeFilter::After(id) = filter {
+                    if m.id <= *id {
+                        break 'pages;
+                    }
+                }
+                if filter.matches(&m) {
+                    found.push(m);
+                    if found.len() == count {
+                        break 'pages;
+                    }
+                }
+            }
+        }
+
+        // bulk delete is refused for older messages, so those go one by one
+        let (recent, old): (Vec<&MessageInfo>, Vec<&MessageInfo>) = found.iter()
+            .partition(|m| now - message_date(m.id) < Duration::days(14) - Duration::minutes(1));
+
+        for chunk in recent.chunks(100) {
+            let ids: Vec<u64> = chunk.iter().map(|m| m.id).collect();
+            if ids.len() == 1 {
+                gateway.delete_message(channel_id, ids[0])?;
+            } else {
+                gateway.delete_messages(channel_id, &ids)?;
+            }
+        }
+        for m in old.iter() {
+            gateway.delete_message(channel_id, m.id)?;
+        }
+
+        Ok(found)
+    }
+}
+
+/// Creation date of the message, read from its ID. Works for IDs of users and everything else too.
+pub fn message_date(message_id: u64) -> NaiveDateTime {
+    let millis = (message_id >> 22) + DISCORD_EPOCH;
+    NaiveDateTime::from_timestamp((millis / 1000) as i64, (millis % 1000 * 1_000_000) as u32)
+}
+
+/// The lowest ID that can be created at the date. Messages sent before the date have lower IDs.
+pub fn date_id(date: NaiveDateTime) -> u64 {
+    (date.timestamp_millis() as u64 - DISCORD_EPOCH) << 22
+}
+
+/// Number of deleted messages of every author, the most active first.
+pub fn purge_breakdown(messages: &[MessageInfo]) -> String {
+    let mut authors: Vec<(&str, usize)> = Vec::new();
+    for m in messages.iter() {
+        match authors.iter_mut().find(|(name, _)| *name == m.author_name) {
+            Some((_, count)) => *count += 1,
+            None => authors.push((m.author_name.as_str(), 1)),
+        }
+    }
+    authors.sort_by(|a, b| b.1.cmp(&a.1));
+
+    authors.iter()
+        .map(|(name, count)| format!("**{}:** {}", name, count))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
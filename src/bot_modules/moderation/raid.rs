@@ -0,0 +1,44 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+
+pub enum Raid {
+    /// Users of the join wave that started the raid.
+    Started(Vec<u64>),
+    /// The raid is still going on, this user joined within the raid time since the last raider.
+    Continues,
+}
+
+/// Recent joins of every guild.
+#[derive(Default)]
+pub struct JoinHistory {
+    joins: HashMap<u64, VecDeque<(DateTime<Utc>, u64)>>,
+    // time of the last join of an ongoing raid
+    raids: HashMap<u64, DateTime<Utc>>,
+}
+
+impl JoinHistory {
+    /// Adds the join. It's a raid when `limit` users joined within `time`.
+    pub fn push(&mut self, guild_id: u64, user_id: u64, now: DateTime<Utc>, limit: usize, time: Duration) -> Option<Raid> {
+        let oldest = now - time;
+        self.raids.retain(|_, last| *last > oldest);
+        self.joins.retain(|_, j| j.back().map_or(false, |(date, _)| *date > oldest));
+
+        if let Some(last) = self.raids.get_mut(&guild_id) {
+            *last = now;
+            return Some(Raid::Continues)
+        }
+
+        let joins = self.joins.entry(guild_id).or_default();
+        while joins.front().map_or(false, |(date, _)| *date <= oldest) {
+            joins.pop_front();
+        }
+        joins.push_back((now, user_id));
+        if limit == 0 || joins.len() < limit {
+            return None
+        }
+
+        let wave = joins.drain(..).map(|(_, id)| id).collect();
+        self.raids.insert(guild_id, now);
+        Some(Raid::Started(wave))
+    }
+}
@@ -0,0 +1,39 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, update_case_reason};
+use crate::utils::special_entities_tools::{case_description, update_case_log};
+use crate::gateway::{DiscordGateway, Embed};
+use super::case_command::find_case;
+
+pub struct ReasonCommand;
+
+impl ReasonCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("reason")
+            .desc("Changes the reason of a moderation case.")
+            .perms(&["case"])
+            .route(Route::new("changes the reason. Message of the case in mod logs is edited too.")
+                .arg("<number>", ArgOption::Numeric)
+                .arg("<reason...>", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| ReasonCommand.change_reason(ctx, msg, args, info))
+            .build()
+    }
+
+    fn change_reason(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let case = find_case(info, &args)?;
+        let reason = args.get_rest(msg, "reason").unwrap();
+
+        let case = update_case_reason(&case, &reason);
+        update_case_log(ctx, &case, false);
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            &format!("Reason - Done! Case #{}", case.case_id.unwrap()),
+            &case_description(&case),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+}
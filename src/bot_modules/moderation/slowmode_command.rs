@@ -0,0 +1,101 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, create_action, create_temp_operation, delete_temp_operations, ActionType};
+use crate::utils::object_finding::get_channel_from_arg;
+use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::utils::format_duration;
+use crate::database::models::TempOperation;
+use crate::scheduler::{register_handler, target_id};
+use crate::gateway::{DiscordGateway, Embed};
+use chrono::Duration;
+
+// the longest slowmode Discord allows
+const MAX_SLOWMODE: u64 = 21600;
+
+pub struct SlowmodeCommand;
+
+impl SlowmodeCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("slowmode")
+            .desc("Limits how often users can send messages.")
+            .perms(&["slowmode"])
+            .route(Route::new("sets how many seconds users have to wait between messages in the current channel. \
+                `off` turns slowmode off. If `[time]` is provided then slowmode is turned off after that time. \
+                You create `[time]` by adding to desired time: `m` \
+                for minutes, `h` for hours, `d` for days behind the `time`. \nExample: `1h`.")
+                .arg("<seconds>", ArgOption::Any)
+                .arg("[time]", ArgOption::Time))
+            .route(Route::new("sets slowmode of the provided channel.")
+                .arg("<channel>", ArgOption::Channel)
+                .arg("<seconds>", ArgOption::Any)
+                .arg("[time]", ArgOption::Time))
+            .usage()
+            .handler(|ctx, msg, info, args| SlowmodeCommand.slowmode(ctx, msg, args, info))
+            .init(|_| register_handler(ActionType::Slowmode, |gateway, info, op| SlowmodeCommand.expire(gateway, info, op)))
+            .build()
+    }
+
+    fn slowmode(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let channel_id = if args.has("channel") {
+            match get_channel_from_arg(ctx, msg, &args, "channel")? {
+                Some(ch) => ch.id.0,
+                None => return Ok(())
+            }
+        } else {
+            msg.channel_id.0
+        };
+
+        let seconds = parse_slowmode(&args.get_str("seconds").unwrap())?;
+        if seconds == 0 && args.has("time") {
+            return Err("Only slowmode that is on can be temporary!".to_string())
+        }
+        if ctx.set_slowmode(channel_id, seconds).is_err() {
+            return Err("Could not set the slowmode. Check permissions!".to_string())
+        }
+
+        // new slowmode replaces the old one together with its end
+        delete_temp_operations(info, &channel_id.to_string(), ActionType::Slowmode);
+        let mut action_msg = if seconds == 0 {
+            format!("Slowmode in <#{}> has been turned off by {}", channel_id, msg.author.name)
+        } else {
+            format!("Slowmode in <#{}> has been set to {} by {}", channel_id, format_duration(Duration::seconds(seconds as i64)), msg.author.name)
+        };
+        if let Some(end_date) = args.get_time("time") {
+            create_temp_operation(info, channel_id.to_string(), end_date, ActionType::Slowmode);
+            action_msg.push_str(&format!(" for {}", args.get_str("time").unwrap()));
+        }
+        create_action(info, msg.author.id.to_string(), Some(channel_id.to_string()), ActionType::Slowmode, action_msg.to_owned());
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Slowmode - Done!", &action_msg, EMBED_REGULAR_COLOR));
+        send_to_mod_logs(ctx, info, "Slowmode", &action_msg);
+        Ok(())
+    }
+
+    pub fn expire(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, op: &TempOperation) -> Result<(), String> {
+        let channel_id = target_id(op)?;
+        // channel is gone, so there is nothing to turn off
+        if gateway.channel(channel_id).is_none() {
+            return Ok(())
+        }
+
+        if gateway.set_slowmode(channel_id, 0).is_err() {
+            return Err("Could not turn the slowmode off".to_string())
+        }
+        send_to_mod_logs(gateway, info, "Slowmode", &format!("Slowmode in <#{}> has been turned off, its time is over", channel_id));
+        Ok(())
+    }
+}
+
+/// Seconds of slowmode, `off` is 0.
+pub fn parse_slowmode(value: &str) -> Result<u64, String> {
+    if value == "off" {
+        return Ok(0)
+    }
+
+    match value.parse::<u64>() {
+        Ok(seconds) if seconds <= MAX_SLOWMODE => Ok(seconds),
+        _ => Err(format!("Slowmode has to be a number of seconds from 0 to {} or `off`!", MAX_SLOWMODE))
+    }
+}
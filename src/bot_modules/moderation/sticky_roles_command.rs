@@ -0,0 +1,86 @@
+use crate::command::{ArgOption, Command, CommandConfig, EMBED_REGULAR_COLOR, get_config, get_config_bool};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, get_special_entity_by_type, set_sticky_roles, take_sticky_roles};
+use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::database::models::SpecialEntityType;
+use crate::bot_modules::automod::rules::parse_ids;
+use crate::gateway::{DiscordGateway, Embed};
+
+pub struct StickyRolesCommand;
+
+impl StickyRolesCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("stickyroles")
+            .desc("Gives members their roles back when they leave and rejoin the server. \
+                Muted role is handled by mutes, so it's given back only while the mute lasts.")
+            .perms(&["stickyroles"])
+            .config(CommandConfig::new("enabled", ArgOption::Boolean, "false")
+                .desc("saves roles of members leaving the server."))
+            .config(CommandConfig::new("exclude", ArgOption::Text, "")
+                .desc("roles that are never given back, separated by spaces."))
+            .route(Route::new("shows whether sticky roles are enabled."))
+            .handler(|ctx, msg, info, _| StickyRolesCommand.show(ctx, msg, info))
+            .build()
+    }
+
+    fn show(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let mut description = if get_config_bool(info, "stickyroles", "enabled") {
+            "Sticky roles are **enabled**.".to_string()
+        } else {
+            "Sticky roles are **disabled**. Use `config stickyroles enabled true` to enable them.".to_string()
+        };
+
+        let excluded = parse_ids(&get_config(info, "stickyroles", "exclude").unwrap_or_default());
+        if !excluded.is_empty() {
+            let roles: Vec<String> = excluded.iter().map(|r| format!("<@&{}>", r)).collect();
+            description.push_str(&format!("\n**Excluded roles:** {}", roles.join(", ")));
+        }
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Sticky roles", &description, EMBED_REGULAR_COLOR));
+        Ok(())
+    }
+
+    /// Remembers roles of the member leaving the server.
+    pub fn save(&self, info: &ServerInfo, user_id: u64, roles: &[u64]) {
+        if info.server.is_none() || roles.is_empty() || !get_config_bool(info, "stickyroles", "enabled") {
+            return
+        }
+
+        set_sticky_roles(info, user_id.to_string(), roles.iter().map(|r| r.to_string()).collect());
+    }
+
+    /// Gives the rejoining member roles they had when leaving. Returns the number of given roles.
+    pub fn restore(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, guild_id: u64, user_id: u64) -> usize {
+        if !get_config_bool(info, "stickyroles", "enabled") {
+            return 0
+        }
+        let saved = match take_sticky_roles(info, &user_id.to_string()) {
+            Some(s) => s.role_ids,
+            None => return 0
+        };
+
+        let mut skipped = parse_ids(&get_config(info, "stickyroles", "exclude").unwrap_or_default());
+        if let Some(mute_role) = get_special_entity_by_type(info, SpecialEntityType::MuteRole) {
+            skipped.push(mute_role.entity_id.parse::<u64>().unwrap());
+        }
+        let existing: Vec<u64> = gateway.roles(guild_id).iter().map(|r| r.id).collect();
+
+        let given = restored_roles(&saved, &existing, &skipped).into_iter()
+            .filter(|role_id| gateway.add_role(guild_id, user_id, *role_id).is_ok())
+            .count();
+        if given > 0 {
+            send_to_mod_logs(gateway, info, "Sticky roles", &format!("User <@{}> rejoined and got {} roles back.", user_id, given));
+        }
+        given
+    }
+}
+
+/// Saved roles that still exist and are not skipped.
+pub fn restored_roles(saved: &[String], existing: &[u64], skipped: &[u64]) -> Vec<u64> {
+    saved.iter()
+        .filter_map(|r| r.parse::<u64>().ok())
+        .filter(|r| existing.contains(r) && !skipped.contains(r))
+        .collect()
+}
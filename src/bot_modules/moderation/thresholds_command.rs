@@ -0,0 +1,100 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, get_warn_thresholds, set_warn_threshold, delete_warn_threshold};
+use crate::gateway::{DiscordGateway, Embed};
+
+pub struct ThresholdsCommand;
+
+impl ThresholdsCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("thresholds")
+            .desc("Punishments given automatically when user reaches the warn level.")
+            .perms(&["thresholds"])
+            .route(Route::new("sets the punishment for the warn level. Mutes and bans are temporary when `[time]` is provided.")
+                .literal("set")
+                .arg("<level>", ArgOption::Numeric)
+                .arg("<mute/kick/ban>", ArgOption::Any)
+                .arg("[time]", ArgOption::Time))
+            .route(Route::new("removes the punishment of the warn level.")
+                .literal("remove")
+                .arg("<level>", ArgOption::Numeric))
+            .route(Route::new("shows every warn threshold."))
+            .handler(|ctx, msg, info, args| {
+                if args.has("set") {
+                    ThresholdsCommand.set(ctx, msg, args, info)
+                } else if args.has("remove") {
+                    ThresholdsCommand.remove(ctx, msg, args, info)
+                } else {
+                    ThresholdsCommand.show(ctx, msg, info)
+                }
+            })
+            .build()
+    }
+
+    fn set(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let level = warn_level(&args)?;
+        let action = args.get_str("mute/kick/ban").unwrap();
+        let duration = args.get_str("time");
+        if action == "kick" && duration.is_some() {
+            return Err("Kick can't be temporary!".to_string())
+        }
+
+        let description = format!("Users reaching warn level **{}** will be {}.", level, threshold_description(&action, &duration));
+        set_warn_threshold(info, level, action, duration);
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Thresholds - Done!", &description, EMBED_REGULAR_COLOR));
+        Ok(())
+    }
+
+    fn remove(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let level = warn_level(&args)?;
+        if !delete_warn_threshold(info, level) {
+            return Err(format!("There is no threshold for warn level {}!", level))
+        }
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Thresholds - Done!",
+            &format!("Threshold for warn level **{}** has been removed.", level),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+
+    fn show(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let thresholds = get_warn_thresholds(info);
+        if thresholds.is_empty() {
+            return Err("There are no warn thresholds!".to_string())
+        }
+
+        let mut description = String::new();
+        for t in thresholds.iter() {
+            description.push_str(&format!("**{}** - {}\n", t.warn_level, threshold_description(&t.action, &t.duration)));
+        }
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Warn thresholds", &description, EMBED_REGULAR_COLOR));
+        Ok(())
+    }
+}
+
+fn warn_level(args: &ParsedArgs) -> Result<i32, String> {
+    let level = args.get_num("level").unwrap();
+    if level < 1.0 || level.fract() != 0.0 {
+        return Err("Warn level has to be a positive integer!".to_string())
+    }
+    Ok(level as i32)
+}
+
+fn threshold_description(action: &str, duration: &Option<String>) -> String {
+    let action = match action {
+        "mute" => "muted",
+        "kick" => "kicked",
+        _ => "banned",
+    };
+
+    match duration {
+        Some(d) => format!("{} for {}", action, d),
+        None => action.to_string()
+    }
+}
@@ -1,20 +1,32 @@
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, CommandConfig, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use serenity::model::channel::Message;
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType};
-use crate::bot_modules::main::help_command;
-use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::utils::db::{ServerInfo, create_case, ActionType, delete_temp_operations};
+use crate::utils::special_entities_tools::{log_case, case_description};
+use crate::gateway::{DiscordGateway, Embed};
+use super::notify_command::{Notice, NotifyCommand};
 
 pub struct UnBanCommand;
 
 impl UnBanCommand {
-    fn unban(&self, ctx: &Context, msg: &Message, args: Vec<String>, info: &ServerInfo) -> Result<(), String> {
-        let user_id = if msg.mentions.len() != 0 {
-            msg.mentions[0].id.0
-        } else {
-            args[0].parse::<u64>().unwrap()
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("unban")
+            .desc("Unbans user from your server.")
+            .perms(&["ban"])
+            .config(CommandConfig::new("dm-user", ArgOption::Boolean, "false")
+                .desc("tells the unbanned user about the unban in direct message. It works only when the user shares another server with the bot."))
+            .route(Route::new("unbans user")
+                .arg("<userID>", ArgOption::UserId))
+            .usage()
+            .handler(|ctx, msg, info, args| UnBanCommand.unban(ctx, msg, args, info))
+            .build()
+    }
+
+    fn unban(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let user_id = match args.get_user("userID") {
+            Some(id) => id.0,
+            None => return Err("Invalid user ID!".to_string())
         };
 
         if user_id == ctx.cache.read().user.id.0 {
@@ -29,86 +41,36 @@ impl UnBanCommand {
             Err(_) => return Err("User does not exist".to_string())
         };
 
-        let action_message = format!("User {} has been unbanned!", user.name);
-        match &ctx.http.remove_ban(msg.guild_id.unwrap().0, user_id) {
-            Ok(_) => create_action(
+        let action_message = format!("User {} has been unbanned", user.name);
+        let case = match ctx.unban(msg.guild_id.unwrap().0, user_id) {
+            Ok(_) => create_case(
                 info,
                 msg.author.id.to_string(),
                 Some(user_id.to_string()),
                 ActionType::UnBan,
-                action_message.to_owned()
+                action_message,
+                None
             ),
             Err(_) => return Err("Could not unban the user. Check permissions!".to_string())
-        }
-
-        let _ = msg.channel_id.send_message(&ctx.http, |m| {
-            m.embed(|e| {
-                e.title("Unban - Done!");
-                e.description(&action_message);
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
+        };
+        // scheduled unban would lift a later ban
+        delete_temp_operations(info, &user_id.to_string(), ActionType::Ban);
+        NotifyCommand.notify(ctx, info, "unban", msg.guild_id.unwrap().0, user_id, &Notice {
+            title: "Unban!",
+            action: "unbanned",
+            reason: None,
+            duration: None,
+            moderator_id: msg.author.id.0,
         });
 
-        send_to_mod_logs(ctx, info, "Un-Ban", &action_message);
-
-        Ok(())
-    }
-}
-
-impl Command for UnBanCommand {
-    fn name(&self) -> String {
-        String::from("unban")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Unbans user from your server.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            &format!("Unban - Done! Case #{}", case.case_id.unwrap()),
+            &case_description(&case),
+            EMBED_REGULAR_COLOR
+        ));
 
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "<userID>".to_string(),
-                desc: Some("unbans user".to_string()),
-                option: Some(ArgOption::UserId),
-                next: None
-            },
-            CommandArg {
-                name: "".to_string(),
-                desc: Some("shows usage information".to_string()),
-                option: None,
-                next: None
-            }
-        ])
-    }
+        log_case(ctx, info, &case);
 
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["ban".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(_) => self.unban(ctx, msg, args, info)?,
-                    None => {
-                        let help_cmd = help_command::HelpCommand {};
-                        help_cmd.show_cmd_details(ctx, msg, info, self.name())?;
-                    }
-                }
-            }
-            Err(why) => return Err(why),
-        }
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -1,129 +1,67 @@
-use crate::command::{get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR, EMBED_QUESTION_COLOR};
-use serenity::model::channel::{Message, PermissionOverwrite, PermissionOverwriteType};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR, EMBED_QUESTION_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType};
-use crate::utils::object_finding::get_channel_from_id;
+use crate::utils::db::{ServerInfo, create_action, delete_temp_operations, ActionType};
+use crate::utils::object_finding::get_channel_from_arg;
 use crate::utils::special_entities_tools::send_to_mod_logs;
-use serenity::model::Permissions;
-use serenity::model::misc::Mentionable;
-use serenity::model::id::RoleId;
+use crate::gateway::{DiscordGateway, Embed};
+use super::lock_command::send_messages_overwrite;
 
 pub struct UnLockCommand;
 
 impl UnLockCommand {
-    fn unlock(&self, ctx: &Context, msg: &Message, current_channel: bool, info: &ServerInfo) -> Result<(), String> {
-        let channel = if current_channel{
-            let g = msg.guild(&ctx.cache).unwrap();
-            let g = g.read();
-            match g.channels(&ctx.http) {
-                Ok(ch) => ch.get(&msg.channel_id).unwrap().clone(),
-                Err(_) => return Err("Could not retrieve the channels".to_string())
-            }
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("unlock")
+            .desc("Unlocks the channels.")
+            .perms(&["lock"])
+            .route(Route::new("unlocks the current channel. If channel will be provided it'll be used instead.")
+                .arg("[channel]", ArgOption::Channel))
+            .handler(|ctx, msg, info, args| UnLockCommand.unlock(ctx, msg, args, info))
+            .build()
+    }
+
+    fn unlock(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let current_channel = !args.has("channel");
+        let channel_id = if current_channel {
+            msg.channel_id.0
         } else {
-            match get_channel_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
-                Some(ch) => ch,
+            match get_channel_from_arg(ctx, msg, &args, "channel")? {
+                Some(ch) => ch.id.0,
                 None => return Ok(())
             }
         };
 
-        let role_id: RoleId = msg.guild_id.unwrap().0.into();
-        let mut perm = PermissionOverwrite{
-            allow: Permissions::SEND_MESSAGES,
-            deny: Permissions::empty(),
-            kind: PermissionOverwriteType::Role(msg.guild_id.unwrap().0.into())
-        };
-
-        for p in channel.permission_overwrites.iter() {
-            if p.kind == PermissionOverwriteType::Role(role_id) {
-                perm.allow = p.allow;
-                perm.deny = p.deny;
-                perm.allow.insert(Permissions::SEND_MESSAGES);
-                perm.deny.remove(Permissions::SEND_MESSAGES);
-                break
-            }
+        if self.unlock_channel(ctx, msg.guild_id.unwrap().0, channel_id).is_err() {
+            return Err("Could not unlock the channel. Check permissions!".to_string())
         }
 
-        let action_msg = format!("Channel **{}** has been unlocked by {}", channel.name, msg.author.name);
-        match channel.create_permission(&ctx.http, &perm) {
-            Ok(_) => create_action(
-                info,
-                msg.author.id.to_string(),
-                Some(channel.to_string()),
-                ActionType::ChannelUnLock,
-                action_msg.to_owned()
-            ),
-            Err(_) => return Err("Could not unlock the channel. Check permissions!".to_string())
-        }
-
-        let _ = channel.send_message(ctx.clone().http, |m| {
-            m.embed(|e| {
-                e.title("Unlocked!");
-                e.description("Channel has been unlocked!");
-                e.color(EMBED_QUESTION_COLOR);
-                e
-            });
-            m
-        });
+        delete_temp_operations(info, &channel_id.to_string(), ActionType::ChannelLock);
+        let action_msg = format!("Channel <#{}> has been unlocked by {}", channel_id, msg.author.name);
+        create_action(info, msg.author.id.to_string(), Some(channel_id.to_string()), ActionType::ChannelUnLock, action_msg.to_owned());
 
+        let _ = ctx.send_embed(channel_id, &Embed::new("Unlocked!", "Channel has been unlocked!", EMBED_QUESTION_COLOR));
         if !current_channel {
-            let _ = msg.channel_id.send_message(ctx.clone().http, |m| {
-                m.embed(|e| {
-                    e.title("Unlocked!");
-                    e.description(format!("Successfully unlocked {}!", channel.mention()));
-                    e.color(EMBED_REGULAR_COLOR);
-                    e
-                });
-                m
-            });
+            let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+                "Unlocked!",
+                &format!("Successfully unlocked <#{}>!", channel_id),
+                EMBED_REGULAR_COLOR
+            ));
         } else {
-            let _ = msg.delete(ctx.clone().http);
+            let _ = ctx.delete_message(msg.channel_id.0, msg.id.0);
         }
 
         send_to_mod_logs(ctx, info, "Unlock", &action_msg);
         Ok(())
     }
-}
-
-impl Command for UnLockCommand {
-    fn name(&self) -> String {
-        String::from("unlock")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Unlocks the channels.")
-    }
 
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "[channel]".to_string(),
-                desc: Some("unlocks the current channel. If channel will be provided it'll be used instead.".to_string()),
-                option: Some(ArgOption::Channel),
-                next: None
-            }
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["lock".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
+    /// Allows sending messages in the channel again. Other permissions of `@everyone` are kept.
+    pub fn unlock_channel(&self, gateway: &dyn DiscordGateway, guild_id: u64, channel_id: u64) -> Result<(), String> {
+        let overwrites = match gateway.channel(channel_id) {
+            Some(ch) => ch.overwrites,
+            None => return Err("Could not find the channel".to_string())
+        };
 
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                self.unlock(ctx, msg, routes.is_none(), info)?;
-            }
-            Err(why) => return Err(why),
-        }
-        Ok(())
+        gateway.set_permission(channel_id, &send_messages_overwrite(guild_id, &overwrites, true))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,36 @@
+use crate::command::{Command, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, create_action, ActionType};
+use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::gateway::{DiscordGateway, Embed};
+use super::lockdown_command::LockdownCommand;
+
+pub struct UnLockdownCommand;
+
+impl UnLockdownCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("unlockdown")
+            .desc("Ends the lockdown.")
+            .perms(&["lockdown"])
+            .route(Route::new("unlocks every channel locked by `lockdown`. Channels get back the permissions they had before."))
+            .handler(|ctx, msg, info, _| UnLockdownCommand.unlockdown(ctx, msg, info))
+            .build()
+    }
+
+    fn unlockdown(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let count = LockdownCommand.end(ctx, info)?;
+
+        let action_message = format!("Lockdown has been ended by {}", msg.author.name);
+        create_action(info, msg.author.id.to_string(), None, ActionType::UnLockdown, action_message.to_owned());
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Unlockdown - Done!",
+            &format!("Unlocked {} channels!", count),
+            EMBED_REGULAR_COLOR
+        ));
+        send_to_mod_logs(ctx, info, "Unlockdown", &action_message);
+        Ok(())
+    }
+}
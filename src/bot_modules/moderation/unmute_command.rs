@@ -1,19 +1,32 @@
-use crate::command::{
-    get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR,
-};
+use crate::command::{ArgOption, Command, CommandConfig, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
 use serenity::model::channel::Message;
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType, get_special_entity_by_type};
+use crate::utils::db::{ServerInfo, create_case, ActionType, delete_temp_operations, get_special_entity_by_type};
 use crate::utils::object_finding::get_member_from_id;
-use crate::bot_modules::main::help_command;
 use crate::database::models::SpecialEntityType;
-use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::utils::special_entities_tools::{log_case, case_description};
+use crate::gateway::{DiscordGateway, Embed};
 use crate::config::DEFAULT_PREFIX;
+use super::notify_command::{Notice, NotifyCommand};
 
 pub struct UnMuteCommand;
 
 impl UnMuteCommand {
-    fn unmute(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("unmute")
+            .desc("Mute system.")
+            .perms(&["mute"])
+            .config(CommandConfig::new("dm-user", ArgOption::Boolean, "false")
+                .desc("tells the unmuted user about the unmute in direct message. Its content is set up by `notify` command."))
+            .route(Route::new("unmutes user")
+                .arg("<user>", ArgOption::User))
+            .usage()
+            .handler(|ctx, msg, info, args| UnMuteCommand.unmute(ctx, msg, args, info))
+            .build()
+    }
+
+    fn unmute(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
         let prefix = if let Some(s) = info.server.clone() {
             s.prefix
         } else {
@@ -25,7 +38,7 @@ impl UnMuteCommand {
             None => return Err(format!("There is no muted role. Please use `{}setup muted-role`!", prefix))
         };
 
-        let mut member = match get_member_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
             Some(m) => m,
             None => return Ok(())
         };
@@ -37,86 +50,36 @@ impl UnMuteCommand {
             return Err("???".to_string())
         }
 
-        let action_message = format!("User {} has been un-muted!", member.display_name());
+        let action_message = format!("User {} has been un-muted", member.display_name());
 
-        match member.remove_role(&ctx.http, mute_role_id.parse::<u64>().unwrap()) {
-            Ok(_) => create_action(
+        let case = match ctx.remove_role(msg.guild_id.unwrap().0, member.user_id().0, mute_role_id.parse::<u64>().unwrap()) {
+            Ok(_) => create_case(
                 info,
                 msg.author.id.to_string(),
                 Some(member.user_id().to_string()),
                 ActionType::UnMute,
-                action_message.to_owned()
+                action_message,
+                None
             ),
             Err(_) => return Err("Could not un-mute the user. Check permissions!".to_string())
-        }
-
-        let _ = msg.channel_id.send_message(&ctx.http, |m| {
-            m.embed(|e| {
-                e.title("Un-Mute - Done!");
-                e.description(&action_message);
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
+        };
+        // mute is over, so its end must not unmute the user again
+        delete_temp_operations(info, &member.user_id().to_string(), ActionType::Mute);
+        NotifyCommand.notify(ctx, info, "unmute", msg.guild_id.unwrap().0, member.user_id().0, &Notice {
+            title: "Unmute!",
+            action: "unmuted",
+            reason: None,
+            duration: None,
+            moderator_id: msg.author.id.0,
         });
 
-        send_to_mod_logs(ctx, info, "Un-Mute", &action_message);
-        Ok(())
-    }
-}
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            &format!("Un-Mute - Done! Case #{}", case.case_id.unwrap()),
+            &case_description(&case),
+            EMBED_REGULAR_COLOR
+        ));
 
-impl Command for UnMuteCommand {
-    fn name(&self) -> String {
-        String::from("unmute")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Mute system.")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "<user>".to_string(),
-                desc: Some("unmutes user".to_string()),
-                option: Some(ArgOption::User),
-                next: None
-            },
-            CommandArg {
-                name: "".to_string(),
-                desc: Some("shows usage information".to_string()),
-                option: None,
-                next: None
-            }
-        ])
-    }
-
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["mute".to_string()])
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
-
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(_) => self.unmute(ctx, msg, info)?,
-                    None => {
-                        let help_cmd = help_command::HelpCommand {};
-                        help_cmd.show_cmd_details(ctx, msg, info, self.name())?;
-                    }
-                }
-            }
-            Err(why) => return Err(why),
-        }
+        log_case(ctx, info, &case);
         Ok(())
     }
 }
@@ -1,16 +1,44 @@
-use crate::command::{get_args, parse_args, ArgOption, Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR, EMBED_ERROR_COLOR};
+use crate::command::{ArgOption, Command, CommandConfig, ParsedArgs, EMBED_REGULAR_COLOR, get_config};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::command::cooldown::CooldownBucket;
 use serenity::model::channel::Message;
+use serenity::model::guild::Member;
 use serenity::prelude::Context;
-use crate::utils::db::{ServerInfo, create_action, ActionType};
+use crate::utils::db::{ServerInfo, create_case, ActionType, create_temp_operation, delete_temp_operations, get_user_warn_lvl, get_warn_thresholds, get_special_entity_by_type, expire_action};
+use crate::scheduler::register_handler;
+use crate::database::models::{Action, SpecialEntityType, TempOperation, WarnThreshold};
 use crate::utils::object_finding::{get_member_from_id, FindObject};
-use crate::bot_modules::main::help_command;
-use crate::utils::special_entities_tools::send_to_mod_logs;
+use crate::utils::special_entities_tools::{log_case, case_description};
+use crate::gateway::{DiscordGateway, Embed};
+use crate::utils::get_time;
+use super::notify_command::{Notice, NotifyCommand};
 
 pub struct WarnCommand;
 
 impl WarnCommand {
-    fn warn(&self, ctx: &Context, msg: &Message, args: Vec<String>, info: &ServerInfo) -> Result<(), String> {
-        let member = match get_member_from_id(ctx, msg, get_args(msg.to_owned(), true), 1)? {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("warn")
+            .desc("Warn system.")
+            .perms(&["warn"])
+            .cooldown(CooldownBucket::User, 3)
+            .config(CommandConfig::new("dm-user", ArgOption::Boolean, "true")
+                .desc("tells the warned user about the warn in direct message. Its content is set up by `notify` command."))
+            .config(CommandConfig::new("expire-after", ArgOption::Time, "")
+                .desc("time after which warns expire and stop counting into the warn level. Warns never expire when it is not set."))
+            .route(Route::new("warns user. If `[time]` is provided then the warn expires after that time. \
+                You create `[time]` by adding to desired time: `m` \
+                for minutes, `h` for hours, `d` for days behind the `time`. \nExample: `30d`.")
+                .arg("<user>", ArgOption::User)
+                .arg("[time]", ArgOption::Time)
+                .arg("<reason...>", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| WarnCommand.warn(ctx, msg, args, info))
+            .init(|_| register_handler(ActionType::Warn, |_, _, op| WarnCommand.expire(op)))
+            .build()
+    }
+
+    fn warn(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
             Some(m) => m,
             None => return Ok(())
         };
@@ -22,97 +50,143 @@ impl WarnCommand {
             return Err(r#"¯\_(ツ)_/¯"#.to_string())
         }
 
-        let reason = args[1..].join(" ");
-        let action_message = format!("User {} has been warned. Reason: {}!", member.display_name(), reason);
-        let _ = member.user.read().direct_message(ctx.clone().http, |m| {
-            m.embed(|e| {
-                e.title("Warn!");
-                e.description(format!("You have been warned! Reason: {}", reason));
-                e.color(EMBED_ERROR_COLOR);
-                e
-            });
-            m
-        });
+        let reason = args.get_rest(msg, "reason").unwrap();
+        let case = self.warn_user(ctx, info, msg.author.id.0, &member, reason, args.get_str("time"))?;
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            &format!("Warned! Case #{}", case.case_id.unwrap()),
+            &case_description(&case),
+            EMBED_REGULAR_COLOR
+        ));
 
-        let _ = msg.channel_id.send_message(&ctx.http, |m| {
-            m.embed(|e| {
-                e.title("Warned!");
-                e.description(&action_message);
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
+        if let Some((level, case)) = self.check_thresholds(ctx, info, &member)? {
+            let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+                &format!("Warn level {} reached! Case #{}", level, case.case_id.unwrap()),
+                &case_description(&case),
+                EMBED_REGULAR_COLOR
+            ));
+        }
+        Ok(())
+    }
+
+    /// Warns the member and logs the case. Warn expires after `time`, or the server's default time when it's not given.
+    pub fn warn_user(&self, ctx: &Context, info: &ServerInfo, issuer_id: u64, member: &Member, reason: String, time: Option<String>)
+        -> Result<Action, String> {
+        // explicit time wins over the server's default one
+        let time = match time {
+            Some(t) => Some(t),
+            None => get_config(info, "warn", "expire-after").filter(|t| !t.is_empty())
+        };
+        let end_date = match &time {
+            Some(t) => Some(get_time(t)?),
+            None => None
+        };
+
+        let action_message = match &time {
+            Some(t) => format!("User {} has been warned. The warn expires in {}", member.display_name(), t),
+            None => format!("User {} has been warned", member.display_name())
+        };
+        NotifyCommand.notify(ctx, info, "warn", member.guild_id.0, member.user_id().0, &Notice {
+            title: "Warn!",
+            action: "warned",
+            reason: Some(reason.as_str()),
+            duration: time.as_ref().map(|t| t.as_str()),
+            moderator_id: issuer_id,
         });
 
-        create_action(
+        let case = create_case(
             info,
-            msg.author.id.to_string(),
+            issuer_id.to_string(),
             Some(member.get_id().to_string()),
             ActionType::Warn,
-            action_message.to_owned()
+            action_message,
+            Some(reason)
         );
-        send_to_mod_logs(ctx, info, "Warn", &action_message);
-        Ok(())
-    }
-}
 
-impl Command for WarnCommand {
-    fn name(&self) -> String {
-        String::from("warn")
-    }
+        // target is the warn itself, not the user
+        if let Some(end_date) = end_date {
+            create_temp_operation(info, case.id.to_string(), end_date, ActionType::Warn);
+        }
 
-    fn desc(&self) -> String {
-        String::from("Warn system.")
+        log_case(ctx, info, &case);
+        Ok(case)
     }
 
-    fn use_in_dm(&self) -> bool {
-        false
+    /// Punishes the member when their warn level has a threshold. Returns the level and the punishment's case.
+    pub fn check_thresholds(&self, ctx: &Context, info: &ServerInfo, member: &Member) -> Result<Option<(i64, Action)>, String> {
+        let level = get_user_warn_lvl(info, &member.get_id().to_string());
+        let threshold = match get_warn_thresholds(info).into_iter().find(|t| t.warn_level as i64 == level) {
+            Some(t) => t,
+            None => return Ok(None)
+        };
+
+        let case = self.punish(ctx, info, member.guild_id.0, member.user_id().0, &member.display_name(), &threshold)?;
+        log_case(ctx, info, &case);
+        Ok(Some((level, case)))
     }
 
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        Some(vec![
-            CommandArg {
-                name: "<user>".to_string(),
-                desc: Some("warns user".to_string()),
-                option: Some(ArgOption::User),
-                next: Some(Box::new(CommandArg{
-                    name: "<reason...>".to_string(),
-                    desc: None,
-                    option: Some(ArgOption::Any),
-                    next: None
-                }))
+    fn expire(&self, op: &TempOperation) -> Result<(), String> {
+        match op.target_id.parse::<i32>() {
+            Ok(id) => {
+                expire_action(id);
+                Ok(())
             },
-            CommandArg {
-                name: "".to_string(),
-                desc: Some("shows usage information".to_string()),
-                option: None,
-                next: None
-            }
-        ])
+            Err(_) => Err("Invalid warn id".to_string())
+        }
     }
 
-    fn perms(&self) -> Option<Vec<String>> {
-        Some(vec!["warn".to_string()])
-    }
+    /// Executes the threshold's punishment. It's issued by the bot itself.
+    fn punish(&self, ctx: &Context, info: &ServerInfo, guild_id: u64, user_id: u64, user_name: &str, threshold: &WarnThreshold) -> Result<Action, String> {
+        let reason = format!("Reached warn level {}", threshold.warn_level);
+        let end_date = match &threshold.duration {
+            Some(d) => Some(get_time(d)?),
+            None => None
+        };
 
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
+        let role_id = get_special_entity_by_type(info, SpecialEntityType::MuteRole).map(|r| r.entity_id.parse::<u64>().unwrap());
+        if threshold.action == "mute" && role_id.is_none() {
+            return Err("Warn threshold could not mute the user. There is no muted role!".to_string())
+        }
+
+        let bot_id = ctx.cache.read().user.id;
+        let (cmd_name, title, done) = match threshold.action.as_str() {
+            "mute" => ("mute", "Mute!", "muted"),
+            "kick" => ("kick", "Kick!", "kicked"),
+            _ => ("ban", "Ban!", "banned"),
+        };
+        // user can't be messaged after leaving the server
+        NotifyCommand.notify(ctx, info, cmd_name, guild_id, user_id, &Notice {
+            title,
+            action: done,
+            reason: Some(reason.as_str()),
+            duration: threshold.duration.as_ref().map(|d| d.as_str()),
+            moderator_id: bot_id.0,
+        });
 
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        let args = get_args(msg.clone(), false);
-        match parse_args(&self.args().unwrap(), &args) {
-            Ok(routes) => {
-                match routes {
-                    Some(_) => self.warn(ctx, msg, args, info)?,
-                    None => {
-                        let help_cmd = help_command::HelpCommand {};
-                        help_cmd.show_cmd_details(ctx, msg, info, self.name())?;
-                    }
-                }
-            }
-            Err(why) => return Err(why),
+        let (kind, result) = match threshold.action.as_str() {
+            "mute" => (ActionType::Mute, ctx.add_role(guild_id, user_id, role_id.unwrap())),
+            "kick" => (ActionType::Kick, ctx.kick(guild_id, user_id)),
+            _ => (ActionType::Ban, ctx.ban(guild_id, user_id, &reason)),
+        };
+        if result.is_err() {
+            return Err("Warn threshold could not punish the user. Check permissions!".to_string())
         }
-        Ok(())
+
+        let action_message = match (kind, &threshold.duration) {
+            (ActionType::Mute, Some(d)) => format!("User {} has been temp-muted for {}", user_name, d),
+            (ActionType::Mute, None) => format!("User {} has been muted", user_name),
+            (ActionType::Kick, _) => format!("User {} has been kicked out", user_name),
+            (_, Some(d)) => format!("User {} has been temp-banned for {}", user_name, d),
+            (_, None) => format!("User {} has been banned", user_name),
+        };
+
+        // new mute or ban replaces the old one together with its end
+        if threshold.action != "kick" {
+            delete_temp_operations(info, &user_id.to_string(), kind);
+        }
+        if let Some(end_date) = end_date {
+            create_temp_operation(info, user_id.to_string(), end_date, kind);
+        }
+
+        Ok(create_case(info, bot_id.to_string(), Some(user_id.to_string()), kind, action_message, Some(reason)))
     }
 }
@@ -0,0 +1,22 @@
+use super::BotModule;
+use crate::command::Command;
+
+pub mod tag_command;
+
+pub struct TagsModule;
+
+impl BotModule for TagsModule {
+    fn name(&self) -> String {
+        String::from("tags")
+    }
+
+    fn desc(&self) -> String {
+        String::from("Custom text commands created by the server.")
+    }
+
+    fn commands(&self) -> Vec<Box<dyn Command>> {
+        vec![
+            tag_command::TagCommand::command(),
+        ]
+    }
+}
@@ -0,0 +1,220 @@
+use crate::bot_modules::find_module;
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR, resolve_command_name};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::database::models::Tag;
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, command_disabled, get_tag, create_db_tag, update_db_tag, delete_db_tag, increase_tag_uses};
+use log::error;
+
+// Discord does not allow longer messages
+const MAX_TAG_LENGTH: usize = 2000;
+
+pub struct TagCommand;
+
+impl TagCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("tag")
+            .desc("Custom text commands. Tag is used like any other command and can contain \
+                `{user}`, `{channel}` and `{server}` placeholders.")
+            .perms(&["tag"])
+            .route(Route::new("creates new tag.")
+                .literal("create")
+                .arg("<name>", ArgOption::Text)
+                .arg("<content...>", ArgOption::Any))
+            .route(Route::new("changes content of the tag. Only the owner and members with `Manage Messages` permission can do it.")
+                .literal("edit")
+                .arg("<name>", ArgOption::Text)
+                .arg("<content...>", ArgOption::Any))
+            .route(Route::new("removes the tag. Only the owner and members with `Manage Messages` permission can do it.")
+                .literal("delete")
+                .arg("<name>", ArgOption::Text))
+            .route(Route::new("shows information about the tag.")
+                .literal("info")
+                .arg("<name>", ArgOption::Text))
+            .route(Route::new("shows every tag created on this server.")
+                .literal("list"))
+            .usage()
+            .handler(|ctx, msg, info, args| match args.first() {
+                "create" => TagCommand.create(ctx, msg, info, args),
+                "edit" => TagCommand.edit(ctx, msg, info, args),
+                "delete" => TagCommand.delete(ctx, msg, info, args),
+                "info" => TagCommand.info(ctx, msg, info, args),
+                _ => TagCommand.list(ctx, msg, info),
+            })
+            .build()
+    }
+
+    /// Sends the tag if it exists and can be used in this channel. Returns `false` when there is no such tag.
+    pub fn run(&self, ctx: &Context, msg: &Message, info: &ServerInfo, name: &str) -> bool {
+        let tag = match get_tag(info, name) {
+            Some(t) => t,
+            None => return false
+        };
+
+        if !find_module("tags").map_or(false, |m| m.enabled(info)) {
+            return false
+        }
+        // disabled tags are ignored the same way as disabled commands
+        if command_disabled(info, &tag.name, &msg.channel_id.to_string()) {
+            return true
+        }
+
+        let server_name = msg.guild(&ctx.cache).map(|g| g.read().name.to_owned()).unwrap_or_default();
+        let content = fill_placeholders(
+            &tag.content,
+            &format!("<@{}>", msg.author.id),
+            &format!("<#{}>", msg.channel_id),
+            &server_name
+        );
+
+        match msg.channel_id.say(&ctx.http, content) {
+            Ok(_) => increase_tag_uses(&tag),
+            Err(_) => error!("Could not send tag '{}'", tag.name)
+        }
+        true
+    }
+
+    fn create(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let name = args.get_str("name").unwrap().to_lowercase();
+        let content = self.get_content(msg, &args)?;
+        if let Some(used_by) = resolve_command_name(&name, info) {
+            return Err(format!("`{}` is already used by `{}` command!", name, used_by))
+        }
+        if get_tag(info, &name).is_some() {
+            return Err(format!("Tag `{}` already exists!", name))
+        }
+
+        create_db_tag(info, name.to_owned(), content, msg.author.id.to_string());
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Tag has been created!");
+                e.description(format!("Use it by typing `{}` after the prefix.", name));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn edit(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let tag = self.get_owned_tag(ctx, msg, info, &args)?;
+        let content = self.get_content(msg, &args)?;
+        update_db_tag(&tag, &content);
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Tag has been edited!");
+                e.description(format!("Changed content of `{}` tag.", tag.name));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn delete(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let tag = self.get_owned_tag(ctx, msg, info, &args)?;
+        delete_db_tag(info, &tag);
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Tag has been removed!");
+                e.description(format!("Removed `{}` tag.", tag.name));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn info(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let name = args.get_str("name").unwrap().to_lowercase();
+        let tag = match get_tag(info, &name) {
+            Some(t) => t,
+            None => return Err(format!("Tag `{}` does not exist!", name))
+        };
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(format!("Tag - {}", tag.name));
+                e.description(format!(
+                    "**Owner:** <@{}>\n\
+                     **Uses:** {}\n\
+                     **Created:** {}",
+                    tag.owner,
+                    tag.uses,
+                    tag.creation_date.format("%Y-%m-%d %H:%M UTC")
+                ));
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn list(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let mut tags_message = String::new();
+        if let Some(tags) = &info.tags {
+            let mut tags = tags.to_owned();
+            tags.sort_by(|a, b| a.name.cmp(&b.name));
+            for t in tags.iter() {
+                tags_message.push_str(&format!("**{}** - used {} times\n", t.name, t.uses));
+            }
+        }
+
+        if tags_message.is_empty() {
+            tags_message = String::from("This server does not have any tags.");
+        }
+
+        let _ = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Tags");
+                e.description(tags_message);
+                e.color(EMBED_REGULAR_COLOR);
+                e
+            });
+            m
+        });
+        Ok(())
+    }
+
+    fn get_content(&self, msg: &Message, args: &ParsedArgs) -> Result<String, String> {
+        let content = args.get_rest(msg, "content").unwrap();
+        if content.chars().count() > MAX_TAG_LENGTH {
+            return Err(format!("Tag can't be longer than {} characters!", MAX_TAG_LENGTH))
+        }
+        Ok(content)
+    }
+
+    // only the owner and moderators can change the tag
+    fn get_owned_tag(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: &ParsedArgs) -> Result<Tag, String> {
+        let name = args.get_str("name").unwrap().to_lowercase();
+        let tag = match get_tag(info, &name) {
+            Some(t) => t,
+            None => return Err(format!("Tag `{}` does not exist!", name))
+        };
+
+        let is_moderator = match msg.guild(&ctx.cache) {
+            Some(g) => g.read().member_permissions(msg.author.id).manage_messages(),
+            None => false
+        };
+        if tag.owner != msg.author.id.to_string() && !is_moderator {
+            return Err("You are not the owner of this tag!".to_string())
+        }
+
+        Ok(tag)
+    }
+}
+
+pub fn fill_placeholders(content: &str, user: &str, channel: &str, server: &str) -> String {
+    content
+        .replace("{user}", user)
+        .replace("{channel}", channel)
+        .replace("{server}", server)
+}
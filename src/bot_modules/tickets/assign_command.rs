@@ -0,0 +1,53 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, ActionType, create_action, set_ticket_claim};
+use crate::utils::object_finding::get_member_from_id;
+use crate::gateway::{DiscordGateway, Embed};
+use super::participants::{current_ticket, set_claim};
+
+pub struct AssignCommand;
+
+impl AssignCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("assign")
+            .desc("Hands the current ticket over to someone.")
+            .perms(&["tickets"])
+            .route(Route::new("makes the user the one handling the ticket. The user is pinged in the ticket.")
+                .arg("<user>", ArgOption::User))
+            .usage()
+            .handler(|ctx, msg, info, args| AssignCommand.assign(ctx, msg, args, info))
+            .build()
+    }
+
+    fn assign(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let ticket = current_ticket(info, msg.channel_id.0)?;
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
+            Some(m) => m,
+            None => return Ok(())
+        };
+        let user_id = member.user_id().0;
+        if ticket.claimed_by == Some(user_id.to_string()) {
+            return Err(format!("<@{}> is already handling this ticket!", user_id))
+        }
+
+        set_claim(ctx, &ticket, Some(user_id))?;
+        set_ticket_claim(&ticket, Some(user_id));
+        create_action(
+            info,
+            msg.author.id.to_string(),
+            Some(msg.channel_id.to_string()),
+            ActionType::AssignTicket,
+            format!("{} assigned ticket-{} to {}.", msg.author.name, ticket.number, member.display_name())
+        );
+
+        let _ = ctx.send_message(msg.channel_id.0, &format!("<@{}>", user_id));
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Ticket assigned!",
+            &format!("<@{}> assigned this ticket to <@{}>.", msg.author.id, user_id),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::command::{Command, EMBED_REGULAR_COLOR};
+use crate::command::builder::CommandBuilder;
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, ActionType, create_action, set_ticket_claim};
+use crate::gateway::{DiscordGateway, Embed};
+use super::participants::{current_ticket, set_claim};
+
+pub struct ClaimCommand;
+
+impl ClaimCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("claim")
+            .desc("Tells everyone that you are handling the current ticket.")
+            .perms(&["tickets"])
+            .handler(|ctx, msg, info, _| ClaimCommand.claim(ctx, msg, info))
+            .build()
+    }
+
+    fn claim(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let ticket = current_ticket(info, msg.channel_id.0)?;
+        if let Some(claimed_by) = &ticket.claimed_by {
+            return Err(format!("This ticket is already claimed by <@{}>!", claimed_by))
+        }
+
+        set_claim(ctx, &ticket, Some(msg.author.id.0))?;
+        set_ticket_claim(&ticket, Some(msg.author.id.0));
+        create_action(
+            info,
+            msg.author.id.to_string(),
+            Some(msg.channel_id.to_string()),
+            ActionType::ClaimTicket,
+            format!("{} claimed ticket-{}.", msg.author.name, ticket.number)
+        );
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Ticket claimed!",
+            &format!("<@{}> is handling this ticket now.", msg.author.id),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+}
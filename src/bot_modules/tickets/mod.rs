@@ -1,8 +1,16 @@
 use super::BotModule;
 use crate::command::Command;
 
-mod new_command;
+pub mod new_command;
 pub mod solved_command;
+pub mod transcript;
+pub mod tickets_command;
+pub mod participants;
+pub mod claim_command;
+pub mod unclaim_command;
+pub mod assign_command;
+pub mod ticket_command;
+pub mod ticket_type_command;
 
 pub struct TicketsModule;
 
@@ -17,8 +25,14 @@ impl BotModule for TicketsModule {
 
     fn commands(&self) -> Vec<Box<dyn Command>> {
         vec![
-            Box::new(new_command::NewTicketCommand {}),
-            Box::new(solved_command::SolvedTicketCommand {}),
+            new_command::NewTicketCommand::command(),
+            solved_command::SolvedTicketCommand::command(),
+            tickets_command::TicketsCommand::command(),
+            claim_command::ClaimCommand::command(),
+            unclaim_command::UnclaimCommand::command(),
+            assign_command::AssignCommand::command(),
+            ticket_command::TicketCommand::command(),
+            ticket_type_command::TicketTypeCommand::command(),
         ]
     }
 }
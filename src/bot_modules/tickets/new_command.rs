@@ -1,126 +1,178 @@
-use serenity::model::channel::{Message, ChannelType, PermissionOverwrite, PermissionOverwriteType, ReactionType};
+use serenity::model::channel::{Message, PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::id::{RoleId, UserId};
+use serenity::model::user::User;
 use serenity::model::Permissions;
 use serenity::prelude::Context;
-use crate::database::models::SpecialEntityType;
-use crate::utils::db::{ServerInfo, ActionType, create_action, get_special_entity_by_type};
-use crate::command::{Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR};
-use rand::prelude::*;
-use chrono::Utc;
+use crate::database::models::{SpecialEntityType, Ticket, TicketType};
+use crate::gateway::{DiscordGateway, Embed, NewChannel};
+use crate::utils::db::{ServerInfo, ActionType, create_action, get_special_entity_by_type, create_ticket, set_ticket_channel, delete_ticket,
+    get_active_tickets, get_ticket_types};
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::command::cooldown::CooldownBucket;
+use super::participants::{access_overwrite, ticket_topic};
+use super::ticket_type_command::{DEFAULT_NAME_PATTERN, channel_name, numbered, pick_ticket_type};
+
+/// Reaction of the ticket panel that opens a ticket.
+pub const PANEL_EMOJI: &str = "🎫";
 
 pub struct NewTicketCommand;
 
-impl Command for NewTicketCommand {
-    fn name(&self) -> String {
-        String::from("new")
+impl NewTicketCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("new")
+            .desc("Creates new ticket")
+            .cooldown(CooldownBucket::User, 60)
+            .route(Route::new("creates a ticket. `[subject...]` tells the support team what the ticket is about. \
+                When it starts with a ticket type, e.g. `billing`, the ticket is of that type.")
+                .arg("[subject...]", ArgOption::Any))
+            .handler(|ctx, msg, info, args| NewTicketCommand.create_ticket(ctx, msg, info, args))
+            .build()
     }
 
-    fn desc(&self) -> String {
-        String::from("Creates new ticket")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
-    }
-
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        None
-    }
+    fn create_ticket(&self, ctx: &Context, msg: &Message, info: &ServerInfo, args: ParsedArgs) -> Result<(), String> {
+        let ticket_types = get_ticket_types(info);
+        let (ticket_type, subject) = pick_ticket_type(&ticket_types, args.get_rest(msg, "subject"));
+        let channel_id = self.new_ticket(ctx, info, msg.guild_id.unwrap().0, &msg.author, ticket_type, subject)?;
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Created a new ticket!",
+            &format!("Your ticket: <#{}>", channel_id),
+            EMBED_REGULAR_COLOR
+        ));
 
-    fn perms(&self) -> Option<Vec<String>> {
-        None
-    }
-
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
+        Ok(())
     }
 
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+    /// Opens a ticket for the user, both `new` command and the ticket panel end up here. Tickets without a type are opened
+    /// in the category made by `setup tickets`. Returns id of the ticket channel.
+    pub fn new_ticket(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, guild_id: u64, user: &User, ticket_type: Option<&TicketType>, subject: Option<String>) -> Result<u64, String> {
         let prefix = &info.server.as_ref().unwrap().prefix;
-        let ticket_category = match get_special_entity_by_type(info, SpecialEntityType::TicketsCategory) {
-            Some(id) => id.entity_id,
-            None => return Err(format!("Tickets' category does not exist. Please use `{}setup tickets`!", prefix))
-        };
-
-        {
-            let guild = &msg.guild(&ctx.cache).unwrap();
-            let guild = guild.read();
-            for (_, channel) in &guild.channels {
-                let channel = channel.read();
-                if let Some(c) = channel.category_id {
-                    if c.to_string() != ticket_category {
-                        continue
+        let opened_type = match ticket_type {
+            Some(t) => t.clone(),
+            None => match get_special_entity_by_type(info, SpecialEntityType::TicketsCategory) {
+                Some(category) => default_ticket_type(category.entity_id.parse::<u64>().unwrap()),
+                None => {
+                    let ticket_types = get_ticket_types(info);
+                    if ticket_types.is_empty() {
+                        return Err(format!("Tickets' category does not exist. Please use `{}setup tickets`!", prefix))
                     }
-                } else {
-                    continue
+                    let names: Vec<String> = ticket_types.iter().map(|t| format!("`{}`", t.name)).collect();
+                    return Err(format!("Choose the type of your ticket: {}. For example `{}new {} [subject]`.", names.join(", "), prefix, ticket_types[0].name))
                 }
+            }
+        };
 
-                let found_perms = channel.permission_overwrites.iter().find(|v| v.kind == PermissionOverwriteType::Member(msg.author.id));
-                if found_perms.is_some() {
-                   return Err(format!("You already have an active ticket. It's here <#{}>.", channel.id.0))
-                }
+        let active_tickets = get_active_tickets(info);
+        let ticket = create_ticket(info, user.id.to_string(), ticket_type.map(|t| t.id), subject)?;
+        let result = self.open_ticket(gateway, guild_id, &ticket, &active_tickets, &opened_type, prefix);
+        let channel_id = match result {
+            Ok(id) => id,
+            Err(why) => {
+                delete_ticket(&ticket);
+                return Err(why)
             }
+        };
+        set_ticket_channel(&ticket, channel_id);
+
+        create_action(
+            info,
+            user.id.to_string(),
+            Some(channel_id.to_string()),
+            ActionType::NewTicket,
+            format!("User {} created a {}.", user.name, channel_name(&opened_type.name_pattern, ticket.number, &opened_type.name))
+        );
+
+        Ok(channel_id)
+    }
+
+    /// Posts the embed that opens a ticket of the type whose emoji the user reacts with. Returns id of the panel message.
+    pub fn post_panel(&self, gateway: &dyn DiscordGateway, channel_id: u64, prefix: &str, ticket_types: &[TicketType]) -> Result<u64, String> {
+        let types: Vec<String> = ticket_types.iter().map(|t| format!("{} **{}**", t.emoji, t.name)).collect();
+        let panel_id = gateway.send_embed(channel_id, &Embed::new(
+            "Tickets",
+            &format!("Need help? React with the type of your problem to open a ticket. \
+                Someone from support team will help you out soon!\n\n{}\n\nYou can also type `{}new [type] [subject]`.", types.join("\n"), prefix),
+            EMBED_REGULAR_COLOR
+        )).map_err(|_| format!("Could not post the ticket panel in <#{}>. Check permissions!", channel_id))?;
+
+        if ticket_types.iter().any(|t| gateway.add_reaction(channel_id, panel_id, &t.emoji).is_err()) {
+            let _ = gateway.delete_message(channel_id, panel_id);
+            return Err(format!("Could not add reactions in <#{}>. Check permissions!", channel_id))
         }
+        Ok(panel_id)
+    }
 
-        let mut rng = rand::thread_rng();
-        let ticket_id = rng.gen_range(1000, 10000);
-        let result = msg.guild_id.unwrap().create_channel(&ctx.http, |ch| {
-            ch.name(format!("ticket-{}", ticket_id));
-            ch.kind(ChannelType::Text);
-            ch.category(ticket_category.parse::<u64>().unwrap());
-            ch.topic(format!("Ticket number: {}. Issued by: {}, Creation Date: {}", ticket_id, msg.author.name, Utc::now().to_rfc2822()));
-
-            let mut perms = Permissions::SEND_MESSAGES;
-            perms.insert(Permissions::READ_MESSAGES);
-            perms.insert(Permissions::ADD_REACTIONS);
-            ch.permissions(vec![PermissionOverwrite {
-                allow: perms,
-                deny: Permissions::empty(),
-                kind: PermissionOverwriteType::Member(msg.author.id)
-            }]);
-            ch
-        });
-
-        match result {
-            Ok(c) => {
-                create_action(
-                    info,
-                    msg.author.id.to_string(),
-                    Some(c.id.to_string()),
-                    ActionType::NewTicket,
-                    format!("User {} created a ticket-{}.", msg.author.name, ticket_id)
-                );
-
-                let result = c.send_message(ctx.http.clone(), |m| {
-                    m.embed(|e| {
-                        e.title("Ticket has been created!");
-                        e.description(format!("Hi <@{}>! Someone from support team will help you out soon! \
-                                               Type `{}solved` or react with ✅ to mark this ticket as solved.", msg.author.id.0, prefix));
-                        e.color(EMBED_REGULAR_COLOR);
-                        e
-                    });
-                    m
-                });
-
-                match result {
-                    Ok(m) => {
-                        let _ = m.react(ctx.http.clone(), ReactionType::from("✅"));
-                    },
-                    Err(_) => {}
-                }
+    /// Creates ticket channel that only the author and the staff can see. Returns id of the channel.
+    /// `active_tickets` tell apart tickets of the author from the ones the author was added to.
+    pub fn open_ticket(&self, gateway: &dyn DiscordGateway, guild_id: u64, ticket: &Ticket, active_tickets: &[Ticket], ticket_type: &TicketType, prefix: &str) -> Result<u64, String> {
+        let author_id = UserId(ticket.opener_id.parse::<u64>().unwrap());
+        let category_id = ticket_type.category_id.parse::<u64>().unwrap();
+        let active_ticket = gateway.channels(guild_id).into_iter()
+            .filter(|ch| ch.category_id == Some(category_id))
+            .filter(|ch| ch.overwrites.iter().any(|v| v.kind == PermissionOverwriteType::Member(author_id)))
+            .find(|ch| active_tickets.iter()
+                .find(|t| t.channel_id == ch.id.to_string())
+                .map_or(true, |t| t.opener_id == ticket.opener_id));
+        if let Some(ch) = active_ticket {
+            return Err(format!("You already have an active ticket. It's here <#{}>.", ch.id))
+        }
 
-                let _ = msg.channel_id.send_message(ctx.http.clone(), |m| {
-                    m.embed(|e| {
-                        e.title("Created a new ticket!");
-                        e.description(format!("Your ticket: <#{}>", c.id.0));
-                        e.color(EMBED_REGULAR_COLOR);
-                        e
-                    });
-                    m
-                });
-            }
+        let mut overwrites = vec![access_overwrite(PermissionOverwriteType::Member(author_id))];
+        // tickets with support roles are hidden from everyone else, the bot still has to see them
+        if !ticket_type.support_role_ids.is_empty() {
+            overwrites.extend(ticket_type.support_role_ids.iter()
+                .filter_map(|r| r.parse::<u64>().ok())
+                .map(|r| access_overwrite(PermissionOverwriteType::Role(RoleId(r)))));
+            overwrites.push(access_overwrite(PermissionOverwriteType::Member(UserId(gateway.bot_id()))));
+            overwrites.push(PermissionOverwrite {
+                allow: Permissions::empty(),
+                deny: Permissions::READ_MESSAGES,
+                kind: PermissionOverwriteType::Role(RoleId(guild_id))
+            });
+        }
+        let channel = NewChannel {
+            name: channel_name(&ticket_type.name_pattern, ticket.number, &ticket_type.name),
+            topic: ticket_topic(ticket),
+            category_id: Some(category_id),
+            overwrites,
+        };
+
+        let channel_id = match gateway.create_channel(guild_id, &channel) {
+            Ok(id) => id,
             Err(_) => return Err(format!("Could not create a new ticket. Check if tickets category is properly setup or use `{}setup tickets`!", prefix))
+        };
+
+        let greeting = match &ticket_type.welcome {
+            Some(w) => w.replace("{user}", &format!("<@{}>", ticket.opener_id)),
+            None => format!("Hi <@{}>! Someone from support team will help you out soon!", ticket.opener_id)
+        };
+        let mut welcome = format!("{} Type `{}solved` or react with ✅ to mark this ticket as solved.", greeting.trim_end(), prefix);
+        if let Some(subject) = &ticket.subject {
+            welcome.push_str(&format!("\n**Subject:** {}", subject));
+        }
+        if !ticket_type.questions.is_empty() {
+            welcome.push_str(&format!("\n\n**Please answer these questions:**\n{}", numbered(&ticket_type.questions)));
+        }
+        let result = gateway.send_embed(channel_id, &Embed::new("Ticket has been created!", &welcome, EMBED_REGULAR_COLOR));
+        if let Ok(message_id) = result {
+            let _ = gateway.add_reaction(channel_id, message_id, "✅");
         }
 
-        Ok(())
+        Ok(channel_id)
+    }
+}
+
+/// Type of tickets opened in the category made by `setup tickets`. It's not saved, so it has no id.
+pub fn default_ticket_type(category_id: u64) -> TicketType {
+    TicketType {
+        id: 0,
+        server_id: 0,
+        name: "ticket".to_string(),
+        emoji: PANEL_EMOJI.to_string(),
+        category_id: category_id.to_string(),
+        support_role_ids: Vec::new(),
+        name_pattern: DEFAULT_NAME_PATTERN.to_string(),
+        questions: Vec::new(),
+        welcome: None,
     }
 }
@@ -0,0 +1,93 @@
+use serenity::model::channel::{PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::id::UserId;
+use serenity::model::Permissions;
+use crate::database::models::Ticket;
+use crate::gateway::DiscordGateway;
+use crate::utils::db::{ServerInfo, get_ticket_by_channel};
+use chrono::{DateTime, Utc};
+
+/// Ticket of the channel the command was used in.
+pub fn current_ticket(info: &ServerInfo, channel_id: u64) -> Result<Ticket, String> {
+    get_ticket_by_channel(info, channel_id).ok_or_else(|| "This is not a ticket!".to_string())
+}
+
+pub fn ticket_topic(ticket: &Ticket) -> String {
+    let mut topic = format!(
+        "Ticket number: {}. Issued by: <@{}>, Creation Date: {}",
+        ticket.number,
+        ticket.opener_id,
+        DateTime::<Utc>::from_utc(ticket.creation_date, Utc).to_rfc2822()
+    );
+    if let Some(subject) = &ticket.subject {
+        topic.push_str(&format!(". Subject: {}", subject));
+    }
+    if let Some(claimed_by) = &ticket.claimed_by {
+        topic.push_str(&format!(". Claimed by: <@{}>", claimed_by));
+    }
+    topic
+}
+
+/// Lets the user see and write in the ticket.
+pub fn add_participant(gateway: &dyn DiscordGateway, channel_id: u64, user_id: u64) -> Result<(), String> {
+    let channel = gateway.channel(channel_id).ok_or_else(|| "This is not a ticket!".to_string())?;
+    if channel.overwrites.iter().any(|o| o.kind == PermissionOverwriteType::Member(UserId(user_id))) {
+        return Err(format!("<@{}> is already in this ticket!", user_id))
+    }
+
+    grant_access(gateway, channel_id, user_id)
+}
+
+/// Hides the ticket from the user. The user who opened the ticket can't be removed.
+pub fn remove_participant(gateway: &dyn DiscordGateway, ticket: &Ticket, user_id: u64) -> Result<(), String> {
+    if ticket.opener_id == user_id.to_string() {
+        return Err("The user who opened the ticket can't be removed from it!".to_string())
+    }
+    let channel_id = ticket.channel_id.parse::<u64>().unwrap();
+    let channel = gateway.channel(channel_id).ok_or_else(|| "This is not a ticket!".to_string())?;
+    if !channel.overwrites.iter().any(|o| o.kind == PermissionOverwriteType::Member(UserId(user_id))) {
+        return Err(format!("<@{}> is not in this ticket!", user_id))
+    }
+
+    gateway.remove_permission(channel_id, PermissionOverwriteType::Member(UserId(user_id)))
+        .map_err(|_| "Could not change permissions of the ticket. Check permissions!".to_string())
+}
+
+/// Makes the user the one handling the ticket, `None` leaves the ticket unclaimed. The previous claimer loses
+/// the access given by claiming. Returns the ticket with the new claimer.
+pub fn set_claim(gateway: &dyn DiscordGateway, ticket: &Ticket, claimer_id: Option<u64>) -> Result<Ticket, String> {
+    let channel_id = ticket.channel_id.parse::<u64>().unwrap();
+    if let Some(claimer_id) = claimer_id {
+        if ticket.opener_id == claimer_id.to_string() {
+            return Err("The user who opened the ticket can't handle it!".to_string())
+        }
+        grant_access(gateway, channel_id, claimer_id)?;
+    }
+    if let Some(previous) = ticket.claimed_by.as_ref().and_then(|c| c.parse::<u64>().ok()) {
+        if Some(previous) != claimer_id {
+            let _ = gateway.remove_permission(channel_id, PermissionOverwriteType::Member(UserId(previous)));
+        }
+    }
+
+    let mut claimed = ticket.clone();
+    claimed.claimed_by = claimer_id.map(|id| id.to_string());
+    // Discord allows only a couple of topic changes in a few minutes, the claim is kept either way
+    let _ = gateway.set_topic(channel_id, &ticket_topic(&claimed));
+    Ok(claimed)
+}
+
+/// Lets the member or role see and write in the ticket.
+pub fn access_overwrite(kind: PermissionOverwriteType) -> PermissionOverwrite {
+    let mut perms = Permissions::SEND_MESSAGES;
+    perms.insert(Permissions::READ_MESSAGES);
+    perms.insert(Permissions::ADD_REACTIONS);
+    PermissionOverwrite {
+        allow: perms,
+        deny: Permissions::empty(),
+        kind
+    }
+}
+
+fn grant_access(gateway: &dyn DiscordGateway, channel_id: u64, user_id: u64) -> Result<(), String> {
+    gateway.set_permission(channel_id, &access_overwrite(PermissionOverwriteType::Member(UserId(user_id))))
+        .map_err(|_| "Could not change permissions of the ticket. Check permissions!".to_string())
+}
@@ -1,47 +1,83 @@
-use serenity::model::channel::{Message, Channel, ReactionType, PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::channel::{PermissionOverwrite, PermissionOverwriteType};
 use serenity::model::user::User;
-use serenity::model::id::ChannelId;
+use serenity::model::id::{ChannelId, UserId};
 use serenity::model::Permissions;
-use serenity::prelude::Context;
-use crate::database::models::{Server, SpecialEntityType, TempOperation};
-use crate::database::schema::{servers, temp_operations};
-use crate::database::schema::temp_operations::columns::{id, action_type};
-use crate::diesel::{RunQueryDsl, BelongingToDsl, ExpressionMethods, QueryDsl, GroupedBy};
-use crate::utils::db::{ServerInfo, ActionType, create_action, get_special_entity_by_type, create_temp_operation};
-use crate::command::{Command, CommandArg, CommandConfig, EMBED_REGULAR_COLOR};
-use crate::database::get_db_con;
+use crate::database::models::{NewTicketTranscript, SpecialEntityType, TempOperation, TicketStatus};
+use crate::gateway::{ChannelInfo, DiscordGateway, Embed, MessageInfo};
+use crate::utils::db::{ServerInfo, ActionType, create_action, get_special_entity_by_type, create_temp_operation, create_ticket_transcript,
+    get_ticket_by_channel, set_ticket_status, get_ticket_type_by_id};
+use crate::utils::format_duration;
+use crate::command::{ArgOption, Command, CommandConfig, EMBED_REGULAR_COLOR, get_config, get_config_bool, get_config_duration};
+use crate::command::builder::CommandBuilder;
+use crate::scheduler::{register_handler, target_id};
+use super::transcript::{channel_history, html_transcript, text_transcript};
 use chrono::{Utc, Duration};
-use std::thread;
-use std::sync::Mutex;
-use std::time::Duration as StdDuration;
-use log::error;
 
 pub struct SolvedTicketCommand;
 
 impl SolvedTicketCommand {
-    pub fn solve(&self, ctx: &Context, channel_id: ChannelId, user: &User, info: &ServerInfo) -> Result<(), String> {
-        let ticket_category = match get_special_entity_by_type(info, SpecialEntityType::TicketsCategory) {
-            Some(cat_id) => cat_id.entity_id,
-            None => return Err(String::from("Tickets' category does not exist. Please use `!setup tickets`!"))
-        };
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("solved")
+            .desc("Marks current ticket as solved")
+            .config(CommandConfig::new("delete-delay", ArgOption::Time, "1h")
+                .desc("time after which solved ticket is removed."))
+            .config(CommandConfig::new("transcript-channel", ArgOption::Channel, "")
+                .desc("channel where transcripts of removed tickets are sent. Transcripts are saved either way."))
+            .config(CommandConfig::new("dm-transcript", ArgOption::Boolean, "false")
+                .desc("sends the transcript to the user who opened the ticket in direct message."))
+            .handler(|ctx, msg, info, _| {
+                SolvedTicketCommand.solve(ctx, msg.channel_id, &msg.author, info)?;
+                let _ = ctx.delete_message(msg.channel_id.0, msg.id.0);
+                Ok(())
+            })
+            .init(|_| register_handler(ActionType::SolvedTicket, |gateway, info, op| SolvedTicketCommand.close(gateway, info, op)))
+            .build()
+    }
 
-        let err_msg = String::from("This is not a ticket!");
-        let channel = if let Channel::Guild(ch) = ctx.cache.as_ref().read().channel(channel_id).unwrap() {
-            match ch.read().category_id {
-                Some(cat) => if cat.to_string() != ticket_category {
-                    return Err(err_msg)
-                } else {
-                    ch.read().clone()
-                }
-                None => return Err(err_msg)
+    pub fn solve(&self, gateway: &dyn DiscordGateway, channel_id: ChannelId, user: &User, info: &ServerInfo) -> Result<(), String> {
+        let ticket = get_ticket_by_channel(info, channel_id.0);
+        let ticket_category = match ticket.as_ref().and_then(|t| t.type_id).and_then(get_ticket_type_by_id) {
+            Some(ticket_type) => ticket_type.category_id.parse::<u64>().unwrap(),
+            None => match get_special_entity_by_type(info, SpecialEntityType::TicketsCategory) {
+                Some(cat_id) => cat_id.entity_id.parse::<u64>().unwrap(),
+                None => return Err(String::from("Tickets' category does not exist. Please use `!setup tickets`!"))
             }
-        } else {
-            return Err(err_msg)
         };
 
-        let mut user_id = user.id;
-        for p in channel.permission_overwrites.iter() {
+        let delete_delay = get_config_duration(info, "solved", "delete-delay").unwrap_or_else(|| Duration::hours(1));
+        let opener_id = ticket.as_ref().map(|t| UserId(t.opener_id.parse::<u64>().unwrap()));
+        let (owner_id, channel_name) = self.mark_solved(gateway, channel_id.0, opener_id, user.id, ticket_category, delete_delay)?;
+
+        create_action(
+            info,
+            owner_id.to_string(),
+            Some(channel_id.to_string()),
+            ActionType::SolvedTicket,
+            format!("{} has been solved by {}.", channel_name, user.name)
+        );
+        create_temp_operation(info, channel_id.to_string(), Utc::now() + delete_delay, ActionType::SolvedTicket);
+        if let Some(ticket) = ticket {
+            set_ticket_status(&ticket, TicketStatus::Solved);
+        }
+
+        Ok(())
+    }
+
+    /// Takes away the right to write from the ticket owner and offers to reopen the ticket. Tickets without `opener_id`
+    /// are owned by the user who has permissions in the channel. Returns the owner and name of the ticket.
+    pub fn mark_solved(&self, gateway: &dyn DiscordGateway, channel_id: u64, opener_id: Option<UserId>, solved_by: UserId, category_id: u64, delete_delay: Duration) -> Result<(UserId, String), String> {
+        let channel = match gateway.channel(channel_id) {
+            Some(ch) if ch.category_id == Some(category_id) => ch,
+            _ => return Err("This is not a ticket!".to_string())
+        };
+
+        let mut user_id = opener_id.unwrap_or(solved_by);
+        for p in channel.overwrites.iter() {
             if let PermissionOverwriteType::Member(m) = p.kind {
+                // users added to the ticket keep writing
+                if opener_id.map_or(false, |o| o != m) {
+                    continue
+                }
                 if p.deny == Permissions::SEND_MESSAGES {
                     return Err("Channel is already marked as solved!".to_string())
                 }
@@ -51,105 +87,84 @@ impl SolvedTicketCommand {
 
         let mut perms = Permissions::READ_MESSAGES;
         perms.insert(Permissions::ADD_REACTIONS);
-        let _ = channel_id.create_permission(&ctx.http, &PermissionOverwrite {
+        let _ = gateway.set_permission(channel_id, &PermissionOverwrite {
             allow: perms,
             deny: Permissions::SEND_MESSAGES,
             kind: PermissionOverwriteType::Member(user_id)
         });
 
-        create_action(
-            info,
-            user_id.to_string(),
-            Some(channel_id.to_string()),
-            ActionType::SolvedTicket,
-            format!("{} has been solved by {}.", channel.name, user.name)
-        );
-        create_temp_operation(info, channel_id.to_string(), Utc::now() + Duration::minutes(1), ActionType::SolvedTicket);
-
-        let final_msg = channel_id.send_message(&ctx.http, |m| {
-            m.embed(|e| {
-                e.title("Marked as solved!");
-                e.description("Ticket will be removed after 1 hour! If you wish to reopen the ticket react with ❎.");
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
-        });
+        let final_msg = gateway.send_embed(channel_id, &Embed::new(
+            "Marked as solved!",
+            &format!(
+                "Ticket will be removed after {}! If you wish to reopen the ticket react with ❎.",
+                format_duration(delete_delay)
+            ),
+            EMBED_REGULAR_COLOR
+        ));
 
         match final_msg {
-            Ok(m) => {
-                let _ = m.react(ctx.http.clone(), ReactionType::Unicode(String::from("❎")));
+            Ok(message_id) => {
+                let _ = gateway.add_reaction(channel_id, message_id, "❎");
             },
             Err(_) => return Err("Could not create a message.".to_string())
         }
 
-        Ok(())
-    }
-}
-
-impl Command for SolvedTicketCommand {
-    fn name(&self) -> String {
-        String::from("solved")
-    }
-
-    fn desc(&self) -> String {
-        String::from("Marks current ticket as solved")
-    }
-
-    fn use_in_dm(&self) -> bool {
-        false
+        Ok((user_id, channel.name))
     }
 
-    fn args(&self) -> Option<Vec<CommandArg>> {
-        None
-    }
+    pub fn close(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, op: &TempOperation) -> Result<(), String> {
+        let channel_id = target_id(op)?;
+        let ticket = get_ticket_by_channel(info, channel_id);
+        // ticket removed by hand has nothing to archive
+        if let Some(channel) = gateway.channel(channel_id) {
+            let opener_id = match &ticket {
+                Some(t) => t.opener_id.parse::<u64>().ok(),
+                None => channel.overwrites.iter().find_map(|o| match o.kind {
+                    PermissionOverwriteType::Member(user_id) => Some(user_id.0),
+                    _ => None
+                })
+            };
+            let messages = channel_history(gateway, channel_id, Utc::now().naive_utc())?;
+            if gateway.delete_channel(channel_id).is_err() {
+                return Err("Could not close the ticket".to_string())
+            }
+            self.archive(gateway, info, &channel, opener_id, &messages);
+        }
 
-    fn perms(&self) -> Option<Vec<String>> {
-        None
+        if let Some(ticket) = ticket {
+            set_ticket_status(&ticket, TicketStatus::Closed);
+        }
+        Ok(())
     }
 
-    fn config(&self) -> Option<Vec<CommandConfig>> {
-        None
-    }
+    /// Saves the transcript of the ticket and sends it to the transcripts channel and to the user who opened the ticket.
+    fn archive(&self, gateway: &dyn DiscordGateway, info: &ServerInfo, channel: &ChannelInfo, opener_id: Option<u64>, messages: &[MessageInfo]) {
+        let text = text_transcript(&channel.name, messages);
+        let html = html_transcript(&channel.name, messages);
+
+        if let Some(server) = &info.server {
+            create_ticket_transcript(&NewTicketTranscript {
+                server_id: server.id,
+                channel_id: channel.id.to_string(),
+                ticket_name: channel.name.to_owned(),
+                opener_id: opener_id.map(|id| id.to_string()),
+                text_content: text.to_owned(),
+                html_content: html.to_owned(),
+                creation_date: Utc::now().naive_utc(),
+            });
+        }
 
-    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
-        self.solve(ctx, msg.channel_id, &msg.author, info)?;
-        let _ = msg.delete(ctx.http.clone());
-        Ok(())
-    }
+        let files = vec![(format!("{}.html", channel.name), html), (format!("{}.txt", channel.name), text)];
+        if let Some(transcripts_channel) = get_config(info, "solved", "transcript-channel").and_then(|c| c.parse::<u64>().ok()) {
+            let opened_by = opener_id.map(|id| format!(", opened by <@{}>", id)).unwrap_or_default();
+            let message = format!("Transcript of **{}**{}. {} messages.", channel.name, opened_by, messages.len());
+            let _ = gateway.send_files(transcripts_channel, &files, &message);
+        }
 
-    fn init(&self, ctx: &Context) {
- let ctx = Mutex::new(ctx.clone());
-        thread::spawn(move || {
-            let db = get_db_con().get().expect("Could not get db pool!");
-            loop {
-                thread::sleep(StdDuration::from_secs(60));
-                let servers = servers::dsl::servers
-                    .load::<Server>(&db)
-                    .expect("Could not load servers!");
-
-                let tickets = TempOperation::belonging_to(&servers)
-                    .filter(action_type.eq(ActionType::SolvedTicket as i32))
-                    .load::<TempOperation>(&db)
-                    .expect("Could not load temp operations")
-                    .grouped_by(&servers);
-
-                let data = servers.into_iter().zip(tickets).collect::<Vec<_>>();
-
-                for v in data {
-                    for t in v.1 {
-                        if t.end_date < Utc::now().naive_utc() {
-                            let channel_id = t.target_id.parse::<u64>().unwrap();
-                            match &ctx.lock().unwrap().http.delete_channel(channel_id) {
-                                Ok(_) => {/* send dm */},
-                                Err(_) => error!("Could not close the ticket")
-                            }
-                            let _ = diesel::delete(temp_operations::table.filter(id.eq(t.id)))
-                                .execute(&db);
-                        }
-                    }
-                }
+        if let Some(opener_id) = opener_id.filter(|_| get_config_bool(info, "solved", "dm-transcript")) {
+            if let Ok(dm_channel) = gateway.dm_channel(opener_id) {
+                let _ = gateway.send_files(dm_channel, &files, &format!("Transcript of your ticket **{}**.", channel.name));
             }
-        });
+        }
     }
 }
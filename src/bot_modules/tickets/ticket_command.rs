@@ -0,0 +1,85 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, ActionType, create_action};
+use crate::utils::object_finding::get_member_from_id;
+use crate::gateway::{DiscordGateway, Embed};
+use super::participants::{add_participant, current_ticket, remove_participant};
+
+pub struct TicketCommand;
+
+impl TicketCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("ticket")
+            .desc("Users of the current ticket.")
+            .perms(&["tickets"])
+            .route(Route::new("lets the user see and write in the ticket.")
+                .literal("add")
+                .arg("<user>", ArgOption::User))
+            .route(Route::new("hides the ticket from the user.")
+                .literal("remove")
+                .arg("<user>", ArgOption::User))
+            .usage()
+            .handler(|ctx, msg, info, args| {
+                if args.has("add") {
+                    TicketCommand.add(ctx, msg, args, info)
+                } else {
+                    TicketCommand.remove(ctx, msg, args, info)
+                }
+            })
+            .build()
+    }
+
+    fn add(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let ticket = current_ticket(info, msg.channel_id.0)?;
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
+            Some(m) => m,
+            None => return Ok(())
+        };
+
+        add_participant(ctx, msg.channel_id.0, member.user_id().0)?;
+        create_action(
+            info,
+            msg.author.id.to_string(),
+            Some(msg.channel_id.to_string()),
+            ActionType::AddTicketUser,
+            format!("{} added {} to ticket-{}.", msg.author.name, member.display_name(), ticket.number)
+        );
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Ticket - Done!",
+            &format!("<@{}> has been added to the ticket.", member.user_id()),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+
+    fn remove(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let ticket = current_ticket(info, msg.channel_id.0)?;
+        let member = match get_member_from_id(ctx, msg, args.index("user").unwrap())? {
+            Some(m) => m,
+            None => return Ok(())
+        };
+        let user_id = member.user_id().0;
+        if ticket.claimed_by == Some(user_id.to_string()) {
+            return Err(format!("<@{}> is handling this ticket. Use `unclaim` or `assign` first!", user_id))
+        }
+
+        remove_participant(ctx, &ticket, user_id)?;
+        create_action(
+            info,
+            msg.author.id.to_string(),
+            Some(msg.channel_id.to_string()),
+            ActionType::RemoveTicketUser,
+            format!("{} removed {} from ticket-{}.", msg.author.name, member.display_name(), ticket.number)
+        );
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Ticket - Done!",
+            &format!("<@{}> has been removed from the ticket.", user_id),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::{Message, PermissionOverwriteType};
+use serenity::model::id::RoleId;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, get_ticket_types, get_ticket_type, update_ticket_type, delete_ticket_type};
+use crate::database::models::TicketType;
+use crate::bot_modules::automod::rules::parse_ids;
+use crate::gateway::{DiscordGateway, Embed};
+use super::participants::access_overwrite;
+
+/// Reactions of ticket types on the ticket panel, so there can't be more types than these.
+pub const TYPE_EMOJIS: [&str; 10] = ["1️⃣", "2️⃣", "3️⃣", "4️⃣", "5️⃣", "6️⃣", "7️⃣", "8️⃣", "9️⃣", "🔟"];
+pub const DEFAULT_NAME_PATTERN: &str = "{type}-{number}";
+
+pub struct TicketTypeCommand;
+
+impl TicketTypeCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("tickettype")
+            .desc("Types of tickets, each with its own category and support roles. New types are added with `setup ticket-type`.")
+            .perms(&["tickettype"])
+            .route(Route::new("sets roles that handle tickets of the type. Tickets opened later are visible only to them.")
+                .literal("roles")
+                .arg("<type>", ArgOption::Any)
+                .arg("<role...>", ArgOption::Any))
+            .route(Route::new("sets how ticket channels of the type are named. Placeholders: `{type}`, `{number}`. \
+                `{number}` is required.")
+                .literal("naming")
+                .arg("<type>", ArgOption::Any)
+                .arg("<pattern>", ArgOption::Any))
+            .route(Route::new("sets questions asked in new tickets of the type, separated by `|`. No questions turns them off.")
+                .literal("questions")
+                .arg("<type>", ArgOption::Any)
+                .arg("[question...]", ArgOption::Any))
+            .route(Route::new("sets the message new tickets of the type start with. `{user}` mentions the user. \
+                No message brings back the default one.")
+                .literal("welcome")
+                .arg("<type>", ArgOption::Any)
+                .arg("[message...]", ArgOption::Any))
+            .route(Route::new("removes the ticket type. Its category is kept for tickets that are still open.")
+                .literal("remove")
+                .arg("<type>", ArgOption::Any))
+            .route(Route::new("shows every ticket type."))
+            .usage()
+            .handler(|ctx, msg, info, args| {
+                if args.has("roles") {
+                    TicketTypeCommand.set_roles(ctx, msg, args, info)
+                } else if args.has("naming") {
+                    TicketTypeCommand.set_naming(ctx, msg, args, info)
+                } else if args.has("questions") {
+                    TicketTypeCommand.set_questions(ctx, msg, args, info)
+                } else if args.has("welcome") {
+                    TicketTypeCommand.set_welcome(ctx, msg, args, info)
+                } else if args.has("remove") {
+                    TicketTypeCommand.remove(ctx, msg, args, info)
+                } else {
+                    TicketTypeCommand.show(ctx, msg, info)
+                }
+            })
+            .build()
+    }
+
+    fn set_roles(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let mut ticket_type = find_type(info, &args)?;
+        let existing: Vec<u64> = ctx.roles(msg.guild_id.unwrap().0).iter().map(|r| r.id).collect();
+        let role_ids: Vec<u64> = parse_ids(&args.get_rest(msg, "role").unwrap()).into_iter()
+            .filter(|r| existing.contains(r))
+            .collect();
+        if role_ids.is_empty() {
+            return Err("Provide at least one role of the server!".to_string())
+        }
+
+        // roles keep their access to tickets that are already open
+        let category_id = ticket_type.category_id.parse::<u64>().unwrap();
+        for old in ticket_type.support_role_ids.iter().filter_map(|r| r.parse::<u64>().ok()) {
+            if !role_ids.contains(&old) {
+                let _ = ctx.remove_permission(category_id, PermissionOverwriteType::Role(RoleId(old)));
+            }
+        }
+        for role_id in role_ids.iter() {
+            if ctx.set_permission(category_id, &access_overwrite(PermissionOverwriteType::Role(RoleId(*role_id)))).is_err() {
+                return Err("Could not change permissions of the category. Check permissions!".to_string())
+            }
+        }
+
+        ticket_type.support_role_ids = role_ids.iter().map(|r| r.to_string()).collect();
+        update_ticket_type(&ticket_type);
+        let roles: Vec<String> = role_ids.iter().map(|r| format!("<@&{}>", r)).collect();
+        self.done(ctx, msg, &format!("Tickets of type `{}` are handled by {} now.", ticket_type.name, roles.join(", ")));
+        Ok(())
+    }
+
+    fn set_naming(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let mut ticket_type = find_type(info, &args)?;
+        let pattern = args.get_str("pattern").unwrap();
+        if !pattern.contains("{number}") {
+            return Err("Naming pattern has to contain `{number}`!".to_string())
+        }
+
+        ticket_type.name_pattern = pattern;
+        update_ticket_type(&ticket_type);
+        self.done(ctx, msg, &format!(
+            "Tickets of type `{}` are named like `{}` now.",
+            ticket_type.name,
+            channel_name(&ticket_type.name_pattern, 1, &ticket_type.name)
+        ));
+        Ok(())
+    }
+
+    fn set_questions(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let mut ticket_type = find_type(info, &args)?;
+        ticket_type.questions = parse_questions(&args.get_rest(msg, "question").unwrap_or_default());
+        update_ticket_type(&ticket_type);
+
+        let description = if ticket_type.questions.is_empty() {
+            format!("Tickets of type `{}` don't ask any questions now.", ticket_type.name)
+        } else {
+            format!("Tickets of type `{}` ask these questions now:\n{}", ticket_type.name, numbered(&ticket_type.questions))
+        };
+        self.done(ctx, msg, &description);
+        Ok(())
+    }
+
+    fn set_welcome(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let mut ticket_type = find_type(info, &args)?;
+        ticket_type.welcome = args.get_rest(msg, "message").filter(|m| !m.is_empty());
+        update_ticket_type(&ticket_type);
+
+        let description = match &ticket_type.welcome {
+            Some(welcome) => format!("Tickets of type `{}` start with:\n{}", ticket_type.name, welcome),
+            None => format!("Tickets of type `{}` start with the default message.", ticket_type.name)
+        };
+        self.done(ctx, msg, &description);
+        Ok(())
+    }
+
+    fn remove(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let ticket_type = find_type(info, &args)?;
+        delete_ticket_type(&ticket_type);
+        self.done(ctx, msg, &format!(
+            "Ticket type `{}` has been removed. Its category <#{}> is kept for tickets that are still open.",
+            ticket_type.name,
+            ticket_type.category_id
+        ));
+        Ok(())
+    }
+
+    fn show(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let ticket_types = get_ticket_types(info);
+        if ticket_types.is_empty() {
+            let prefix = &info.server.as_ref().unwrap().prefix;
+            return Err(format!("There are no ticket types! Use `{}setup ticket-type <name>` to add one.", prefix))
+        }
+
+        let lines: Vec<String> = ticket_types.iter()
+            .map(|t| {
+                let roles: Vec<String> = t.support_role_ids.iter().map(|r| format!("<@&{}>", r)).collect();
+                format!(
+                    "{} **{}** in <#{}>, handled by {}, {} questions",
+                    t.emoji,
+                    t.name,
+                    t.category_id,
+                    roles.join(", "),
+                    t.questions.len()
+                )
+            })
+            .collect();
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Ticket types", &lines.join("\n"), EMBED_REGULAR_COLOR));
+        Ok(())
+    }
+
+    fn done(&self, ctx: &Context, msg: &Message, description: &str) {
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new("Ticket type - Done!", description, EMBED_REGULAR_COLOR));
+    }
+}
+
+fn find_type(info: &ServerInfo, args: &ParsedArgs) -> Result<TicketType, String> {
+    let name = args.get_str("type").unwrap().to_lowercase();
+    get_ticket_type(info, &name).ok_or_else(|| format!("Ticket type `{}` does not exist!", name))
+}
+
+/// Type names are single words, so they can be told apart from the subject in `new <type> [subject...]`.
+pub fn valid_type_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 32 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// The first emoji no other type has.
+pub fn next_emoji(ticket_types: &[TicketType]) -> Option<&'static str> {
+    TYPE_EMOJIS.iter()
+        .find(|e| !ticket_types.iter().any(|t| t.emoji == **e))
+        .copied()
+}
+
+/// Name of the ticket channel made from the naming pattern of its type.
+pub fn channel_name(pattern: &str, number: i32, type_name: &str) -> String {
+    pattern.replace("{type}", type_name)
+        .replace("{number}", &number.to_string())
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// Splits the type from the subject when the subject starts with a name of a ticket type.
+pub fn pick_ticket_type(ticket_types: &[TicketType], subject: Option<String>) -> (Option<&TicketType>, Option<String>) {
+    let subject = match subject {
+        Some(s) => s,
+        None => return (None, None)
+    };
+
+    let mut words = subject.splitn(2, char::is_whitespace);
+    let first = words.next().unwrap_or_default().to_lowercase();
+    let rest = words.next().map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    match ticket_types.iter().find(|t| t.name == first) {
+        Some(t) => (Some(t), rest),
+        None => (None, Some(subject))
+    }
+}
+
+pub fn parse_questions(text: &str) -> Vec<String> {
+    text.split('|')
+        .map(|q| q.trim().to_string())
+        .filter(|q| !q.is_empty())
+        .collect()
+}
+
+pub fn numbered(lines: &[String]) -> String {
+    lines.iter()
+        .enumerate()
+        .map(|(i, line)| format!("{}. {}", i + 1, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
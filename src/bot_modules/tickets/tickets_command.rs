@@ -0,0 +1,82 @@
+use crate::command::{ArgOption, Command, ParsedArgs, EMBED_REGULAR_COLOR};
+use crate::command::builder::{CommandBuilder, Route};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, get_active_tickets};
+use crate::utils::format_duration;
+use crate::database::models::{Ticket, TicketStatus};
+use crate::gateway::{DiscordGateway, Embed};
+use chrono::{NaiveDateTime, Utc};
+
+// keeps the list within a single embed
+const MAX_LISTED: usize = 20;
+
+pub struct TicketsCommand;
+
+impl TicketsCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("tickets")
+            .desc("Tickets of the server.")
+            .perms(&["tickets"])
+            .route(Route::new("lists tickets that are not closed yet. `mine` lists tickets you opened or claimed.")
+                .literal("list")
+                .arg("[open/solved/mine]", ArgOption::Any))
+            .usage()
+            .handler(|ctx, msg, info, args| TicketsCommand.list(ctx, msg, args, info))
+            .build()
+    }
+
+    fn list(&self, ctx: &Context, msg: &Message, args: ParsedArgs, info: &ServerInfo) -> Result<(), String> {
+        let filter = args.get_str("open/solved/mine");
+        let user_id = msg.author.id.to_string();
+        let tickets: Vec<Ticket> = get_active_tickets(info).into_iter()
+            .filter(|t| ticket_matches(t, filter.as_ref().map(|f| f.as_str()), &user_id))
+            .collect();
+        if tickets.is_empty() {
+            return Err("There are no such tickets!".to_string())
+        }
+
+        let now = Utc::now().naive_utc();
+        let mut lines: Vec<String> = tickets.iter().take(MAX_LISTED).map(|t| ticket_line(t, now)).collect();
+        if tickets.len() > MAX_LISTED {
+            lines.push(format!("...and {} more", tickets.len() - MAX_LISTED));
+        }
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            &format!("Tickets ({})", tickets.len()),
+            &lines.join("\n"),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+}
+
+/// Checks the ticket against `open`, `solved` or `mine` filter. Every ticket matches when there is no filter.
+pub fn ticket_matches(ticket: &Ticket, filter: Option<&str>, user_id: &str) -> bool {
+    match filter {
+        Some("open") => ticket.status == TicketStatus::Open as i32,
+        Some("solved") => ticket.status == TicketStatus::Solved as i32,
+        Some("mine") => ticket.opener_id == user_id || ticket.claimed_by.as_ref().map_or(false, |c| c == user_id),
+        _ => true,
+    }
+}
+
+pub fn ticket_line(ticket: &Ticket, now: NaiveDateTime) -> String {
+    let mut line = format!(
+        "**#{}** <#{}> by <@{}>, opened {} ago",
+        ticket.number,
+        ticket.channel_id,
+        ticket.opener_id,
+        format_duration(now - ticket.creation_date)
+    );
+    if ticket.status == TicketStatus::Solved as i32 {
+        line.push_str(", solved");
+    }
+    if let Some(claimed_by) = &ticket.claimed_by {
+        line.push_str(&format!(", claimed by <@{}>", claimed_by));
+    }
+    if let Some(subject) = &ticket.subject {
+        line.push_str(&format!(" - {}", subject));
+    }
+    line
+}
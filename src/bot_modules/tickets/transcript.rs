@@ -0,0 +1,118 @@
+use crate::gateway::{DiscordGateway, Embed, MessageInfo};
+use crate::bot_modules::moderation::purge_command::{date_id, message_date};
+use chrono::NaiveDateTime;
+
+// tickets are short, but the history has to end somewhere
+const MAX_MESSAGES: usize = 5000;
+
+/// Messages of the channel sent before `now`, the oldest first.
+pub fn channel_history(gateway: &dyn DiscordGateway, channel_id: u64, now: NaiveDateTime) -> Result<Vec<MessageInfo>, String> {
+    let mut before = date_id(now);
+    let mut history = Vec::new();
+    while history.len() < MAX_MESSAGES {
+        let page = gateway.messages(channel_id, before, 100)?;
+        if page.is_empty() {
+            break;
+        }
+        before = page.last().unwrap().id;
+        history.extend(page);
+    }
+
+    history.reverse();
+    Ok(history)
+}
+
+pub fn text_transcript(ticket_name: &str, messages: &[MessageInfo]) -> String {
+    let mut text = format!("Transcript of {} - {} messages\n\n", ticket_name, messages.len());
+    for m in messages.iter() {
+        text.push_str(&format!("[{}] {} ({}): {}\n", message_time(m.id), m.author_name, m.author_id, indent(&m.content)));
+        for url in m.attachments.iter() {
+            text.push_str(&format!("    Attachment: {}\n", url));
+        }
+        for e in m.embeds.iter() {
+            let parts: Vec<&str> = vec![e.title.as_str(), e.description.as_str()].into_iter()
+                .filter(|p| !p.is_empty())
+                .collect();
+            text.push_str(&format!("    Embed: {}\n", indent(&parts.join(" | "))));
+        }
+    }
+    text
+}
+
+/// Self-contained HTML page that looks like the Discord channel.
+pub fn html_transcript(ticket_name: &str, messages: &[MessageInfo]) -> String {
+    let mut html = format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{name}</title>
+<style>
+body {{ background: #36393f; color: #dcddde; font-family: Helvetica, Arial, sans-serif; margin: 0; padding: 16px; }}
+.message {{ margin: 12px 0; }}
+.author {{ color: #fff; font-weight: bold; }}
+.bot {{ background: #7289da; border-radius: 3px; color: #fff; font-size: 10px; margin-left: 4px; padding: 1px 4px; }}
+.date {{ color: #72767d; font-size: 12px; margin-left: 6px; }}
+.content {{ white-space: pre-wrap; word-wrap: break-word; }}
+.embed {{ background: #2f3136; border-left: 4px solid; border-radius: 4px; margin-top: 4px; max-width: 520px; padding: 8px 12px; }}
+.embed-title {{ color: #fff; font-weight: bold; }}
+a {{ color: #00b0f4; }}
+</style>
+</head>
+<body>
+<h2>{name}</h2>
+<p>{count} messages</p>
+"#, name = escape_html(ticket_name), count = messages.len());
+
+    for m in messages.iter() {
+        html.push_str("<div class=\"message\">\n");
+        html.push_str(&format!(
+            "<span class=\"author\" title=\"{}\">{}</span>{}<span class=\"date\">{}</span>\n",
+            m.author_id,
+            escape_html(&m.author_name),
+            if m.bot { "<span class=\"bot\">BOT</span>" } else { "" },
+            message_time(m.id)
+        ));
+        if !m.content.is_empty() {
+            html.push_str(&format!("<div class=\"content\">{}</div>\n", escape_html(&m.content)));
+        }
+        for url in m.attachments.iter() {
+            html.push_str(&format!("<div><a href=\"{0}\">{0}</a></div>\n", escape_html(url)));
+        }
+        for e in m.embeds.iter() {
+            html.push_str(&html_embed(e));
+        }
+        html.push_str("</div>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn html_embed(embed: &Embed) -> String {
+    let mut html = format!("<div class=\"embed\" style=\"border-color: #{:06x}\">\n", embed.color as u32 & 0xff_ffff);
+    if !embed.title.is_empty() {
+        html.push_str(&format!("<div class=\"embed-title\">{}</div>\n", escape_html(&embed.title)));
+    }
+    if !embed.description.is_empty() {
+        html.push_str(&format!("<div class=\"content\">{}</div>\n", escape_html(&embed.description)));
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+fn message_time(message_id: u64) -> String {
+    message_date(message_id).format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+// keeps following lines of a message under the first one
+fn indent(text: &str) -> String {
+    text.replace('\n', "\n    ")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
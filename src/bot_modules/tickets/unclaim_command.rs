@@ -0,0 +1,44 @@
+use crate::command::{Command, EMBED_REGULAR_COLOR};
+use crate::command::builder::CommandBuilder;
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use crate::utils::db::{ServerInfo, ActionType, create_action, set_ticket_claim};
+use crate::gateway::{DiscordGateway, Embed};
+use super::participants::{current_ticket, set_claim};
+
+pub struct UnclaimCommand;
+
+impl UnclaimCommand {
+    pub fn command() -> Box<dyn Command> {
+        CommandBuilder::new("unclaim")
+            .desc("Leaves the current ticket for someone else from the support team.")
+            .perms(&["tickets"])
+            .handler(|ctx, msg, info, _| UnclaimCommand.unclaim(ctx, msg, info))
+            .build()
+    }
+
+    fn unclaim(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        let ticket = current_ticket(info, msg.channel_id.0)?;
+        let claimed_by = match &ticket.claimed_by {
+            Some(c) => c.to_owned(),
+            None => return Err("This ticket is not claimed!".to_string())
+        };
+
+        set_claim(ctx, &ticket, None)?;
+        set_ticket_claim(&ticket, None);
+        create_action(
+            info,
+            msg.author.id.to_string(),
+            Some(msg.channel_id.to_string()),
+            ActionType::UnClaimTicket,
+            format!("{} unclaimed ticket-{} that was claimed by {}.", msg.author.name, ticket.number, claimed_by)
+        );
+
+        let _ = ctx.send_embed(msg.channel_id.0, &Embed::new(
+            "Ticket unclaimed!",
+            &format!("<@{}> is not handling this ticket anymore. Someone else can `claim` it.", claimed_by),
+            EMBED_REGULAR_COLOR
+        ));
+        Ok(())
+    }
+}
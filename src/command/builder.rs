@@ -0,0 +1,342 @@
+use super::cooldown::{Cooldown, CooldownBucket, MAX_COOLDOWN};
+use super::{get_args, parse_args, closest_route, ArgOption, Command, CommandArg, CommandConfig, ParsedArgs};
+use crate::bot_modules::main::help_command::HelpCommand;
+use crate::config::DEFAULT_PREFIX;
+use crate::utils::db::ServerInfo;
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+pub type CommandHandler = fn(&Context, &Message, &ServerInfo, ParsedArgs) -> Result<(), String>;
+pub type InitHandler = fn(&Context);
+
+/// Single usage of a command, e.g. `<user> [time] [reason...]`.
+pub struct Route {
+    desc: String,
+    args: Vec<(String, Option<ArgOption>)>,
+}
+
+impl Route {
+    pub fn new(desc: &str) -> Route {
+        Route {
+            desc: desc.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Variable argument like `<user>` or `[time]`.
+    pub fn arg(mut self, name: &str, option: ArgOption) -> Route {
+        self.args.push((name.to_string(), Some(option)));
+        self
+    }
+
+    /// Argument that has to be typed as it is, e.g. `add` or `reduce-warns`.
+    pub fn literal(mut self, name: &str) -> Route {
+        self.args.push((name.to_string(), None));
+        self
+    }
+
+    pub(crate) fn build(self) -> Result<CommandArg, String> {
+        let last = self.args.len();
+        for (i, (name, option)) in self.args.iter().enumerate() {
+            let required = name.starts_with("<") && name.ends_with(">");
+            let optional = name.starts_with("[") && name.ends_with("]");
+            let variable = required || optional;
+
+            if name.trim().is_empty() {
+                return Err("argument name can't be empty".to_string());
+            }
+            if !variable && (name.contains(char::is_whitespace) || name.contains(|c: char| "<>[]".contains(c))) {
+                return Err(format!("`{}` is not a valid argument name", name));
+            }
+            if variable && option.is_none() {
+                return Err(format!("`{}` is missing an argument type", name));
+            }
+            if !variable && option.is_some() {
+                return Err(format!("`{}` is a literal and can't have an argument type", name));
+            }
+            if (name.ends_with("...>") || name.ends_with("...]")) && i + 1 != last {
+                return Err(format!("`{}` takes the rest of the message so it has to be the last argument", name));
+            }
+            // optional argument is skipped only when its type check fails
+            if optional && i + 1 != last && option == &Some(ArgOption::Any) {
+                return Err(format!("`{}` can't be followed by other arguments unless it has a specific type", name));
+            }
+        }
+
+        let mut next: Option<Box<CommandArg>> = None;
+        for (i, (name, option)) in self.args.into_iter().enumerate().rev() {
+            let arg = CommandArg {
+                name,
+                desc: if i == 0 { Some(self.desc.to_owned()) } else { None },
+                option,
+                next,
+            };
+            next = Some(Box::new(arg));
+        }
+
+        Ok(match next {
+            Some(arg) => *arg,
+            None => CommandArg {
+                name: String::new(),
+                desc: Some(self.desc),
+                option: None,
+                next: None,
+            },
+        })
+    }
+}
+
+/// Declares a command in one place and generates its `Command` implementation.
+pub struct CommandBuilder {
+    name: String,
+    desc: String,
+    use_in_dm: bool,
+    perms: Option<Vec<String>>,
+    aliases: Vec<String>,
+    cooldowns: Vec<Cooldown>,
+    configs: Vec<CommandConfig>,
+    routes: Vec<Route>,
+    show_usage: bool,
+    handler: Option<CommandHandler>,
+    init: Option<InitHandler>,
+}
+
+impl CommandBuilder {
+    pub fn new(name: &str) -> CommandBuilder {
+        CommandBuilder {
+            name: name.to_string(),
+            desc: String::new(),
+            use_in_dm: false,
+            perms: None,
+            aliases: Vec::new(),
+            cooldowns: Vec::new(),
+            configs: Vec::new(),
+            routes: Vec::new(),
+            show_usage: false,
+            handler: None,
+            init: None,
+        }
+    }
+
+    pub fn desc(mut self, desc: &str) -> CommandBuilder {
+        self.desc = desc.to_string();
+        self
+    }
+
+    pub fn use_in_dm(mut self, use_in_dm: bool) -> CommandBuilder {
+        self.use_in_dm = use_in_dm;
+        self
+    }
+
+    pub fn perms(mut self, perms: &[&str]) -> CommandBuilder {
+        self.perms = Some(perms.iter().map(|p| p.to_string()).collect());
+        self
+    }
+
+    pub fn aliases(mut self, aliases: &[&str]) -> CommandBuilder {
+        self.aliases = aliases.iter().map(|a| a.to_lowercase()).collect();
+        self
+    }
+
+    /// Setting that servers can change with `config` command.
+    pub fn config(mut self, config: CommandConfig) -> CommandBuilder {
+        self.configs.push(config);
+        self
+    }
+
+    /// Default cooldown for the bucket. Servers can override it with `config` or `command` command.
+    pub fn cooldown(mut self, bucket: CooldownBucket, seconds: i64) -> CommandBuilder {
+        self.cooldowns.retain(|c| c.bucket != bucket);
+        self.cooldowns.push(Cooldown { bucket, seconds });
+        self
+    }
+
+    /// Routes are matched in the same order they were added.
+    /// Route without arguments is used when command is called without any.
+    pub fn route(mut self, route: Route) -> CommandBuilder {
+        self.routes.push(route);
+        self
+    }
+
+    /// Shows usage information when command is called without arguments.
+    pub fn usage(mut self) -> CommandBuilder {
+        self.routes.push(Route::new("shows usage information"));
+        self.show_usage = true;
+        self
+    }
+
+    pub fn handler(mut self, handler: CommandHandler) -> CommandBuilder {
+        self.handler = Some(handler);
+        self
+    }
+
+    pub fn init(mut self, init: InitHandler) -> CommandBuilder {
+        self.init = Some(init);
+        self
+    }
+
+    pub(crate) fn try_build(self) -> Result<BuiltCommand, String> {
+        let name = self.name;
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(format!("`{}` is not a valid command name", name));
+        }
+
+        if let Some(a) = self.aliases.iter().find(|a| a.is_empty() || a.contains(char::is_whitespace) || **a == name) {
+            return Err(format!("Command `{}`: `{}` is not a valid alias", name, a));
+        }
+
+        let mut configs = self.configs;
+        for (i, c) in configs.iter().enumerate() {
+            if c.name.is_empty() || c.name.contains(char::is_whitespace) || configs[..i].iter().any(|o| o.name == c.name) {
+                return Err(format!("Command `{}`: `{}` is not a valid config name", name, c.name));
+            }
+            // empty default means the setting is not set
+            if !c.default.is_empty() {
+                if let Err(why) = c.parse(&c.default) {
+                    return Err(format!("Command `{}`: {}", name, why));
+                }
+            }
+        }
+
+        for bucket in CooldownBucket::all().iter() {
+            let seconds = self.cooldowns.iter().find(|c| c.bucket == *bucket).map_or(0, |c| c.seconds);
+            configs.push(
+                CommandConfig::new(&bucket.config_name(), ArgOption::Numeric, &seconds.to_string())
+                    .desc(&format!("cooldown in seconds for every {}. 0 turns it off.", bucket.name()))
+                    .max(MAX_COOLDOWN as f64)
+            );
+        }
+
+        let handler = match self.handler {
+            Some(h) => h,
+            None => return Err(format!("Command `{}` has no handler", name)),
+        };
+
+        let mut routes = Vec::new();
+        for r in self.routes.into_iter() {
+            match r.build() {
+                Ok(arg) => routes.push(arg),
+                Err(why) => return Err(format!("Command `{}`: {}", name, why)),
+            }
+        }
+
+        Ok(BuiltCommand {
+            name,
+            desc: self.desc,
+            use_in_dm: self.use_in_dm,
+            perms: self.perms,
+            aliases: self.aliases,
+            configs,
+            routes,
+            show_usage: self.show_usage,
+            handler,
+            init: self.init,
+        })
+    }
+
+    /// Panics when the command is malformed. Every command is built on start-up so mistakes show up right away.
+    pub fn build(self) -> Box<dyn Command> {
+        match self.try_build() {
+            Ok(cmd) => Box::new(cmd),
+            Err(why) => panic!("{}", why),
+        }
+    }
+}
+
+pub struct BuiltCommand {
+    name: String,
+    desc: String,
+    use_in_dm: bool,
+    perms: Option<Vec<String>>,
+    aliases: Vec<String>,
+    configs: Vec<CommandConfig>,
+    routes: Vec<CommandArg>,
+    show_usage: bool,
+    handler: CommandHandler,
+    init: Option<InitHandler>,
+}
+
+impl BuiltCommand {
+    // points the user to the usage they most likely wanted
+    fn args_error(&self, why: String, args: &Vec<String>, info: &ServerInfo) -> String {
+        let prefix = match &info.server {
+            Some(s) => s.prefix.to_owned(),
+            None => DEFAULT_PREFIX.to_string(),
+        };
+
+        match closest_route(&self.routes, args) {
+            Some(route) => format!(
+                "{}\n\n**Did you mean:**\n{}\nUse `{}help {}` to see every usage.",
+                why,
+                route.help_line(&prefix, &self.name),
+                prefix,
+                self.name
+            ),
+            None => why,
+        }
+    }
+}
+
+impl Command for BuiltCommand {
+    fn name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    fn desc(&self) -> String {
+        self.desc.to_owned()
+    }
+
+    fn use_in_dm(&self) -> bool {
+        self.use_in_dm
+    }
+
+    fn args(&self) -> Option<Vec<CommandArg>> {
+        if self.routes.is_empty() {
+            None
+        } else {
+            Some(self.routes.clone())
+        }
+    }
+
+    fn perms(&self) -> Option<Vec<String>> {
+        self.perms.clone()
+    }
+
+    fn config(&self) -> Option<Vec<CommandConfig>> {
+        Some(self.configs.clone())
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        self.aliases.clone()
+    }
+
+    fn exe(&self, ctx: &Context, msg: &Message, info: &ServerInfo) -> Result<(), String> {
+        if self.routes.is_empty() {
+            return (self.handler)(ctx, msg, info, ParsedArgs::default());
+        }
+
+        let args = get_args(msg.clone(), false);
+        let parsed = match parse_args(&self.routes, &args) {
+            Ok(parsed) => parsed,
+            Err(why) => return Err(self.args_error(why, &args, info)),
+        };
+
+        match parsed {
+            Some(parsed) => (self.handler)(ctx, msg, info, parsed),
+            None => {
+                if self.show_usage {
+                    let help_cmd = HelpCommand {};
+                    help_cmd.show_cmd_details(ctx, msg, info, self.name())
+                } else {
+                    (self.handler)(ctx, msg, info, ParsedArgs::default())
+                }
+            }
+        }
+    }
+
+    fn init(&self, ctx: &Context) {
+        if let Some(init) = self.init {
+            init(ctx);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// Longest cooldown in seconds, one week.
+pub const MAX_COOLDOWN: i64 = 7 * 24 * 60 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CooldownBucket {
+    User,
+    Channel,
+    Guild,
+}
+
+impl CooldownBucket {
+    pub fn all() -> [CooldownBucket; 3] {
+        [CooldownBucket::User, CooldownBucket::Channel, CooldownBucket::Guild]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CooldownBucket::User => "user",
+            CooldownBucket::Channel => "channel",
+            CooldownBucket::Guild => "guild",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<CooldownBucket> {
+        CooldownBucket::all().iter().find(|b| b.name() == name).copied()
+    }
+
+    /// Name of the `CommandConfig` that overrides this bucket, e.g. `cooldown-user`.
+    pub fn config_name(&self) -> String {
+        format!("cooldown-{}", self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cooldown {
+    pub bucket: CooldownBucket,
+    pub seconds: i64,
+}
+
+/// Ends of active cooldowns. Key is command name, bucket and id of the user/channel/guild.
+#[derive(Default)]
+pub struct Cooldowns {
+    ends: HashMap<(String, CooldownBucket, u64), DateTime<Utc>>,
+}
+
+impl Cooldowns {
+    /// Time left until the command can be used again.
+    pub fn remaining(&self, cmd_name: &str, bucket: CooldownBucket, id: u64, now: DateTime<Utc>) -> Option<Duration> {
+        let end = self.ends.get(&(cmd_name.to_string(), bucket, id))?;
+        if *end > now {
+            Some(*end - now)
+        } else {
+            None
+        }
+    }
+
+    pub fn start(&mut self, cmd_name: &str, cooldown: &Cooldown, id: u64, now: DateTime<Utc>) {
+        self.ends.retain(|_, end| *end > now);
+        if cooldown.seconds <= 0 {
+            return
+        }
+        let seconds = Duration::seconds(cooldown.seconds.min(MAX_COOLDOWN));
+        if let Some(end) = now.checked_add_signed(seconds) {
+            self.ends.insert((cmd_name.to_string(), cooldown.bucket, id), end);
+        }
+    }
+}
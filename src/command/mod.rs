@@ -0,0 +1,877 @@
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, RoleId, UserId};
+use serenity::prelude::Context;
+use crate::bot_modules::{get_modules, PROTECTED_MODULES};
+use crate::utils::db::{ServerInfo, get_command_config, command_disabled};
+use cooldown::{Cooldown, CooldownBucket, MAX_COOLDOWN};
+use crate::utils::{get_time, get_duration, is_time_format, edit_distance};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+pub mod builder;
+pub mod cooldown;
+
+pub const EMBED_REGULAR_COLOR: i32 = 714968;
+pub const EMBED_QUESTION_COLOR: i32 = 16772147;
+pub const EMBED_ERROR_COLOR: i32 = 13632773;
+
+/// Per-server setting of a command, e.g. `delete-delay` of `solved`.
+/// Values are stored as text so `option` and `values` are checked before saving.
+#[derive(Clone)]
+pub struct CommandConfig {
+    pub name: String,
+    pub desc: String,
+    pub option: ArgOption,
+    pub default: String,
+    // allowed values, empty means anything that fits `option`
+    pub values: Vec<String>,
+    // highest allowed number of `Numeric` settings
+    pub max: Option<f64>,
+}
+
+impl CommandConfig {
+    pub fn new(name: &str, option: ArgOption, default: &str) -> CommandConfig {
+        CommandConfig {
+            name: name.to_string(),
+            desc: String::new(),
+            option,
+            default: default.to_string(),
+            values: Vec::new(),
+            max: None,
+        }
+    }
+
+    pub fn desc(mut self, desc: &str) -> CommandConfig {
+        self.desc = desc.to_string();
+        self
+    }
+
+    pub fn values(mut self, values: &[&str]) -> CommandConfig {
+        self.values = values.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    pub fn max(mut self, max: f64) -> CommandConfig {
+        self.max = Some(max);
+        self
+    }
+
+    /// Checks the value and returns it in the form it is stored in, e.g. `<#123>` becomes `123`.
+    pub fn parse(&self, value: &str) -> Result<String, String> {
+        if !self.values.is_empty() {
+            if self.values.iter().any(|v| v == value) {
+                return Ok(value.to_string());
+            }
+            return Err(format!("`{}` has to be one of: {}", self.name, self.values.join(", ")));
+        }
+
+        let arg = CommandArg {
+            name: format!("<{}>", self.name),
+            desc: None,
+            option: Some(self.option.to_owned()),
+            next: None,
+        };
+
+        match convert_arg(&arg, value) {
+            ArgValue::Numeric(n) => match self.max {
+                Some(max) if n > max => Err(format!("`{}` can be at most {}!", self.name, max)),
+                _ => Ok(n.to_string()),
+            },
+            ArgValue::Boolean(b) => Ok(b.to_string()),
+            ArgValue::User(id) => Ok(id.0.to_string()),
+            ArgValue::Role(id) => Ok(id.0.to_string()),
+            ArgValue::Channel(id) => Ok(id.0.to_string()),
+            ArgValue::Time(_, _) => Ok(value.to_string()),
+            ArgValue::Text(_) => match self.option {
+                ArgOption::Text | ArgOption::Any => Ok(value.to_string()),
+                _ => Err(format!("`{}` is not a valid value for `{}`!", value, self.name)),
+            },
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, PartialEq)]
+pub enum ArgOption {
+    Numeric,
+    Text,
+    Boolean,
+    Any,
+    Role,
+    Channel,
+    ChannelId,
+    User,
+    UserId,
+    Time
+}
+
+#[derive(Clone)]
+pub struct CommandArg {
+    pub name: String,
+    pub desc: Option<String>,
+    pub option: Option<ArgOption>,
+    pub next: Option<Box<CommandArg>>,
+}
+
+pub trait Command {
+    fn name(&self) -> String;
+    fn desc(&self) -> String;
+    fn use_in_dm(&self) -> bool;
+    fn args(&self) -> Option<Vec<CommandArg>>;
+    fn perms(&self) -> Option<Vec<String>>;
+    fn config(&self) -> Option<Vec<CommandConfig>>;
+    fn aliases(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn exe(&self, ctx: &Context, msg: &Message, server: &ServerInfo) -> Result<(), String>;
+    fn init(&self, _ctx: &Context) {}
+}
+
+impl dyn Command {
+    pub fn disabled(&self, info: &ServerInfo, channel_id: String) -> bool {
+        command_disabled(info, &self.name(), &channel_id) && !is_command_protected(&self.name()).unwrap()
+    }
+
+    /// Value set by the server or the default one. `None` when command has no such config.
+    pub fn config_value(&self, info: &ServerInfo, name: &str) -> Option<String> {
+        let config = self.config()?.into_iter().find(|c| c.name == name)?;
+        match get_command_config(info, &self.name(), name) {
+            Some(value) if config.parse(&value).is_ok() => Some(value),
+            _ => Some(config.default),
+        }
+    }
+
+    /// Cooldowns with server overrides applied. Cooldowns set to 0 are skipped.
+    pub fn active_cooldowns(&self, info: &ServerInfo) -> Vec<Cooldown> {
+        CooldownBucket::all()
+            .iter()
+            .filter_map(|bucket| {
+                let seconds = parse_number(&self.config_value(info, &bucket.config_name())?)?.min(MAX_COOLDOWN as f64) as i64;
+                if seconds > 0 {
+                    Some(Cooldown { bucket: *bucket, seconds })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub value: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+fn is_escapable(c: char) -> bool {
+    c == '"' || c == '\'' || c == '\\' || c == '`' || c.is_whitespace()
+}
+
+// returns index of the quote that closes the one at `i`
+fn closing_quote(chars: &Vec<(usize, char)>, i: usize) -> Option<usize> {
+    let quote = chars[i].1;
+    if quote != '"' && quote != '\'' {
+        return None;
+    }
+
+    let mut j = i + 1;
+    while j < chars.len() {
+        let c = chars[j].1;
+        if c == '\\' && quote == '"' {
+            j += 2;
+            continue;
+        }
+        if c == quote && (j + 1 == chars.len() || chars[j + 1].1.is_whitespace()) {
+            return Some(j);
+        }
+        j += 1;
+    }
+
+    None
+}
+
+// returns index right after the backticks that close the code-span at `i`
+fn closing_code_span(chars: &Vec<(usize, char)>, i: usize) -> Option<usize> {
+    let mut ticks = 0;
+    while i + ticks < chars.len() && chars[i + ticks].1 == '`' {
+        ticks += 1;
+    }
+
+    let mut j = i + ticks;
+    while j < chars.len() {
+        if chars[j].1 != '`' {
+            j += 1;
+            continue;
+        }
+
+        let mut run = 0;
+        while j + run < chars.len() && chars[j + run].1 == '`' {
+            run += 1;
+        }
+        if run == ticks {
+            return Some(j + run);
+        }
+        j += run;
+    }
+
+    None
+}
+
+/// Splits message content into arguments. Double/single quotes group words into one argument,
+/// backslash escapes quotes, backticks and whitespace, code-spans are kept as they are.
+pub fn tokenize(content: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = content.char_indices().collect();
+    let byte_at = |i: usize| if i < chars.len() { chars[i].0 } else { content.len() };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].1.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let mut value = String::new();
+        if let Some(end) = closing_quote(&chars, i) {
+            let quote = chars[i].1;
+            let mut j = i + 1;
+            while j < end {
+                let c = chars[j].1;
+                if c == '\\' && quote == '"' && j + 1 < end && is_escapable(chars[j + 1].1) {
+                    value.push(chars[j + 1].1);
+                    j += 2;
+                    continue;
+                }
+                value.push(c);
+                j += 1;
+            }
+            i = end + 1;
+        } else {
+            while i < chars.len() && !chars[i].1.is_whitespace() {
+                let c = chars[i].1;
+                if c == '\\' && i + 1 < chars.len() && is_escapable(chars[i + 1].1) {
+                    value.push(chars[i + 1].1);
+                    i += 2;
+                    continue;
+                }
+                if c == '`' {
+                    if let Some(end) = closing_code_span(&chars, i) {
+                        chars[i..end].iter().for_each(|(_, ch)| value.push(*ch));
+                        i = end;
+                        continue;
+                    }
+                }
+                value.push(c);
+                i += 1;
+            }
+        }
+
+        tokens.push(Token {
+            value,
+            start: byte_at(start),
+            end: byte_at(i),
+        });
+    }
+
+    tokens
+}
+
+/// Reverses `tokenize`, quoting arguments when it's needed.
+pub fn join_args(args: &Vec<String>) -> String {
+    args.iter()
+        .map(|a| {
+            if a.is_empty() || a.contains(char::is_whitespace) || a.starts_with('"') || a.starts_with('\'') {
+                format!("\"{}\"", a.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                a.to_owned()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// number of tokens taken by the prefix mention and the command name
+fn command_len(content: &str, tokens: &Vec<Token>) -> usize {
+    let len = if content.trim_start().starts_with("<@") { 2 } else { 1 };
+    if len > tokens.len() {
+        tokens.len()
+    } else {
+        len
+    }
+}
+
+pub fn args_from_content(content: &str, include_cmd: bool) -> Vec<String> {
+    let tokens = tokenize(content);
+    let skip = if include_cmd { 0 } else { command_len(content, &tokens) };
+
+    tokens[skip..].iter().map(|t| t.value.to_owned()).collect()
+}
+
+pub fn get_args(msg: Message, include_cmd: bool) -> Vec<String> {
+    args_from_content(&msg.content, include_cmd)
+}
+
+/// Index of the first command argument in `get_args(msg, true)`.
+pub fn args_offset(msg: &Message) -> usize {
+    command_len(&msg.content, &tokenize(&msg.content))
+}
+
+/// Returns the original text starting at the `from` argument (command excluded).
+/// Used by `...` arguments so the text is not re-joined with single spaces.
+pub fn raw_args_from_content(content: &str, from: usize) -> String {
+    let tokens = tokenize(content);
+    let index = command_len(content, &tokens) + from;
+    if index >= tokens.len() {
+        return String::new();
+    }
+
+    content[tokens[index].start..].trim_end().to_string()
+}
+
+pub fn get_raw_args(msg: &Message, from: usize) -> String {
+    raw_args_from_content(&msg.content, from)
+}
+
+// if Ok(true) it will skip this route
+fn check_option(arg: &CommandArg, message: &str) -> Result<bool, String> {
+    if let Some(op) = &arg.option {
+        match op {
+            ArgOption::Numeric => {
+                if parse_number(message).is_none() {
+                    return Ok(true);
+                }
+            }
+            ArgOption::Text => {
+                if parse_number(message).is_some() {
+                    return Ok(true);
+                }
+            }
+            ArgOption::Boolean => match message {
+                "yes" | "no" | "true" | "false" | "enable" | "disable" => {}
+                _ => return Ok(true),
+            },
+            ArgOption::Role => {
+                if message.starts_with("<@&") && message.ends_with(">") {
+                    if message.len() != 22 {
+                        return Ok(true);
+                    }
+                    if message[3..message.len()-1].parse::<u64>().is_err() {
+                        return Ok(true);
+                    }
+                }
+            },
+            ArgOption::Channel | ArgOption::ChannelId => {
+                if message.starts_with("<#") && message.ends_with(">") {
+                    if message.len() != 21 {
+                        return Ok(true);
+                    }
+                    if message[2..message.len()-1].parse::<u64>().is_err() {
+                        return Ok(true);
+                    }
+                } else if op.to_owned() == ArgOption::ChannelId {
+                    if message.len() != 18 {
+                        return Ok(true);
+                    }
+                    if message.parse::<u64>().is_err() {
+                        return Ok(true);
+                    }
+                }
+            },
+            ArgOption::User | ArgOption::UserId => {
+                if message.starts_with("<@!") && message.ends_with(">") {
+                    if message.len() != 22 {
+                        return Ok(true);
+                    }
+                    if message[3..message.len()-1].parse::<u64>().is_err() {
+                        return Ok(true);
+                    }
+                } else if message.starts_with("<@") && message.ends_with(">") {
+                    if message.len() != 21 {
+                        return Ok(true);
+                    }
+                    if message[2..message.len()-1].parse::<u64>().is_err() {
+                        return Ok(true);
+                    }
+                } else if op.to_owned() == ArgOption::UserId {
+                    if message.len() != 18 {
+                        return Ok(true);
+                    }
+                    if message.parse::<u64>().is_err() {
+                        return Ok(true);
+                    }
+                }
+            },
+            // invalid time like `-5m` is an error, not the next argument
+            ArgOption::Time => {
+                if !is_time_format(message) {
+                    return Ok(true);
+                }
+                get_time(message)?;
+            },
+            ArgOption::Any => {}
+        }
+    }
+
+    if arg.name.contains("/") {
+        let mut name = arg.name.to_owned();
+        name.remove(0);
+        name.remove(name.len() - 1);
+        let options: Vec<&str> = name.split("/").collect();
+        if !options.contains(&message) {
+            return Err(format!("Invalid argument `{}`", message));
+        }
+    }
+
+    Ok(false)
+}
+
+impl CommandArg {
+    fn is_arg_variable(&self) -> bool {
+        self.name.starts_with("<") && self.name.ends_with(">") ||
+            self.name.starts_with("[") && self.name.ends_with("]")
+    }
+
+    fn is_optional(&self) -> bool {
+        self.name.starts_with("[") && self.name.ends_with("]")
+    }
+
+    fn accepts_more(&self) -> bool {
+        self.name.ends_with("...>") || self.name.ends_with("...]")
+    }
+
+    /// Whole route as it is typed, e.g. `.ban <user> [time] [reason...]`.
+    pub fn usage(&self, prefix: &str, cmd_name: &str) -> String {
+        let mut usage = format!("{}{}", prefix, cmd_name);
+        let mut next_arg = Some(self);
+        while let Some(a) = next_arg {
+            if !a.name.is_empty() {
+                usage.push_str(&format!(" {}", a.name));
+            }
+            next_arg = a.next.as_deref();
+        }
+        usage
+    }
+
+    /// Usage line shown in the command details, e.g. `**.ban <user> [time] [reason...]** - bans user`.
+    pub fn help_line(&self, prefix: &str, cmd_name: &str) -> String {
+        format!(
+            "**{}** - {}\n",
+            self.usage(prefix, cmd_name),
+            self.desc.as_ref().unwrap_or(&String::new())
+        )
+    }
+
+    fn fits(&self, message: &str) -> bool {
+        if self.is_arg_variable() {
+            check_option(self, message) == Ok(false)
+        } else {
+            self.name == message || is_similar(&self.name, message)
+        }
+    }
+
+    /// Name used as a key in `ParsedArgs`. `<reason...>` becomes `reason`.
+    pub fn key(&self) -> String {
+        if !self.is_arg_variable() {
+            return self.name.to_owned();
+        }
+
+        self.name[1..self.name.len() - 1].trim_end_matches("...").to_string()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgValue {
+    Numeric(f64),
+    Text(String),
+    Boolean(bool),
+    User(UserId),
+    Role(RoleId),
+    Channel(ChannelId),
+    Time(DateTime<Utc>, Duration),
+}
+
+#[derive(Clone, Debug)]
+pub struct ParsedArg {
+    pub index: usize,
+    pub raw: String,
+    pub value: ArgValue,
+}
+
+/// Arguments matched by `parse_args`, keyed by `CommandArg::key`.
+#[derive(Clone, Debug, Default)]
+pub struct ParsedArgs {
+    route: Vec<String>,
+    values: HashMap<String, ParsedArg>,
+    message_args: Vec<String>,
+}
+
+fn parse_id(message: &str, prefixes: &[&str]) -> Option<u64> {
+    if let Ok(id) = message.parse::<u64>() {
+        return Some(id);
+    }
+
+    for p in prefixes.iter() {
+        if message.starts_with(p) && message.ends_with(">") {
+            if let Ok(id) = message[p.len()..message.len() - 1].parse::<u64>() {
+                return Some(id);
+            }
+        }
+    }
+
+    None
+}
+
+// `NaN` and `inf` are not numbers users mean
+fn parse_number(message: &str) -> Option<f64> {
+    message.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+fn convert_arg(arg: &CommandArg, message: &str) -> ArgValue {
+    let text = ArgValue::Text(message.to_owned());
+    match &arg.option {
+        Some(ArgOption::Numeric) => match parse_number(message) {
+            Some(num) => ArgValue::Numeric(num),
+            None => text,
+        },
+        Some(ArgOption::Boolean) => match message {
+            "yes" | "true" | "enable" => ArgValue::Boolean(true),
+            "no" | "false" | "disable" => ArgValue::Boolean(false),
+            _ => text,
+        },
+        Some(ArgOption::User) | Some(ArgOption::UserId) => match parse_id(message, &["<@!", "<@"]) {
+            Some(id) => ArgValue::User(UserId(id)),
+            None => text,
+        },
+        Some(ArgOption::Role) => match parse_id(message, &["<@&"]) {
+            Some(id) => ArgValue::Role(RoleId(id)),
+            None => text,
+        },
+        Some(ArgOption::Channel) | Some(ArgOption::ChannelId) => match parse_id(message, &["<#"]) {
+            Some(id) => ArgValue::Channel(ChannelId(id)),
+            None => text,
+        },
+        Some(ArgOption::Time) => match get_duration(message) {
+            Ok(duration) => ArgValue::Time(Utc::now() + duration, duration),
+            Err(_) => text,
+        },
+        _ => text,
+    }
+}
+
+impl ParsedArgs {
+    fn push(&mut self, arg: &CommandArg, message_args: &Vec<String>) {
+        let index = self.route.len();
+        let raw = if arg.accepts_more() {
+            message_args[index..].join(" ")
+        } else {
+            message_args[index].to_owned()
+        };
+
+        let value = if arg.accepts_more() {
+            ArgValue::Text(raw.to_owned())
+        } else {
+            convert_arg(arg, &raw)
+        };
+
+        self.route.push(arg.name.to_owned());
+        self.values.insert(arg.key(), ParsedArg { index, raw, value });
+    }
+
+    /// Number of matched route arguments.
+    pub fn len(&self) -> usize {
+        self.route.len()
+    }
+
+    /// Names of the matched route arguments (e.g. `<user>`, `[time]`).
+    pub fn route(&self) -> &Vec<String> {
+        &self.route
+    }
+
+    /// Name of the first matched route argument. Useful for sub-commands like `add`/`remove`.
+    pub fn first(&self) -> &str {
+        match self.route.first() {
+            Some(name) => name.as_str(),
+            None => "",
+        }
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Index of the argument in `get_args(msg, false)`. Used by `object_finding`.
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.values.get(name).map(|a| a.index)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ArgValue> {
+        self.values.get(name).map(|a| &a.value)
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<String> {
+        self.values.get(name).map(|a| a.raw.to_owned())
+    }
+
+    /// Every message argument starting with `name`. Meant for `...` arguments.
+    pub fn get_list(&self, name: &str) -> Option<Vec<String>> {
+        self.index(name).map(|i| self.message_args[i..].to_vec())
+    }
+
+    /// Original text of a `...` argument, with its whitespace and newlines preserved.
+    pub fn get_rest(&self, msg: &Message, name: &str) -> Option<String> {
+        self.index(name).map(|i| get_raw_args(msg, i))
+    }
+
+    pub fn get_num(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Some(ArgValue::Numeric(num)) => Some(*num),
+            _ => None,
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name) {
+            Some(ArgValue::Boolean(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn get_user(&self, name: &str) -> Option<UserId> {
+        match self.get(name) {
+            Some(ArgValue::User(id)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn get_role(&self, name: &str) -> Option<RoleId> {
+        match self.get(name) {
+            Some(ArgValue::Role(id)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn get_channel(&self, name: &str) -> Option<ChannelId> {
+        match self.get(name) {
+            Some(ArgValue::Channel(id)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn get_time(&self, name: &str) -> Option<DateTime<Utc>> {
+        match self.get(name) {
+            Some(ArgValue::Time(date, _)) => Some(*date),
+            _ => None,
+        }
+    }
+
+    pub fn get_duration(&self, name: &str) -> Option<Duration> {
+        match self.get(name) {
+            Some(ArgValue::Time(_, duration)) => Some(*duration),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_args(
+    args: &Vec<CommandArg>,
+    message_args: &Vec<String>,
+) -> Result<Option<ParsedArgs>, String> {
+    'main: for a in args.iter() {
+        let mut depth = 0;
+        let mut route = ParsedArgs {
+            message_args: message_args.to_owned(),
+            ..ParsedArgs::default()
+        };
+
+        if message_args.len() == 0 {
+            return Ok(None);
+        }
+
+        if !a.is_arg_variable() {
+            if a.name != message_args[0] {
+                continue;
+            }
+        } else {
+            if check_option(&a, message_args[depth].as_str())? {
+                continue;
+            }
+        }
+
+        route.push(a, message_args);
+
+        let mut next_arg = a.next.as_ref();
+        'nextArg: while next_arg.is_some() {
+            let na = next_arg.unwrap();
+            if depth >= message_args.len() - 1 {
+                if na.is_optional() {
+                    break;
+                }
+                continue 'main;
+            }
+
+            if !na.is_arg_variable() {
+                if na.name != message_args[depth + 1] {
+                    continue 'main;
+                }
+            } else {
+                if check_option(&na, message_args[depth + 1].as_str())? {
+                    if na.is_optional() {
+                        next_arg = na.next.as_ref();
+                        continue 'nextArg;
+                    } else {
+                        continue 'main;
+                    }
+                }
+            }
+
+            route.push(na, message_args);
+
+            depth += 1;
+            next_arg = na.next.as_ref();
+        }
+
+        if route.len() == message_args.len() {
+            return Ok(Some(route));
+        } else if route.len() < message_args.len() {
+            if let Some(name) = route.route().last() {
+                if name.ends_with("...>") || name.ends_with("...]") {
+                    return Ok(Some(route));
+                }
+            }
+        }
+    }
+
+    Err(String::from("Invalid arguments!"))
+}
+
+/// Route that is the closest to provided arguments. Used to show expected usage when `parse_args` fails.
+pub fn closest_route<'a>(args: &'a Vec<CommandArg>, message_args: &Vec<String>) -> Option<&'a CommandArg> {
+    let mut closest: Option<(&CommandArg, usize, usize)> = None;
+    for a in args.iter() {
+        if a.name.is_empty() {
+            continue;
+        }
+
+        // leading arguments that fit the route (literals count more) and the difference in length
+        let mut matched = 0;
+        let mut fitting = true;
+        let mut len = 0;
+        let mut next_arg = Some(a);
+        while let Some(na) = next_arg {
+            fitting = fitting && len < message_args.len() && na.fits(&message_args[len]);
+            if fitting {
+                matched += if na.is_arg_variable() { 1 } else { 2 };
+            }
+            len += 1;
+            next_arg = na.next.as_deref();
+        }
+        let diff = (len as i64 - message_args.len() as i64).abs() as usize;
+
+        closest = match closest {
+            Some((_, m, d)) if m > matched || (m == matched && d <= diff) => closest,
+            _ => Some((a, matched, diff)),
+        };
+    }
+
+    closest.map(|(a, _, _)| a)
+}
+
+fn is_similar(a: &str, b: &str) -> bool {
+    let max_distance = match a.chars().count() {
+        0..=2 => 0,
+        3 => 1,
+        4..=6 => 2,
+        _ => 3,
+    };
+
+    edit_distance(a, b) <= max_distance
+}
+
+/// Closest name to the typed one. Returns `None` when nothing is similar enough.
+pub fn suggest_name<'a>(name: &str, candidates: &'a [String]) -> Option<&'a String> {
+    candidates.iter()
+        .filter(|c| is_similar(c, name))
+        .min_by_key(|c| edit_distance(c, name))
+}
+
+// returns lower case command name typed after the prefix, e.g. `ban` for `.Ban @user`.
+// Prefix has to match exactly
+pub fn get_command_name(content: &str, prefix: &str) -> Option<String> {
+    if !content.starts_with(prefix) {
+        return None;
+    }
+
+    content[prefix.len()..]
+        .split_whitespace()
+        .next()
+        .map(|n| n.to_lowercase())
+}
+
+// built-in names and aliases always win over server aliases
+pub fn resolve_command_name(name: &str, info: &ServerInfo) -> Option<String> {
+    for m in get_modules() {
+        for c in m.commands() {
+            if c.name() == name || c.aliases().iter().any(|a| a == name) {
+                return Some(c.name());
+            }
+        }
+    }
+
+    if let Some(aliases) = &info.aliases {
+        for a in aliases.iter() {
+            if a.alias == name {
+                return Some(a.command_name.to_owned());
+            }
+        }
+    }
+
+    None
+}
+
+pub fn get_config(info: &ServerInfo, cmd_name: &str, name: &str) -> Option<String> {
+    for m in get_modules() {
+        for c in m.commands() {
+            if c.name() == cmd_name {
+                return c.config_value(info, name);
+            }
+        }
+    }
+    None
+}
+
+pub fn get_config_bool(info: &ServerInfo, cmd_name: &str, name: &str) -> bool {
+    get_config(info, cmd_name, name).map_or(false, |v| v == "true")
+}
+
+// empty value means there is no duration
+pub fn get_config_duration(info: &ServerInfo, cmd_name: &str, name: &str) -> Option<Duration> {
+    get_duration(&get_config(info, cmd_name, name)?).ok()
+}
+
+pub fn find_command(name: &str, info: &ServerInfo) -> Result<Box<dyn Command>, String> {
+    for m in get_modules() {
+        for c in m.commands() {
+            if c.name() == name {
+                if m.enabled(info) {
+                    return Ok(c)
+                } else {
+                    return Err("Command is in disabled module!".to_string())
+                }
+            }
+        }
+    }
+    Err("Command does not exist!".to_string())
+}
+
+pub fn is_command_protected(name: &str) -> Result<bool, String> {
+    for m in get_modules() {
+        for c in m.commands() {
+            if c.name() == name {
+                return Ok(PROTECTED_MODULES.contains(&m.name().as_str()))
+            }
+        }
+    }
+    Err("Command does not exist!".to_string())
+}
@@ -1,4 +1,4 @@
-use super::schema::{roles, servers, commands, actions, temp_operations, special_entities};
+use super::schema::{roles, servers, commands, command_aliases, command_configs, actions, temp_operations, special_entities, tags, warn_thresholds, filtered_words, locked_channels, sticky_roles, ticket_transcripts, ticket_types, tickets};
 use chrono::NaiveDateTime;
 
 #[derive(Identifiable, Queryable, Clone)]
@@ -8,6 +8,8 @@ pub struct Server {
     pub guildid: String,
     pub prefix: String,
     pub enabledmodules: Vec<String>,
+    pub last_case_id: i32,
+    pub last_ticket_number: i32,
 }
 
 #[derive(Insertable)]
@@ -55,6 +57,46 @@ pub struct NewDBCommand {
     pub disabled_channels: Vec<String>
 }
 
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "command_aliases"]
+pub struct CommandAlias {
+    pub id: i32,
+    pub server_id: i32,
+    pub alias: String,
+    pub command_name: String,
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "command_aliases"]
+pub struct NewCommandAlias {
+    pub server_id: i32,
+    pub alias: String,
+    pub command_name: String,
+}
+
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "command_configs"]
+pub struct DBCommandConfig {
+    pub id: i32,
+    pub server_id: i32,
+    pub command_name: String,
+    pub config_name: String,
+    pub value: String,
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "command_configs"]
+pub struct NewDBCommandConfig {
+    pub server_id: i32,
+    pub command_name: String,
+    pub config_name: String,
+    pub value: String,
+}
+
 #[derive(Identifiable, Queryable, Associations, Clone, QueryableByName)]
 #[belongs_to(Server, foreign_key = "server_id")]
 #[table_name = "actions"]
@@ -65,7 +107,12 @@ pub struct Action {
     pub issuer: String,
     pub target: Option<String>,
     pub message: String,
-    pub creation_date: NaiveDateTime
+    pub creation_date: NaiveDateTime,
+    pub case_id: Option<i32>,
+    pub reason: Option<String>,
+    pub log_channel_id: Option<String>,
+    pub log_message_id: Option<String>,
+    pub expired: bool
 }
 
 #[derive(Insertable, Associations)]
@@ -77,7 +124,9 @@ pub struct NewAction {
     pub issuer: String,
     pub target: Option<String>,
     pub message: String,
-    pub creation_date: NaiveDateTime
+    pub creation_date: NaiveDateTime,
+    pub case_id: Option<i32>,
+    pub reason: Option<String>
 }
 
 #[derive(Identifiable, Queryable, Associations, Clone)]
@@ -88,7 +137,9 @@ pub struct TempOperation {
     pub server_id: i32,
     pub action_type: i32,
     pub target_id: String,
-    pub end_date: NaiveDateTime
+    pub end_date: NaiveDateTime,
+    pub attempts: i32,
+    pub last_error: Option<String>
 }
 
 #[derive(Insertable, Associations)]
@@ -107,6 +158,7 @@ pub enum SpecialEntityType {
     ModLogsChannel = 1,
     MuteRole = 2,
     TicketsCategory = 3,
+    TicketPanel = 4,
 }
 
 #[derive(Identifiable, Queryable, Associations, Clone)]
@@ -127,3 +179,206 @@ pub struct NewSpecialEntity {
     pub entity_type: i32,
     pub entity_id: String,
 }
+
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "tags"]
+pub struct Tag {
+    pub id: i32,
+    pub server_id: i32,
+    pub name: String,
+    pub content: String,
+    pub owner: String,
+    pub uses: i32,
+    pub creation_date: NaiveDateTime
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "tags"]
+pub struct NewTag {
+    pub server_id: i32,
+    pub name: String,
+    pub content: String,
+    pub owner: String,
+    pub creation_date: NaiveDateTime
+}
+
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "warn_thresholds"]
+pub struct WarnThreshold {
+    pub id: i32,
+    pub server_id: i32,
+    pub warn_level: i32,
+    pub action: String,
+    pub duration: Option<String>
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "warn_thresholds"]
+pub struct NewWarnThreshold {
+    pub server_id: i32,
+    pub warn_level: i32,
+    pub action: String,
+    pub duration: Option<String>
+}
+
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "filtered_words"]
+pub struct FilteredWord {
+    pub id: i32,
+    pub server_id: i32,
+    pub pattern: String,
+    pub is_regex: bool
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "filtered_words"]
+pub struct NewFilteredWord {
+    pub server_id: i32,
+    pub pattern: String,
+    pub is_regex: bool
+}
+
+/// Channel locked by lockdown. `allow` and `deny` are its `@everyone` permissions from before the lockdown,
+/// both are `None` when the channel had no such overwrite.
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "locked_channels"]
+pub struct LockedChannel {
+    pub id: i32,
+    pub server_id: i32,
+    pub channel_id: String,
+    pub allow: Option<i64>,
+    pub deny: Option<i64>
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "locked_channels"]
+pub struct NewLockedChannel {
+    pub server_id: i32,
+    pub channel_id: String,
+    pub allow: Option<i64>,
+    pub deny: Option<i64>
+}
+
+/// Roles the member had when leaving the server.
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "sticky_roles"]
+pub struct StickyRoles {
+    pub id: i32,
+    pub server_id: i32,
+    pub user_id: String,
+    pub role_ids: Vec<String>
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "sticky_roles"]
+pub struct NewStickyRoles {
+    pub server_id: i32,
+    pub user_id: String,
+    pub role_ids: Vec<String>
+}
+
+/// Messages of a closed ticket.
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "ticket_transcripts"]
+pub struct TicketTranscript {
+    pub id: i32,
+    pub server_id: i32,
+    pub channel_id: String,
+    pub ticket_name: String,
+    pub opener_id: Option<String>,
+    pub text_content: String,
+    pub html_content: String,
+    pub creation_date: NaiveDateTime
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "ticket_transcripts"]
+pub struct NewTicketTranscript {
+    pub server_id: i32,
+    pub channel_id: String,
+    pub ticket_name: String,
+    pub opener_id: Option<String>,
+    pub text_content: String,
+    pub html_content: String,
+    pub creation_date: NaiveDateTime
+}
+
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "ticket_types"]
+pub struct TicketType {
+    pub id: i32,
+    pub server_id: i32,
+    pub name: String,
+    pub emoji: String,
+    pub category_id: String,
+    pub support_role_ids: Vec<String>,
+    pub name_pattern: String,
+    pub questions: Vec<String>,
+    pub welcome: Option<String>
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "ticket_types"]
+pub struct NewTicketType {
+    pub server_id: i32,
+    pub name: String,
+    pub emoji: String,
+    pub category_id: String,
+    pub support_role_ids: Vec<String>,
+    pub name_pattern: String,
+    pub questions: Vec<String>,
+    pub welcome: Option<String>
+}
+
+#[derive(Clone, Copy)]
+pub enum TicketStatus {
+    Open = 0,
+    Solved = 1,
+    Closed = 2,
+}
+
+#[derive(Identifiable, Queryable, Associations, Clone)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "tickets"]
+pub struct Ticket {
+    pub id: i32,
+    pub server_id: i32,
+    pub number: i32,
+    pub channel_id: String,
+    pub opener_id: String,
+    pub claimed_by: Option<String>,
+    pub status: i32,
+    pub subject: Option<String>,
+    pub creation_date: NaiveDateTime,
+    pub solved_date: Option<NaiveDateTime>,
+    pub closed_date: Option<NaiveDateTime>,
+    pub type_id: Option<i32>
+}
+
+#[derive(Insertable, Associations)]
+#[belongs_to(Server, foreign_key = "server_id")]
+#[table_name = "tickets"]
+pub struct NewTicket {
+    pub server_id: i32,
+    pub number: i32,
+    pub channel_id: String,
+    pub opener_id: String,
+    pub status: i32,
+    pub subject: Option<String>,
+    pub creation_date: NaiveDateTime,
+    pub type_id: Option<i32>
+}
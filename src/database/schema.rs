@@ -7,6 +7,30 @@ table! {
         target -> Nullable<Varchar>,
         message -> Varchar,
         creation_date -> Timestamp,
+        case_id -> Nullable<Int4>,
+        reason -> Nullable<Varchar>,
+        log_channel_id -> Nullable<Varchar>,
+        log_message_id -> Nullable<Varchar>,
+        expired -> Bool,
+    }
+}
+
+table! {
+    command_aliases (id) {
+        id -> Int4,
+        server_id -> Int4,
+        alias -> Varchar,
+        command_name -> Varchar,
+    }
+}
+
+table! {
+    command_configs (id) {
+        id -> Int4,
+        server_id -> Int4,
+        command_name -> Varchar,
+        config_name -> Varchar,
+        value -> Varchar,
     }
 }
 
@@ -19,6 +43,25 @@ table! {
     }
 }
 
+table! {
+    filtered_words (id) {
+        id -> Int4,
+        server_id -> Int4,
+        pattern -> Varchar,
+        is_regex -> Bool,
+    }
+}
+
+table! {
+    locked_channels (id) {
+        id -> Int4,
+        server_id -> Int4,
+        channel_id -> Varchar,
+        allow -> Nullable<Int8>,
+        deny -> Nullable<Int8>,
+    }
+}
+
 table! {
     roles (id) {
         id -> Int4,
@@ -34,6 +77,8 @@ table! {
         guildid -> Varchar,
         prefix -> Varchar,
         enabledmodules -> Array<Text>,
+        last_case_id -> Int4,
+        last_ticket_number -> Int4,
     }
 }
 
@@ -46,6 +91,27 @@ table! {
     }
 }
 
+table! {
+    sticky_roles (id) {
+        id -> Int4,
+        server_id -> Int4,
+        user_id -> Varchar,
+        role_ids -> Array<Text>,
+    }
+}
+
+table! {
+    tags (id) {
+        id -> Int4,
+        server_id -> Int4,
+        name -> Varchar,
+        content -> Varchar,
+        owner -> Varchar,
+        uses -> Int4,
+        creation_date -> Timestamp,
+    }
+}
+
 table! {
     temp_operations (id) {
         id -> Int4,
@@ -53,14 +119,80 @@ table! {
         action_type -> Int4,
         target_id -> Varchar,
         end_date -> Timestamp,
+        attempts -> Int4,
+        last_error -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    ticket_transcripts (id) {
+        id -> Int4,
+        server_id -> Int4,
+        channel_id -> Varchar,
+        ticket_name -> Varchar,
+        opener_id -> Nullable<Varchar>,
+        text_content -> Text,
+        html_content -> Text,
+        creation_date -> Timestamp,
+    }
+}
+
+table! {
+    ticket_types (id) {
+        id -> Int4,
+        server_id -> Int4,
+        name -> Varchar,
+        emoji -> Varchar,
+        category_id -> Varchar,
+        support_role_ids -> Array<Text>,
+        name_pattern -> Varchar,
+        questions -> Array<Text>,
+        welcome -> Nullable<Text>,
+    }
+}
+
+table! {
+    tickets (id) {
+        id -> Int4,
+        server_id -> Int4,
+        number -> Int4,
+        channel_id -> Varchar,
+        opener_id -> Varchar,
+        claimed_by -> Nullable<Varchar>,
+        status -> Int4,
+        subject -> Nullable<Varchar>,
+        creation_date -> Timestamp,
+        solved_date -> Nullable<Timestamp>,
+        closed_date -> Nullable<Timestamp>,
+        type_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    warn_thresholds (id) {
+        id -> Int4,
+        server_id -> Int4,
+        warn_level -> Int4,
+        action -> Varchar,
+        duration -> Nullable<Varchar>,
     }
 }
 
 allow_tables_to_appear_in_same_query!(
     actions,
+    command_aliases,
+    command_configs,
     commands,
+    filtered_words,
+    locked_channels,
     roles,
     servers,
     special_entities,
+    sticky_roles,
+    tags,
     temp_operations,
+    ticket_transcripts,
+    ticket_types,
+    tickets,
+    warn_thresholds,
 );
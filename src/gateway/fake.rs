@@ -0,0 +1,214 @@
+use super::{ChannelInfo, DiscordGateway, Embed, MemberInfo, MessageInfo, NewChannel, RoleInfo};
+use serenity::model::channel::{ChannelType, PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::Permissions;
+use std::cell::{Cell, RefCell};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Call {
+    SendEmbed(u64, Embed),
+    SendMessage(u64, String),
+    EditEmbed(u64, u64, Embed),
+    /// Names of the sent files.
+    SendFiles(u64, Vec<String>),
+    SendDm(u64, Embed),
+    AddReaction(u64, u64, String),
+    DeleteMessage(u64, u64),
+    DeleteMessages(u64, Vec<u64>),
+    AddRole(u64, u64, u64),
+    RemoveRole(u64, u64, u64),
+    Ban(u64, u64, String),
+    Unban(u64, u64),
+    Kick(u64, u64),
+    CreateChannel(u64, String),
+    SetPermission(u64, PermissionOverwriteType, Permissions, Permissions),
+    RemovePermission(u64, PermissionOverwriteType),
+    SetSlowmode(u64, u64),
+    SetTopic(u64, String),
+    DeleteChannel(u64),
+}
+
+/// In-memory guild that records every call. Created channels and permission changes are applied to `channels`.
+#[derive(Default)]
+pub struct FakeGateway {
+    pub calls: RefCell<Vec<Call>>,
+    pub channels: RefCell<Vec<ChannelInfo>>,
+    /// Messages of every channel.
+    pub messages: RefCell<Vec<MessageInfo>>,
+    pub members: Vec<MemberInfo>,
+    pub roles: Vec<RoleInfo>,
+    pub bot_id: u64,
+    pub guild_name: Option<String>,
+    /// Makes every call that changes something fail.
+    pub failing: Cell<bool>,
+    last_id: Cell<u64>,
+}
+
+impl FakeGateway {
+    pub fn calls(&self) -> Vec<Call> {
+        self.calls.borrow().clone()
+    }
+
+    pub fn embeds(&self, channel_id: u64) -> Vec<Embed> {
+        self.calls.borrow().iter()
+            .filter_map(|c| match c {
+                Call::SendEmbed(id, embed) if *id == channel_id => Some(embed.to_owned()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn record(&self, call: Call) -> Result<u64, String> {
+        if self.failing.get() {
+            return Err("Fake failure".to_string())
+        }
+        self.calls.borrow_mut().push(call);
+        self.last_id.set(self.last_id.get() + 1);
+        Ok(self.last_id.get())
+    }
+}
+
+impl DiscordGateway for FakeGateway {
+    fn bot_id(&self) -> u64 {
+        self.bot_id
+    }
+
+    fn guild_name(&self, _: u64) -> Option<String> {
+        self.guild_name.clone()
+    }
+
+    fn send_embed(&self, channel_id: u64, embed: &Embed) -> Result<u64, String> {
+        self.record(Call::SendEmbed(channel_id, embed.to_owned()))
+    }
+
+    fn send_message(&self, channel_id: u64, content: &str) -> Result<u64, String> {
+        self.record(Call::SendMessage(channel_id, content.to_string()))
+    }
+
+    fn edit_embed(&self, channel_id: u64, message_id: u64, embed: &Embed) -> Result<(), String> {
+        self.record(Call::EditEmbed(channel_id, message_id, embed.to_owned())).map(|_| ())
+    }
+
+    fn send_files(&self, channel_id: u64, files: &[(String, String)], _: &str) -> Result<u64, String> {
+        self.record(Call::SendFiles(channel_id, files.iter().map(|(name, _)| name.to_owned()).collect()))
+    }
+
+    fn send_dm(&self, user_id: u64, embed: &Embed) -> Result<(), String> {
+        self.record(Call::SendDm(user_id, embed.to_owned())).map(|_| ())
+    }
+
+    // direct message channel has the same id as its user
+    fn dm_channel(&self, user_id: u64) -> Result<u64, String> {
+        if self.failing.get() {
+            return Err("Fake failure".to_string())
+        }
+        Ok(user_id)
+    }
+
+    fn add_reaction(&self, channel_id: u64, message_id: u64, emoji: &str) -> Result<(), String> {
+        self.record(Call::AddReaction(channel_id, message_id, emoji.to_string())).map(|_| ())
+    }
+
+    fn delete_message(&self, channel_id: u64, message_id: u64) -> Result<(), String> {
+        self.record(Call::DeleteMessage(channel_id, message_id))?;
+        self.messages.borrow_mut().retain(|m| m.id != message_id);
+        Ok(())
+    }
+
+    fn delete_messages(&self, channel_id: u64, message_ids: &[u64]) -> Result<(), String> {
+        if message_ids.len() < 2 || message_ids.len() > 100 {
+            return Err("Bulk delete needs 2 to 100 messages".to_string())
+        }
+        self.record(Call::DeleteMessages(channel_id, message_ids.to_vec()))?;
+        self.messages.borrow_mut().retain(|m| !message_ids.contains(&m.id));
+        Ok(())
+    }
+
+    fn messages(&self, _: u64, before: u64, limit: u64) -> Result<Vec<MessageInfo>, String> {
+        let mut messages: Vec<MessageInfo> = self.messages.borrow().iter()
+            .filter(|m| m.id < before)
+            .cloned()
+            .collect();
+        messages.sort_by(|a, b| b.id.cmp(&a.id));
+        messages.truncate(limit as usize);
+        Ok(messages)
+    }
+
+    fn add_role(&self, guild_id: u64, user_id: u64, role_id: u64) -> Result<(), String> {
+        self.record(Call::AddRole(guild_id, user_id, role_id)).map(|_| ())
+    }
+
+    fn remove_role(&self, guild_id: u64, user_id: u64, role_id: u64) -> Result<(), String> {
+        self.record(Call::RemoveRole(guild_id, user_id, role_id)).map(|_| ())
+    }
+
+    fn ban(&self, guild_id: u64, user_id: u64, reason: &str) -> Result<(), String> {
+        self.record(Call::Ban(guild_id, user_id, reason.to_string())).map(|_| ())
+    }
+
+    fn unban(&self, guild_id: u64, user_id: u64) -> Result<(), String> {
+        self.record(Call::Unban(guild_id, user_id)).map(|_| ())
+    }
+
+    fn kick(&self, guild_id: u64, user_id: u64) -> Result<(), String> {
+        self.record(Call::Kick(guild_id, user_id)).map(|_| ())
+    }
+
+    fn create_channel(&self, guild_id: u64, channel: &NewChannel) -> Result<u64, String> {
+        let id = self.record(Call::CreateChannel(guild_id, channel.name.to_owned()))?;
+        self.channels.borrow_mut().push(ChannelInfo {
+            id,
+            name: channel.name.to_owned(),
+            kind: ChannelType::Text,
+            category_id: channel.category_id,
+            overwrites: channel.overwrites.to_owned(),
+        });
+        Ok(id)
+    }
+
+    fn set_permission(&self, channel_id: u64, overwrite: &PermissionOverwrite) -> Result<(), String> {
+        self.record(Call::SetPermission(channel_id, overwrite.kind, overwrite.allow, overwrite.deny))?;
+        if let Some(ch) = self.channels.borrow_mut().iter_mut().find(|ch| ch.id == channel_id) {
+            ch.overwrites.retain(|o| o.kind != overwrite.kind);
+            ch.overwrites.push(overwrite.to_owned());
+        }
+        Ok(())
+    }
+
+    fn remove_permission(&self, channel_id: u64, kind: PermissionOverwriteType) -> Result<(), String> {
+        self.record(Call::RemovePermission(channel_id, kind))?;
+        if let Some(ch) = self.channels.borrow_mut().iter_mut().find(|ch| ch.id == channel_id) {
+            ch.overwrites.retain(|o| o.kind != kind);
+        }
+        Ok(())
+    }
+
+    fn set_slowmode(&self, channel_id: u64, seconds: u64) -> Result<(), String> {
+        self.record(Call::SetSlowmode(channel_id, seconds)).map(|_| ())
+    }
+
+    fn set_topic(&self, channel_id: u64, topic: &str) -> Result<(), String> {
+        self.record(Call::SetTopic(channel_id, topic.to_string())).map(|_| ())
+    }
+
+    fn delete_channel(&self, channel_id: u64) -> Result<(), String> {
+        self.record(Call::DeleteChannel(channel_id))?;
+        self.channels.borrow_mut().retain(|ch| ch.id != channel_id);
+        Ok(())
+    }
+
+    fn channel(&self, channel_id: u64) -> Option<ChannelInfo> {
+        self.channels.borrow().iter().find(|ch| ch.id == channel_id).cloned()
+    }
+
+    fn channels(&self, _: u64) -> Vec<ChannelInfo> {
+        self.channels.borrow().clone()
+    }
+
+    fn members(&self, _: u64) -> Vec<MemberInfo> {
+        self.members.clone()
+    }
+
+    fn roles(&self, _: u64) -> Vec<RoleInfo> {
+        self.roles.clone()
+    }
+}
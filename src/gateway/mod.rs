@@ -0,0 +1,335 @@
+use serenity::model::channel::{Channel, ChannelType, PermissionOverwrite, PermissionOverwriteType, ReactionType};
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+use serenity::prelude::Context;
+
+#[cfg(test)]
+pub mod fake;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embed {
+    pub title: String,
+    pub description: String,
+    pub color: i32,
+}
+
+impl Embed {
+    pub fn new(title: &str, description: &str, color: i32) -> Embed {
+        Embed {
+            title: title.to_string(),
+            description: description.to_string(),
+            color,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChannelInfo {
+    pub id: u64,
+    pub name: String,
+    pub kind: ChannelType,
+    pub category_id: Option<u64>,
+    pub overwrites: Vec<PermissionOverwrite>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemberInfo {
+    pub id: u64,
+    pub name: String,
+    pub roles: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoleInfo {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageInfo {
+    pub id: u64,
+    pub author_id: u64,
+    pub author_name: String,
+    pub bot: bool,
+    pub content: String,
+    /// URLs of attached files.
+    pub attachments: Vec<String>,
+    pub embeds: Vec<Embed>,
+}
+
+#[derive(Clone, Debug)]
+pub struct NewChannel {
+    pub name: String,
+    pub topic: String,
+    pub category_id: Option<u64>,
+    pub overwrites: Vec<PermissionOverwrite>,
+}
+
+/// Every Discord side-effect used by commands. Serenity's `Context` is the real implementation
+/// and `fake::FakeGateway` records calls so flows can be tested offline.
+pub trait DiscordGateway {
+    /// Id of the bot's own user.
+    fn bot_id(&self) -> u64;
+    fn guild_name(&self, guild_id: u64) -> Option<String>;
+    /// Returns id of the sent message.
+    fn send_embed(&self, channel_id: u64, embed: &Embed) -> Result<u64, String>;
+    /// Plain message, unlike embeds it pings mentioned users. Returns id of the sent message.
+    fn send_message(&self, channel_id: u64, content: &str) -> Result<u64, String>;
+    fn edit_embed(&self, channel_id: u64, message_id: u64, embed: &Embed) -> Result<(), String>;
+    /// Files are pairs of name and content. Returns id of the sent message.
+    fn send_files(&self, channel_id: u64, files: &[(String, String)], message: &str) -> Result<u64, String>;
+    fn send_dm(&self, user_id: u64, embed: &Embed) -> Result<(), String>;
+    /// Returns id of the direct message channel with the user.
+    fn dm_channel(&self, user_id: u64) -> Result<u64, String>;
+    fn add_reaction(&self, channel_id: u64, message_id: u64, emoji: &str) -> Result<(), String>;
+    fn delete_message(&self, channel_id: u64, message_id: u64) -> Result<(), String>;
+    /// Bulk delete. Works only for 2 to 100 messages that are not older than 14 days.
+    fn delete_messages(&self, channel_id: u64, message_ids: &[u64]) -> Result<(), String>;
+    /// Messages sent before the given one, the newest first.
+    fn messages(&self, channel_id: u64, before: u64, limit: u64) -> Result<Vec<MessageInfo>, String>;
+    fn add_role(&self, guild_id: u64, user_id: u64, role_id: u64) -> Result<(), String>;
+    fn remove_role(&self, guild_id: u64, user_id: u64, role_id: u64) -> Result<(), String>;
+    fn ban(&self, guild_id: u64, user_id: u64, reason: &str) -> Result<(), String>;
+    fn unban(&self, guild_id: u64, user_id: u64) -> Result<(), String>;
+    fn kick(&self, guild_id: u64, user_id: u64) -> Result<(), String>;
+    /// Returns id of the created channel.
+    fn create_channel(&self, guild_id: u64, channel: &NewChannel) -> Result<u64, String>;
+    fn set_permission(&self, channel_id: u64, overwrite: &PermissionOverwrite) -> Result<(), String>;
+    fn remove_permission(&self, channel_id: u64, kind: PermissionOverwriteType) -> Result<(), String>;
+    /// Seconds every user has to wait between messages. 0 turns slowmode off.
+    fn set_slowmode(&self, channel_id: u64, seconds: u64) -> Result<(), String>;
+    fn set_topic(&self, channel_id: u64, topic: &str) -> Result<(), String>;
+    fn delete_channel(&self, channel_id: u64) -> Result<(), String>;
+    fn channel(&self, channel_id: u64) -> Option<ChannelInfo>;
+    fn channels(&self, guild_id: u64) -> Vec<ChannelInfo>;
+    fn members(&self, guild_id: u64) -> Vec<MemberInfo>;
+    fn roles(&self, guild_id: u64) -> Vec<RoleInfo>;
+}
+
+impl DiscordGateway for Context {
+    fn bot_id(&self) -> u64 {
+        self.cache.read().user.id.0
+    }
+
+    fn guild_name(&self, guild_id: u64) -> Option<String> {
+        self.cache.read().guild(GuildId(guild_id)).map(|g| g.read().name.to_owned())
+    }
+
+    fn send_embed(&self, channel_id: u64, embed: &Embed) -> Result<u64, String> {
+        let result = ChannelId(channel_id).send_message(&self.http, |m| {
+            m.embed(|e| {
+                e.title(&embed.title);
+                e.description(&embed.description);
+                e.color(embed.color);
+                e
+            });
+            m
+        });
+
+        match result {
+            Ok(m) => Ok(m.id.0),
+            Err(why) => Err(why.to_string())
+        }
+    }
+
+    fn send_message(&self, channel_id: u64, content: &str) -> Result<u64, String> {
+        ChannelId(channel_id).say(&self.http, content).map(|m| m.id.0).map_err(|why| why.to_string())
+    }
+
+    fn edit_embed(&self, channel_id: u64, message_id: u64, embed: &Embed) -> Result<(), String> {
+        ChannelId(channel_id).edit_message(&self.http, message_id, |m| {
+            m.embed(|e| {
+                e.title(&embed.title);
+                e.description(&embed.description);
+                e.color(embed.color);
+                e
+            });
+            m
+        }).map(|_| ()).map_err(|why| why.to_string())
+    }
+
+    fn send_files(&self, channel_id: u64, files: &[(String, String)], message: &str) -> Result<u64, String> {
+        let attachments: Vec<(&[u8], &str)> = files.iter()
+            .map(|(name, content)| (content.as_bytes(), name.as_str()))
+            .collect();
+
+        ChannelId(channel_id).send_files(&self.http, attachments, |m| m.content(message))
+            .map(|m| m.id.0)
+            .map_err(|why| why.to_string())
+    }
+
+    fn send_dm(&self, user_id: u64, embed: &Embed) -> Result<(), String> {
+        let channel_id = self.dm_channel(user_id)?;
+        self.send_embed(channel_id, embed).map(|_| ())
+    }
+
+    fn dm_channel(&self, user_id: u64) -> Result<u64, String> {
+        UserId(user_id).create_dm_channel(&self.http)
+            .map(|ch| ch.id.0)
+            .map_err(|why| why.to_string())
+    }
+
+    fn add_reaction(&self, channel_id: u64, message_id: u64, emoji: &str) -> Result<(), String> {
+        self.http.create_reaction(channel_id, message_id, &ReactionType::Unicode(emoji.to_string()))
+            .map_err(|why| why.to_string())
+    }
+
+    fn delete_message(&self, channel_id: u64, message_id: u64) -> Result<(), String> {
+        self.http.delete_message(channel_id, message_id).map_err(|why| why.to_string())
+    }
+
+    fn delete_messages(&self, channel_id: u64, message_ids: &[u64]) -> Result<(), String> {
+        let ids: Vec<MessageId> = message_ids.iter().map(|id| MessageId(*id)).collect();
+        ChannelId(channel_id).delete_messages(&self.http, ids).map_err(|why| why.to_string())
+    }
+
+    fn messages(&self, channel_id: u64, before: u64, limit: u64) -> Result<Vec<MessageInfo>, String> {
+        let messages = match ChannelId(channel_id).messages(&self.http, |r| r.before(MessageId(before)).limit(limit)) {
+            Ok(m) => m,
+            Err(why) => return Err(why.to_string())
+        };
+
+        Ok(messages.iter()
+            .map(|m| MessageInfo {
+                id: m.id.0,
+                author_id: m.author.id.0,
+                author_name: m.author.name.to_owned(),
+                bot: m.author.bot,
+                content: m.content.to_owned(),
+                attachments: m.attachments.iter().map(|a| a.url.to_owned()).collect(),
+                embeds: m.embeds.iter()
+                    .map(|e| Embed::new(
+                        e.title.as_ref().map(|t| t.as_str()).unwrap_or_default(),
+                        e.description.as_ref().map(|d| d.as_str()).unwrap_or_default(),
+                        e.colour.0 as i32
+                    ))
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn add_role(&self, guild_id: u64, user_id: u64, role_id: u64) -> Result<(), String> {
+        self.http.add_member_role(guild_id, user_id, role_id).map_err(|why| why.to_string())
+    }
+
+    fn remove_role(&self, guild_id: u64, user_id: u64, role_id: u64) -> Result<(), String> {
+        self.http.remove_member_role(guild_id, user_id, role_id).map_err(|why| why.to_string())
+    }
+
+    fn ban(&self, guild_id: u64, user_id: u64, reason: &str) -> Result<(), String> {
+        self.http.ban_user(guild_id, user_id, 0, reason).map_err(|why| why.to_string())
+    }
+
+    fn unban(&self, guild_id: u64, user_id: u64) -> Result<(), String> {
+        self.http.remove_ban(guild_id, user_id).map_err(|why| why.to_string())
+    }
+
+    fn kick(&self, guild_id: u64, user_id: u64) -> Result<(), String> {
+        self.http.kick_member(guild_id, user_id).map_err(|why| why.to_string())
+    }
+
+    fn create_channel(&self, guild_id: u64, channel: &NewChannel) -> Result<u64, String> {
+        let result = GuildId(guild_id).create_channel(&self.http, |ch| {
+            ch.name(&channel.name);
+            ch.kind(ChannelType::Text);
+            ch.topic(&channel.topic);
+            if let Some(category_id) = channel.category_id {
+                ch.category(category_id);
+            }
+            ch.permissions(channel.overwrites.to_owned());
+            ch
+        });
+
+        match result {
+            Ok(ch) => Ok(ch.id.0),
+            Err(why) => Err(why.to_string())
+        }
+    }
+
+    fn set_permission(&self, channel_id: u64, overwrite: &PermissionOverwrite) -> Result<(), String> {
+        ChannelId(channel_id).create_permission(&self.http, overwrite).map_err(|why| why.to_string())
+    }
+
+    fn remove_permission(&self, channel_id: u64, kind: PermissionOverwriteType) -> Result<(), String> {
+        ChannelId(channel_id).delete_permission(&self.http, kind).map_err(|why| why.to_string())
+    }
+
+    fn set_slowmode(&self, channel_id: u64, seconds: u64) -> Result<(), String> {
+        ChannelId(channel_id).edit(&self.http, |ch| ch.slow_mode_rate(seconds)).map(|_| ()).map_err(|why| why.to_string())
+    }
+
+    fn set_topic(&self, channel_id: u64, topic: &str) -> Result<(), String> {
+        ChannelId(channel_id).edit(&self.http, |ch| ch.topic(topic)).map(|_| ()).map_err(|why| why.to_string())
+    }
+
+    fn delete_channel(&self, channel_id: u64) -> Result<(), String> {
+        self.http.delete_channel(channel_id).map(|_| ()).map_err(|why| why.to_string())
+    }
+
+    fn channel(&self, channel_id: u64) -> Option<ChannelInfo> {
+        match self.cache.read().channel(ChannelId(channel_id)) {
+            Some(Channel::Guild(ch)) => {
+                let ch = ch.read();
+                Some(ChannelInfo {
+                    id: ch.id.0,
+                    name: ch.name.to_owned(),
+                    kind: ch.kind,
+                    category_id: ch.category_id.map(|c| c.0),
+                    overwrites: ch.permission_overwrites.to_owned(),
+                })
+            },
+            _ => None
+        }
+    }
+
+    fn channels(&self, guild_id: u64) -> Vec<ChannelInfo> {
+        let guild = match self.cache.read().guild(GuildId(guild_id)) {
+            Some(g) => g,
+            None => return Vec::new()
+        };
+
+        let guild = guild.read();
+        guild.channels.values()
+            .map(|ch| {
+                let ch = ch.read();
+                ChannelInfo {
+                    id: ch.id.0,
+                    name: ch.name.to_owned(),
+                    kind: ch.kind,
+                    category_id: ch.category_id.map(|c| c.0),
+                    overwrites: ch.permission_overwrites.to_owned(),
+                }
+            })
+            .collect()
+    }
+
+    fn members(&self, guild_id: u64) -> Vec<MemberInfo> {
+        let guild = match self.cache.read().guild(GuildId(guild_id)) {
+            Some(g) => g,
+            None => return Vec::new()
+        };
+
+        let guild = guild.read();
+        guild.members.values()
+            .map(|m| MemberInfo {
+                id: m.user.read().id.0,
+                name: m.display_name().to_string(),
+                roles: m.roles.iter().map(|r| r.0).collect(),
+            })
+            .collect()
+    }
+
+    fn roles(&self, guild_id: u64) -> Vec<RoleInfo> {
+        let guild = match self.cache.read().guild(GuildId(guild_id)) {
+            Some(g) => g,
+            None => return Vec::new()
+        };
+
+        let guild = guild.read();
+        guild.roles.values()
+            .map(|r| RoleInfo {
+                id: r.id.0,
+                name: r.name.to_owned(),
+            })
+            .collect()
+    }
+}
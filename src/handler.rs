@@ -1,31 +1,53 @@
-use crate::command::Command;
+use crate::command::cooldown::{Cooldown, CooldownBucket, Cooldowns};
+use crate::bot_modules::automod::rules::MessageHistory;
+use crate::bot_modules::moderation::raid::JoinHistory;
+use crate::command::{Command, join_args, get_command_name, resolve_command_name, suggest_name, EMBED_QUESTION_COLOR};
 use log::{error, info};
 use lazy_static::lazy_static;
 use std::sync::Mutex;
+use serenity::model::event::MessageUpdateEvent;
 use serenity::model::channel::{Message, Reaction, ReactionType, PermissionOverwrite, PermissionOverwriteType};
 use serenity::{
     model::gateway::Ready,
-    model::id::ChannelId,
+    model::guild::Member,
+    model::user::User,
+    model::id::{ChannelId, GuildId, UserId},
     model::Permissions,
     prelude::*,
 };
 use chrono::{Utc, Duration};
 use crate::utils::object_finding::FindsAwaitingAnswer;
 use crate::utils::perms::has_perms;
-use crate::utils::db::{ServerInfo, ActionType};
+use crate::utils::{check_if_dev, format_duration, send_dm};
+use crate::config::DEV_MODULE;
+use crate::utils::db::{ServerInfo, ActionType, get_ticket_by_channel, set_ticket_status, get_special_entity_by_type, get_ticket_types};
+use crate::database::models::{SpecialEntityType, TicketStatus};
 use crate::bot_modules::get_modules;
 use super::bot_modules::main::help_command::HelpCommand;
 use super::bot_modules::tickets::solved_command::SolvedTicketCommand;
+use super::bot_modules::tickets::new_command::{NewTicketCommand, PANEL_EMOJI};
+use super::bot_modules::tickets::ticket_type_command::TYPE_EMOJIS;
+use super::bot_modules::tags::tag_command::TagCommand;
+use super::bot_modules::automod::automod_command::{AutomodCommand, AutomodConfig};
+use super::bot_modules::automod::filter_command::FilterCommand;
+use super::bot_modules::moderation::lockdown_command::LockdownCommand;
+use super::bot_modules::moderation::mute_command::MuteCommand;
+use super::bot_modules::moderation::sticky_roles_command::StickyRolesCommand;
 use crate::database::schema::{servers, temp_operations};
 use crate::database::schema::temp_operations::columns::{id, action_type, target_id};
 use crate::diesel::{RunQueryDsl, BelongingToDsl, ExpressionMethods, QueryDsl, BoolExpressionMethods, TextExpressionMethods};
 use crate::database::get_db_con;
+use crate::scheduler;
+use crate::gateway::DiscordGateway;
 
 pub struct Handler;
 
 #[derive(Default)]
 pub struct State {
     pub role_finds_awaiting: Vec<FindsAwaitingAnswer>,
+    pub cooldowns: Cooldowns,
+    pub message_history: MessageHistory,
+    pub join_history: JoinHistory,
 }
 
 lazy_static! {
@@ -45,6 +67,72 @@ impl Handler {
         });
     }
 
+    fn bucket_id(&self, msg: &Message, bucket: CooldownBucket) -> u64 {
+        match bucket {
+            CooldownBucket::User => msg.author.id.0,
+            CooldownBucket::Channel => msg.channel_id.0,
+            // DM channel is the only "guild" there
+            CooldownBucket::Guild => msg.guild_id.map_or(msg.channel_id.0, |g| g.0),
+        }
+    }
+
+    // the longest wait from every bucket
+    fn cooldown_remaining(&self, msg: &Message, cmd_name: &str, cooldowns: &Vec<Cooldown>) -> Option<Duration> {
+        let state = STATE.lock().unwrap();
+        cooldowns.iter()
+            .filter_map(|cd| state.cooldowns.remaining(cmd_name, cd.bucket, self.bucket_id(msg, cd.bucket), Utc::now()))
+            .max()
+    }
+
+    fn suggest_command(&self, ctx: &Context, msg: &Message, info: &ServerInfo, prefix: &str, name: &str) {
+        let mut candidates = Vec::new();
+        for m in get_modules().iter() {
+            if !m.enabled(info) || (m.name() == DEV_MODULE && !check_if_dev(msg)) {
+                continue
+            }
+
+            for c in m.commands().iter() {
+                if c.disabled(info, msg.channel_id.to_string()) {
+                    continue
+                }
+
+                let mut names = c.aliases();
+                names.push(c.name());
+                if let Some(aliases) = &info.aliases {
+                    aliases.iter()
+                        .filter(|a| a.command_name == c.name())
+                        .for_each(|a| names.push(a.alias.to_owned()));
+                }
+
+                // checking perms requires db so it's done only for similar commands
+                if suggest_name(name, &names).is_none() {
+                    continue
+                }
+
+                let allowed = if msg.is_private() {
+                    c.use_in_dm()
+                } else {
+                    has_perms(ctx, msg, info, &c.perms())
+                };
+                if allowed {
+                    candidates.extend(names);
+                }
+            }
+        }
+
+        if let Some(suggestion) = suggest_name(name, &candidates) {
+            let _ = msg.channel_id.send_message(&ctx.http, |m| {
+                m.embed(|e| {
+                    e.title("Unknown command");
+                    e.color(EMBED_QUESTION_COLOR);
+                    e.description(format!("Did you mean `{}{}`?", prefix, suggestion));
+                    e
+                });
+                m
+            });
+        }
+    }
+
     fn check_awaiting_answers(&self, ctx: Context, msg: &mut Message) -> bool {
         {
             let mut state = STATE.lock().unwrap();
@@ -93,7 +181,7 @@ impl Handler {
         }
 
         picked.args[picked.replace_index] = picked.finds[answer-1].0.to_string();
-        msg.content = picked.args.join(" ");
+        msg.content = join_args(&picked.args);
 
         false
     }
@@ -111,6 +199,12 @@ impl EventHandler for Handler {
         }
 
         let info = ServerInfo::new(msg.guild_id);
+        let automod = AutomodConfig::load(&info);
+        let checked = msg.guild_id.map_or(false, |g| automod.is_checked(&ctx, g.0, msg.channel_id.0, msg.author.id.0));
+        if checked && (FilterCommand.run(&ctx, &info, msg.channel_id, msg.id, &msg.author, &msg.content) || AutomodCommand.run(&ctx, &msg, &info, &automod)) {
+            return;
+        }
+
         let prefix = if msg.content.starts_with(&format!("<@{}> ", ctx.cache.read().user.id)) {
             format!("<@{}> ", ctx.cache.read().user.id)
         } else if msg.content.starts_with(&format!("<@!{}> ", ctx.cache.read().user.id)) {
@@ -124,11 +218,23 @@ impl EventHandler for Handler {
         if msg.content.trim() == format!("<@{}>", ctx.cache.read().user.id)
             || msg.content.trim() == format!("<@!{}>", ctx.cache.read().user.id)
         {
-            let _ = HelpCommand {}.exe(&ctx, &msg, &info);
+            let _ = HelpCommand::command().exe(&ctx, &msg, &info);
             return;
         }
 
-        let check_msg = msg.content.to_lowercase();
+        let cmd_name = match get_command_name(&msg.content, &prefix) {
+            Some(name) => match resolve_command_name(&name, &info) {
+                Some(name) => name,
+                None => {
+                    if !TagCommand.run(&ctx, &msg, &info, &name) {
+                        self.suggest_command(&ctx, &msg, &info, &prefix, &name);
+                    }
+                    return
+                }
+            },
+            None => return
+        };
+
         for m in super::get_modules().iter() {
             if !m.enabled(&info) {
                 continue
@@ -139,7 +245,7 @@ impl EventHandler for Handler {
                     continue;
                 }
 
-                if check_msg.starts_with(&format!("{}{}", prefix, c.name().to_lowercase())) {
+                if c.name() == cmd_name {
                     if !c.use_in_dm() && msg.is_private() {
                         self.send_error(
                             ctx.clone(),
@@ -174,9 +280,27 @@ impl EventHandler for Handler {
                         }
                     }
 
-                    if let Err(why) = c.exe(&ctx, &msg, &info) {
-                        error!("Command '{}' failed. Reason: {}", c.name(), why.to_owned());
-                        self.send_error(ctx.clone(), msg.channel_id, &why);
+                    let cooldowns = c.active_cooldowns(&info);
+                    if let Some(remaining) = self.cooldown_remaining(&msg, &c.name(), &cooldowns) {
+                        self.send_error(
+                            ctx.clone(),
+                            msg.channel_id,
+                            &format!("Slow down! You can use this command again in {}.", format_duration(remaining)),
+                        );
+                        return;
+                    }
+
+                    match c.exe(&ctx, &msg, &info) {
+                        Ok(_) => {
+                            let mut state = STATE.lock().unwrap();
+                            for cd in cooldowns.iter() {
+                                state.cooldowns.start(&c.name(), cd, self.bucket_id(&msg, cd.bucket), Utc::now());
+                            }
+                        }
+                        Err(why) => {
+                            error!("Command '{}' failed. Reason: {}", c.name(), why.to_owned());
+                            self.send_error(ctx.clone(), msg.channel_id, &why);
+                        }
                     }
 
                     break;
@@ -185,6 +309,39 @@ impl EventHandler for Handler {
         }
     }
 
+    // filtered words can't be edited into a message either
+    fn message_update(&self, ctx: Context, _: Option<Message>, _: Option<Message>, event: MessageUpdateEvent) {
+        let (author, content) = match (&event.author, &event.content) {
+            (Some(a), Some(c)) => (a, c),
+            _ => return
+        };
+
+        let info = ServerInfo::new(event.guild_id);
+        let checked = event.guild_id.map_or(false, |g| AutomodConfig::load(&info).is_checked(&ctx, g.0, event.channel_id.0, author.id.0));
+        if checked {
+            FilterCommand.run(&ctx, &info, event.channel_id, event.id, author, content);
+        }
+    }
+
+    fn guild_member_addition(&self, ctx: Context, guild_id: GuildId, member: Member) {
+        let info = ServerInfo::new(Some(guild_id));
+        LockdownCommand.check_raid(&ctx, &info, guild_id.0, &member);
+
+        // leaving and rejoining is not a way around a mute
+        let user_id = member.user_id().0;
+        if let Err(why) = MuteCommand.reapply(&ctx, &info, guild_id.0, user_id) {
+            error!("Could not mute rejoining user {}. Reason: {}", user_id, why);
+        }
+        StickyRolesCommand.restore(&ctx, &info, guild_id.0, user_id);
+    }
+
+    fn guild_member_removal(&self, _: Context, guild_id: GuildId, user: User, member: Option<Member>) {
+        if let Some(member) = member {
+            let roles: Vec<u64> = member.roles.iter().map(|r| r.0).collect();
+            StickyRolesCommand.save(&ServerInfo::new(Some(guild_id)), user.id.0, &roles);
+        }
+    }
+
     fn reaction_add(&self, ctx: Context, reaction: Reaction) {
         if ctx.cache.read().user.id == reaction.user_id {
             return
@@ -212,10 +369,28 @@ impl EventHandler for Handler {
                 "❎" => {
                     match reaction.guild_id {
                         Some(guild) => {
+                            // support team can reopen the ticket too, but only the opener was denied writing
+                            let ticket = get_ticket_by_channel(&ServerInfo::new(Some(guild)), reaction.channel_id.0);
+                            let owner_id = match ticket.as_ref().map(|t| t.opener_id.parse::<u64>()) {
+                                Some(Ok(id)) => UserId(id),
+                                Some(Err(why)) => {
+                                    error!("Reaction Callback '❎' failed. Reason: Invalid ticket opener id: {}", why);
+                                    return
+                                },
+                                None => reaction.user_id
+                            };
+
+                            let db = match get_db_con().get() {
+                                Ok(db) => db,
+                                Err(why) => {
+                                    error!("Reaction Callback '❎' failed. Reason: {}", why);
+                                    return
+                                }
+                            };
                             let result = diesel::delete(temp_operations::table.filter(
                                 action_type.eq(ActionType::SolvedTicket as i32)
                                 .and(target_id.like(reaction.channel_id.to_string()))
-                            )).execute(&get_db_con().get().expect("Could not get db pool"));
+                            )).execute(&db);
                             match result {
                                 Ok(removed) => {
                                     if removed == 0 {
@@ -229,16 +404,46 @@ impl EventHandler for Handler {
                             perms.insert(Permissions::SEND_MESSAGES);
                             perms.insert(Permissions::ADD_REACTIONS);
 
-                            let _ = reaction.channel_id.create_permission(&ctx.http, &PermissionOverwrite {
+                            let _ = ctx.set_permission(reaction.channel_id.0, &PermissionOverwrite {
                                 allow: perms,
                                 deny: Permissions::empty(),
-                                kind: PermissionOverwriteType::Member(reaction.user_id)
+                                kind: PermissionOverwriteType::Member(owner_id)
                             });
+                            if let Some(ticket) = ticket {
+                                set_ticket_status(&ticket, TicketStatus::Open);
+                            }
 
-                            let _ = ctx.http.delete_message(reaction.channel_id.into(), reaction.message_id.into());
+                            let _ = ctx.delete_message(reaction.channel_id.0, reaction.message_id.0);
                         },
                         None => {}
                     }
+                },
+                e if e == PANEL_EMOJI || TYPE_EMOJIS.contains(&e) => {
+                    if let Some(guild) = reaction.guild_id {
+                        // panel is looked up by its message, so it keeps working after restarts
+                        let info = ServerInfo::new(Some(guild));
+                        let is_panel = get_special_entity_by_type(&info, SpecialEntityType::TicketPanel)
+                            .map_or(false, |panel| panel.entity_id == reaction.message_id.to_string());
+                        if !is_panel {
+                            return
+                        }
+                        // the default emoji opens tickets without a type
+                        let ticket_types = get_ticket_types(&info);
+                        let ticket_type = ticket_types.iter().find(|t| t.emoji == e);
+                        if ticket_type.is_none() && e != PANEL_EMOJI {
+                            return
+                        }
+                        let user = match reaction.user(ctx.http.clone()) {
+                            Ok(u) => u,
+                            Err(_) => return
+                        };
+
+                        // taking the reaction back lets the user react again later
+                        let _ = reaction.delete(ctx.http.clone());
+                        if let Err(why) = NewTicketCommand.new_ticket(&ctx, &info, guild.0, &user, ticket_type, None) {
+                            send_dm(&ctx, user.id.0, "Could not open a ticket", &why);
+                        }
+                    }
                 }
                 _ => {}
             }
@@ -253,5 +458,6 @@ impl EventHandler for Handler {
                 info!("Command {} initialized!", c.name())
             }
         }
+        scheduler::start(&ctx);
     }
 }
@@ -5,7 +5,9 @@ mod bot_modules;
 mod command;
 mod config;
 mod database;
+mod gateway;
 mod handler;
+mod scheduler;
 mod utils;
 
 #[cfg(test)]
@@ -0,0 +1,202 @@
+use crate::database::get_db_con;
+use crate::database::models::{Server, TempOperation};
+use crate::database::schema::{servers, temp_operations};
+use crate::diesel::{RunQueryDsl, QueryDsl, ExpressionMethods};
+use crate::gateway::DiscordGateway;
+use crate::utils::db::{ServerInfo, ActionType};
+use chrono::{Duration, NaiveDateTime, Utc};
+use lazy_static::lazy_static;
+use log::{error, info};
+use serenity::model::id::GuildId;
+use serenity::prelude::Context;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+/// Operation that failed this many times stays in the database with its last error and is not retried.
+pub const MAX_ATTEMPTS: i32 = 5;
+
+pub type OperationHandler = fn(&dyn DiscordGateway, &ServerInfo, &TempOperation) -> Result<(), String>;
+
+/// Temp operations ordered by `end_date`, the earliest one first.
+#[derive(Default)]
+pub struct Queue {
+    heap: BinaryHeap<Reverse<(NaiveDateTime, i32)>>,
+}
+
+impl Queue {
+    pub fn push(&mut self, id: i32, end_date: NaiveDateTime) {
+        self.heap.push(Reverse((end_date, id)));
+    }
+
+    /// Id of the earliest operation if it is already due.
+    pub fn pop_due(&mut self, now: NaiveDateTime) -> Option<i32> {
+        match self.heap.peek() {
+            Some(Reverse((end_date, _))) if *end_date <= now => self.heap.pop().map(|Reverse((_, id))| id),
+            _ => None,
+        }
+    }
+
+    /// Time until the earliest operation is due. `None` when there is nothing to wait for.
+    pub fn time_left(&self, now: NaiveDateTime) -> Option<Duration> {
+        self.heap.peek().map(|Reverse((end_date, _))| *end_date - now)
+    }
+}
+
+/// Whether the loaded operation should run now. The queue can hold stale entries of an operation whose
+/// `end_date` has been moved since, e.g. by a retry backoff, and those must not run it early.
+pub fn is_due(op: &TempOperation, now: NaiveDateTime) -> bool {
+    op.end_date <= now && op.attempts < MAX_ATTEMPTS
+}
+
+/// Backoff after failed attempt, starting at 30 seconds and doubling every time.
+pub fn retry_delay(attempts: i32) -> Duration {
+    Duration::seconds(30 * 2i64.pow((attempts.max(1) - 1).min(10) as u32))
+}
+
+#[derive(Default)]
+struct Scheduler {
+    queue: Queue,
+    handlers: HashMap<i32, OperationHandler>,
+    running: bool,
+}
+
+lazy_static! {
+    static ref SCHEDULER: (Mutex<Scheduler>, Condvar) = (Mutex::new(Scheduler::default()), Condvar::new());
+}
+
+/// Handler is called once the operation of given type ends. Failed operations are retried with backoff.
+pub fn register_handler(kind: ActionType, handler: OperationHandler) {
+    let (lock, _) = &*SCHEDULER;
+    lock.lock().unwrap().handlers.insert(kind as i32, handler);
+}
+
+/// Adds newly created operation. Operations removed from the database before their end are skipped.
+pub fn schedule(op: &TempOperation) {
+    let (lock, cvar) = &*SCHEDULER;
+    lock.lock().unwrap().queue.push(op.id, op.end_date);
+    cvar.notify_one();
+}
+
+/// Loads pending operations and starts the scheduler thread. Does nothing when it's already running.
+pub fn start(ctx: &Context) {
+    {
+        let (lock, _) = &*SCHEDULER;
+        let mut scheduler = lock.lock().unwrap();
+        if scheduler.running {
+            return
+        }
+        scheduler.running = true;
+    }
+
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let pending = temp_operations::table
+        .filter(temp_operations::attempts.lt(MAX_ATTEMPTS))
+        .load::<TempOperation>(&db)
+        .expect("Could not load temp operations");
+    info!("Scheduler loaded {} temp operations", pending.len());
+    pending.iter().for_each(schedule);
+
+    let ctx = Mutex::new(ctx.clone());
+    thread::spawn(move || {
+        let (lock, cvar) = &*SCHEDULER;
+        loop {
+            let id = {
+                let mut scheduler = lock.lock().unwrap();
+                loop {
+                    let now = Utc::now().naive_utc();
+                    if let Some(id) = scheduler.queue.pop_due(now) {
+                        break id
+                    }
+
+                    scheduler = match scheduler.queue.time_left(now) {
+                        Some(left) => cvar.wait_timeout(scheduler, left.to_std().unwrap_or_default()).unwrap().0,
+                        None => cvar.wait(scheduler).unwrap(),
+                    };
+                }
+            };
+
+            run_operation(&*ctx.lock().unwrap(), id);
+        }
+    });
+}
+
+/// Id of the operation's target, e.g. the muted user or the locked channel.
+pub fn target_id(op: &TempOperation) -> Result<u64, String> {
+    op.target_id.parse::<u64>().map_err(|_| format!("Invalid target id `{}`", op.target_id))
+}
+
+/// Id of the guild the handler runs for.
+pub fn guild_id(info: &ServerInfo) -> Result<u64, String> {
+    match &info.server {
+        Some(server) => server.guildid.parse::<u64>().map_err(|_| format!("Invalid guild id `{}`", server.guildid)),
+        None => Err("Server is not loaded".to_string())
+    }
+}
+
+fn run_operation(gateway: &dyn DiscordGateway, op_id: i32) {
+    let db = match get_db_con().get() {
+        Ok(db) => db,
+        Err(why) => {
+            // nothing can be recorded without the database, so it's only tried again later
+            error!("Temp operation {} could not get db pool. Reason: {}", op_id, why);
+            let (lock, _) = &*SCHEDULER;
+            lock.lock().unwrap().queue.push(op_id, Utc::now().naive_utc() + retry_delay(1));
+            return
+        }
+    };
+    // operation could have been removed in the meantime, e.g. user was unmuted manually
+    let op = match temp_operations::table.find(op_id).first::<TempOperation>(&db) {
+        Ok(op) => op,
+        Err(_) => return
+    };
+    if !is_due(&op, Utc::now().naive_utc()) {
+        return
+    }
+
+    let handler = {
+        let (lock, _) = &*SCHEDULER;
+        lock.lock().unwrap().handlers.get(&op.action_type).copied()
+    };
+
+    let result = match handler {
+        Some(handler) => match servers::table.find(op.server_id).first::<Server>(&db) {
+            Ok(server) => match server.guildid.parse::<u64>() {
+                // panicking handler would stop every timed action, so it's recorded as a failure instead
+                Ok(guild_id) => panic::catch_unwind(AssertUnwindSafe(|| {
+                    handler(gateway, &ServerInfo::new(Some(GuildId(guild_id))), &op)
+                })).unwrap_or_else(|_| Err("Handler panicked".to_string())),
+                Err(_) => Err(format!("Invalid guild id `{}`", server.guildid))
+            },
+            Err(_) => Err("Could not load the server".to_string())
+        },
+        None => Err(format!("There is no handler for action type {}", op.action_type))
+    };
+
+    match result {
+        Ok(_) => {
+            let _ = diesel::delete(temp_operations::table.find(op.id)).execute(&db);
+        },
+        Err(why) => {
+            let attempts = op.attempts + 1;
+            error!("Temp operation {} failed ({}/{}). Reason: {}", op.id, attempts, MAX_ATTEMPTS, why);
+
+            let end_date = Utc::now().naive_utc() + retry_delay(attempts);
+            let updated = diesel::update(temp_operations::table.find(op.id))
+                .set((
+                    temp_operations::attempts.eq(attempts),
+                    temp_operations::last_error.eq(Some(why)),
+                    temp_operations::end_date.eq(end_date)
+                ))
+                .get_result::<TempOperation>(&db);
+
+            if let Ok(op) = updated {
+                if op.attempts < MAX_ATTEMPTS {
+                    schedule(&op);
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,39 @@
 use super::*;
-use crate::command::{get_args, parse_args, CommandArg};
-use chrono::{DateTime, FixedOffset, TimeZone, Utc};
-use serenity::model::channel::{Message, MessageType};
+use crate::command::{get_args, parse_args, CommandArg, CommandConfig, ArgOption, args_from_content, raw_args_from_content, join_args, get_command_name, resolve_command_name,
+    suggest_name, closest_route};
+use crate::command::cooldown::{Cooldown, CooldownBucket, Cooldowns, MAX_COOLDOWN};
+use crate::utils::{edit_distance, format_duration, get_duration, get_time, is_time_format};
+use crate::utils::db::{ServerInfo, ActionType};
+use crate::command::builder::{CommandBuilder, Route};
+use crate::bot_modules::get_modules;
+use crate::bot_modules::tags::tag_command::fill_placeholders;
+use crate::scheduler::{MAX_ATTEMPTS, Queue, is_due, retry_delay};
+use crate::bot_modules::automod::rules::{MessageHistory, caps_percent, has_invite, blocked_domain, link_hosts, is_zalgo, parse_ids};
+use crate::bot_modules::automod::word_filter::{WordPattern, normalize, parse_pattern, redact};
+use crate::gateway::{ChannelInfo, DiscordGateway, Embed, MemberInfo, MessageInfo, NewChannel, RoleInfo};
+use crate::gateway::fake::{Call, FakeGateway};
+use crate::database::models::{Action, DBCommandConfig, LockedChannel, Server, SpecialEntity, SpecialEntityType, TempOperation, Ticket, TicketStatus, TicketType};
+use crate::utils::special_entities_tools::{case_embed, update_case_log};
+use crate::bot_modules::moderation::ban_command::BanCommand;
+use crate::bot_modules::moderation::mute_command::MuteCommand;
+use crate::bot_modules::moderation::purge_command::{PurgeCommand, PurgeFilter, date_id, message_date, purge_breakdown};
+use crate::bot_modules::moderation::lock_command::LockCommand;
+use crate::bot_modules::moderation::lockdown_command::{LockdownCommand, lock_channels, restore_channels};
+use crate::bot_modules::moderation::raid::{JoinHistory, Raid};
+use crate::bot_modules::moderation::slowmode_command::{SlowmodeCommand, parse_slowmode};
+use crate::bot_modules::moderation::sticky_roles_command::restored_roles;
+use crate::bot_modules::moderation::notify_command::{Notice, NotifyCommand, fill_notice};
+use crate::bot_modules::tickets::new_command::{NewTicketCommand, PANEL_EMOJI, default_ticket_type};
+use crate::bot_modules::tickets::ticket_type_command::{DEFAULT_NAME_PATTERN, channel_name, next_emoji, parse_questions, pick_ticket_type, valid_type_name};
+use crate::bot_modules::tickets::solved_command::SolvedTicketCommand;
+use crate::bot_modules::tickets::tickets_command::{ticket_line, ticket_matches};
+use crate::bot_modules::tickets::transcript::{channel_history, html_transcript, text_transcript};
+use crate::bot_modules::tickets::participants::{add_participant, remove_participant, set_claim, ticket_topic};
+use serenity::model::id::{RoleId, UserId};
+use serenity::model::Permissions;
+use std::cell::RefCell;
+use chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
+use serenity::model::channel::{ChannelType, Message, MessageType, PermissionOverwrite, PermissionOverwriteType};
 use serenity::model::user::User;
 
 #[test]
@@ -32,3 +64,928 @@ fn test_parse_args() {
 
     assert!(parse_args(&c_args, &vec!["name".to_string(), "not_ok".to_string()]).is_err());
 }
+
+#[test]
+fn test_parsed_args() {
+    let c_args = vec![CommandArg {
+        name: "<user>".to_string(),
+        desc: None,
+        option: Some(ArgOption::User),
+        next: Some(Box::new(CommandArg {
+            name: "[time]".to_string(),
+            desc: None,
+            option: Some(ArgOption::Time),
+            next: Some(Box::new(CommandArg {
+                name: "[reason...]".to_string(),
+                desc: None,
+                option: Some(ArgOption::Any),
+                next: None,
+            })),
+        })),
+    }];
+
+    let args = parse_args(&c_args, &args_from_content(".mute <@!246604909451935745> spamming links", false))
+        .unwrap()
+        .unwrap();
+    assert_eq!(args.get_user("user").unwrap().0, 246604909451935745);
+    assert!(!args.has("time"));
+    assert_eq!(args.index("reason"), Some(1));
+    assert_eq!(args.get_str("reason").unwrap(), "spamming links");
+
+    let args = parse_args(&c_args, &args_from_content(".mute bob 2h", false))
+        .unwrap()
+        .unwrap();
+    assert!(args.get_user("user").is_none());
+    assert_eq!(args.get_str("user").unwrap(), "bob");
+    assert_eq!(args.get_duration("time").unwrap(), Duration::hours(2));
+    assert!(args.get_time("time").unwrap() > Utc::now());
+    assert!(!args.has("reason"));
+}
+
+#[test]
+fn test_typed_args() {
+    let c_args = vec![CommandArg {
+        name: "<role>".to_string(),
+        desc: None,
+        option: Some(ArgOption::Role),
+        next: Some(Box::new(CommandArg {
+            name: "<enable/disable>".to_string(),
+            desc: None,
+            option: Some(ArgOption::Boolean),
+            next: Some(Box::new(CommandArg {
+                name: "[channel]".to_string(),
+                desc: None,
+                option: Some(ArgOption::Channel),
+                next: None,
+            })),
+        })),
+    }];
+
+    let args = parse_args(&c_args, &args_from_content(".cmd <@&246604909451935745> disable <#246604909451935745>", false))
+        .unwrap()
+        .unwrap();
+    assert_eq!(args.get_role("role").unwrap().0, 246604909451935745);
+    assert_eq!(args.get_bool("enable/disable"), Some(false));
+    assert_eq!(args.get_channel("channel").unwrap().0, 246604909451935745);
+
+    let args = parse_args(&c_args, &args_from_content(".cmd \"Senior Mod\" enable general", false))
+        .unwrap()
+        .unwrap();
+    assert!(args.get_role("role").is_none());
+    assert_eq!(args.get_str("role").unwrap(), "Senior Mod");
+    assert_eq!(args.get_bool("enable/disable"), Some(true));
+    assert!(args.get_channel("channel").is_none());
+
+    assert!(parse_args(&c_args, &args_from_content(".cmd moderator maybe", false)).is_err());
+}
+
+#[test]
+fn test_durations() {
+    assert_eq!(get_duration("90s").unwrap(), Duration::seconds(90));
+    assert_eq!(get_duration(" 2d ").unwrap(), Duration::days(2));
+    assert!(get_duration("5").is_err());
+    assert!(get_duration("5y").is_err());
+
+    // multibyte last chars can't be cut in half
+    assert!(get_duration("café").is_err());
+    assert!(get_duration("5😀").is_err());
+    assert!(get_duration("é").is_err());
+
+    // too long to be a date
+    assert!(get_duration("9223372036854775807d").is_err());
+    assert!(get_duration("9223372036854775s").is_err());
+    assert!(get_duration("99999999999d").is_err());
+    assert!(get_time("99999999999d").is_err());
+
+    // already over before it starts
+    assert!(get_duration("-5m").is_err());
+    assert!(get_duration("0s").is_err());
+    assert!(is_time_format("-5m"));
+    assert!(!is_time_format("spill"));
+
+    let c_args = vec![CommandArg {
+        name: "<user>".to_string(),
+        desc: None,
+        option: Some(ArgOption::User),
+        next: Some(Box::new(CommandArg {
+            name: "[time]".to_string(),
+            desc: None,
+            option: Some(ArgOption::Time),
+            next: Some(Box::new(CommandArg {
+                name: "<reason...>".to_string(),
+                desc: None,
+                option: Some(ArgOption::Any),
+                next: None,
+            })),
+        })),
+    }];
+    let args = parse_args(&c_args, &args_from_content(".warn bob café spill", false)).unwrap().unwrap();
+    assert!(!args.has("time"));
+    assert_eq!(args.get_str("reason").unwrap(), "café spill");
+    assert!(parse_args(&c_args, &args_from_content(".warn bob -5m spill", false)).is_err());
+}
+
+#[test]
+fn test_tokenizer() {
+    assert_eq!(
+        args_from_content(r#".ban bob "spamming links" 'Senior Mod' it's"#, false),
+        vec!["bob", "spamming links", "Senior Mod", "it's"]
+    );
+
+    assert_eq!(
+        args_from_content(r#".warn bob \"quoted\" a\ b `some code` ¯\_(ツ)_/¯"#, false),
+        vec!["bob", "\"quoted\"", "a b", "`some code`", r#"¯\_(ツ)_/¯"#]
+    );
+
+    assert_eq!(args_from_content("<@123> mute bob", false), vec!["bob"]);
+    assert_eq!(args_from_content(".mute", false).len(), 0);
+
+    let args = args_from_content(r#".role add "Senior Mod" "x \" y""#, false);
+    assert_eq!(args_from_content(&format!(".role {}", join_args(&args)), false), args);
+}
+
+#[test]
+fn test_raw_args() {
+    assert_eq!(raw_args_from_content(".warn bob first line\n  second line\n", 1), "first line\n  second line");
+    // quotes are kept, no matter how many arguments the text has
+    assert_eq!(raw_args_from_content(r#".warn bob "quoted reason""#, 1), r#""quoted reason""#);
+    assert_eq!(raw_args_from_content(r#".warn bob "quoted reason" and more"#, 1), r#""quoted reason" and more"#);
+    assert_eq!(raw_args_from_content(".warn bob", 1), "");
+}
+
+#[test]
+fn test_command_builder() {
+    // every command is built on start-up, so a malformed one would panic here
+    for m in get_modules().iter() {
+        for c in m.commands().iter() {
+            assert!(!c.name().is_empty());
+        }
+    }
+
+    let route = Route::new("bans user")
+        .arg("<user>", ArgOption::User)
+        .arg("[time]", ArgOption::Time)
+        .arg("[reason...]", ArgOption::Any)
+        .build()
+        .unwrap();
+    assert_eq!(route.name, "<user>");
+    assert_eq!(route.desc, Some("bans user".to_string()));
+    assert_eq!(route.next.as_ref().unwrap().name, "[time]");
+    assert!(route.next.as_ref().unwrap().desc.is_none());
+
+    let empty = Route::new("shows usage information").build().unwrap();
+    assert_eq!(empty.name, "");
+    assert!(empty.next.is_none());
+
+    assert!(Route::new("").arg("<user>", ArgOption::User).build().is_ok());
+    assert!(Route::new("").arg("<user>", ArgOption::User).literal("<bad>").build().is_err());
+    assert!(Route::new("").literal("reduce warns").build().is_err());
+    assert!(Route::new("").arg("[reason...]", ArgOption::Any).arg("<user>", ArgOption::User).build().is_err());
+    assert!(Route::new("").arg("[name]", ArgOption::Any).arg("<user>", ArgOption::User).build().is_err());
+    assert!(Route::new("").arg("[time]", ArgOption::Time).arg("<user>", ArgOption::User).build().is_ok());
+
+    assert!(CommandBuilder::new("test").try_build().is_err());
+    assert!(CommandBuilder::new("two words").handler(|_, _, _, _| Ok(())).try_build().is_err());
+    assert!(CommandBuilder::new("test").handler(|_, _, _, _| Ok(())).try_build().is_ok());
+    assert!(CommandBuilder::new("test").aliases(&["test"]).handler(|_, _, _, _| Ok(())).try_build().is_err());
+}
+
+#[test]
+fn test_command_name() {
+    assert_eq!(get_command_name(".ban @user 1d", "."), Some("ban".to_string()));
+    assert_eq!(get_command_name(".mutex", "."), Some("mutex".to_string()));
+    assert_eq!(get_command_name("<@1234> help all", "<@1234> "), Some("help".to_string()));
+    assert_eq!(get_command_name("ban @user", "."), None);
+    assert_eq!(get_command_name(".", "."), None);
+    assert_eq!(get_command_name("B!Ban @user", "B!"), Some("ban".to_string()));
+    assert_eq!(get_command_name("b!ban @user", "B!"), None);
+
+    let info = ServerInfo::default();
+    assert_eq!(resolve_command_name("ban", &info), Some("ban".to_string()));
+    assert_eq!(resolve_command_name("b", &info), Some("ban".to_string()));
+    assert_eq!(resolve_command_name("tempmute", &info), Some("mute".to_string()));
+    assert_eq!(resolve_command_name("mutex", &info), None);
+}
+
+#[test]
+fn test_suggestions() {
+    assert_eq!(edit_distance("mute", "muet"), 2);
+    assert_eq!(edit_distance("kick", "kick"), 0);
+    assert_eq!(edit_distance("", "ban"), 3);
+
+    let names: Vec<String> = vec!["mute", "unmute", "ban", "b", "help"].iter().map(|n| n.to_string()).collect();
+    assert_eq!(suggest_name("muet", &names), Some(&"mute".to_string()));
+    assert_eq!(suggest_name("bna", &names), None);
+    assert_eq!(suggest_name("bam", &names), Some(&"ban".to_string()));
+    assert_eq!(suggest_name("x", &names), None);
+    assert_eq!(suggest_name("kick", &names), None);
+
+    let routes = vec![
+        Route::new("adds permissions to role")
+            .literal("add")
+            .arg("<role>", ArgOption::Role)
+            .arg("<permissions or modules...>", ArgOption::Text)
+            .build()
+            .unwrap(),
+        Route::new("shows role's permissions")
+            .arg("<role>", ArgOption::Role)
+            .build()
+            .unwrap(),
+        Route::new("shows usage information").build().unwrap(),
+    ];
+    let message_args = |s: &str| args_from_content(s, false);
+
+    let route = closest_route(&routes, &message_args(".perms add <@&123456789012345678>")).unwrap();
+    assert_eq!(route.help_line(".", "perms"), "**.perms add <role> <permissions or modules...>** - adds permissions to role\n");
+    let route = closest_route(&routes, &message_args(".perms ad")).unwrap();
+    assert_eq!(route.usage(".", "perms"), ".perms add <role> <permissions or modules...>");
+    let route = closest_route(&routes, &message_args(".perms <@&123456789012345678> 1")).unwrap();
+    assert_eq!(route.usage(".", "perms"), ".perms <role>");
+}
+
+#[test]
+fn test_cooldowns() {
+    let now = Utc::now();
+    let cooldown = Cooldown { bucket: CooldownBucket::User, seconds: 10 };
+    let mut cooldowns = Cooldowns::default();
+
+    assert!(cooldowns.remaining("new", CooldownBucket::User, 1, now).is_none());
+    cooldowns.start("new", &cooldown, 1, now);
+    assert_eq!(cooldowns.remaining("new", CooldownBucket::User, 1, now + Duration::seconds(4)), Some(Duration::seconds(6)));
+    assert!(cooldowns.remaining("new", CooldownBucket::User, 2, now).is_none());
+    assert!(cooldowns.remaining("new", CooldownBucket::Guild, 1, now).is_none());
+    assert!(cooldowns.remaining("ban", CooldownBucket::User, 1, now).is_none());
+    assert!(cooldowns.remaining("new", CooldownBucket::User, 1, now + Duration::seconds(10)).is_none());
+
+    cooldowns.start("new", &Cooldown { bucket: CooldownBucket::User, seconds: 0 }, 3, now);
+    assert!(cooldowns.remaining("new", CooldownBucket::User, 3, now).is_none());
+
+    // too long cooldowns are cut to a week instead of overflowing the date
+    cooldowns.start("new", &Cooldown { bucket: CooldownBucket::User, seconds: i64::max_value() }, 4, now);
+    assert_eq!(cooldowns.remaining("new", CooldownBucket::User, 4, now), Some(Duration::seconds(MAX_COOLDOWN)));
+
+    assert_eq!(CooldownBucket::from_name("channel"), Some(CooldownBucket::Channel));
+    assert_eq!(CooldownBucket::from_name("server"), None);
+    assert_eq!(CooldownBucket::Guild.config_name(), "cooldown-guild");
+
+    assert_eq!(format_duration(Duration::seconds(5)), "5s");
+    assert_eq!(format_duration(Duration::milliseconds(4200)), "5s");
+    assert_eq!(format_duration(Duration::seconds(3725)), "1h 2m 5s");
+    assert_eq!(format_duration(Duration::days(2)), "2d");
+    assert_eq!(format_duration(Duration::zero()), "0s");
+}
+
+#[test]
+fn test_command_config() {
+    let num = CommandConfig::new("limit", ArgOption::Numeric, "5");
+    assert_eq!(num.parse("10"), Ok("10".to_string()));
+    assert!(num.parse("ten").is_err());
+    assert!(num.parse("NaN").is_err());
+    assert!(num.parse("inf").is_err());
+
+    let cooldown = CommandConfig::new("cooldown-user", ArgOption::Numeric, "0").max(MAX_COOLDOWN as f64);
+    assert_eq!(cooldown.parse("60"), Ok("60".to_string()));
+    assert!(cooldown.parse("1e30").is_err());
+
+    let toggle = CommandConfig::new("dm-user", ArgOption::Boolean, "false");
+    assert_eq!(toggle.parse("yes"), Ok("true".to_string()));
+    assert!(toggle.parse("maybe").is_err());
+
+    let channel = CommandConfig::new("logs", ArgOption::Channel, "");
+    assert_eq!(channel.parse("<#123>"), Ok("123".to_string()));
+
+    let mode = CommandConfig::new("mode", ArgOption::Text, "soft").values(&["soft", "hard"]);
+    assert_eq!(mode.parse("hard"), Ok("hard".to_string()));
+    assert!(mode.parse("medium").is_err());
+
+    let built = CommandBuilder::new("test")
+        .config(CommandConfig::new("delay", ArgOption::Time, "1h"))
+        .handler(|_, _, _, _| Ok(()))
+        .try_build();
+    assert!(built.is_ok());
+    assert!(CommandBuilder::new("test")
+        .config(CommandConfig::new("delay", ArgOption::Numeric, "soon"))
+        .handler(|_, _, _, _| Ok(()))
+        .try_build()
+        .is_err());
+    assert!(CommandBuilder::new("test")
+        .config(CommandConfig::new("delay", ArgOption::Time, ""))
+        .config(CommandConfig::new("delay", ArgOption::Time, ""))
+        .handler(|_, _, _, _| Ok(()))
+        .try_build()
+        .is_err());
+}
+
+#[test]
+fn test_tag_placeholders() {
+    assert_eq!(
+        fill_placeholders("Hi {user}, read the rules of {server} before posting in {channel}!", "<@1>", "<#2>", "Utter"),
+        "Hi <@1>, read the rules of Utter before posting in <#2>!"
+    );
+    assert_eq!(fill_placeholders("{user} {user} {unknown}", "<@1>", "", ""), "<@1> <@1> {unknown}");
+}
+
+#[test]
+fn test_scheduler_queue() {
+    let now = Utc::now().naive_utc();
+    let mut queue = Queue::default();
+    assert_eq!(queue.pop_due(now), None);
+    assert_eq!(queue.time_left(now), None);
+
+    queue.push(1, now + Duration::minutes(10));
+    queue.push(2, now - Duration::minutes(1));
+    queue.push(3, now + Duration::minutes(5));
+
+    assert_eq!(queue.pop_due(now), Some(2));
+    assert_eq!(queue.pop_due(now), None);
+    assert_eq!(queue.time_left(now), Some(Duration::minutes(5)));
+    assert_eq!(queue.pop_due(now + Duration::minutes(10)), Some(3));
+    assert_eq!(queue.pop_due(now + Duration::minutes(10)), Some(1));
+
+    assert_eq!(retry_delay(1), Duration::seconds(30));
+    assert_eq!(retry_delay(3), Duration::minutes(2));
+
+    // stale queue entries of retried operations must not run them before their backoff ends
+    let mut op = test_temp_operation("7");
+    assert!(is_due(&op, now + Duration::seconds(1)));
+    op.end_date = now + retry_delay(1);
+    assert!(!is_due(&op, now));
+    op.end_date = now;
+    op.attempts = MAX_ATTEMPTS;
+    assert!(!is_due(&op, now + Duration::seconds(1)));
+}
+
+#[test]
+fn test_automod_rules() {
+    let now = Utc::now();
+    let mut history = MessageHistory::default();
+    history.push(1, 2, "hello", now - Duration::minutes(5));
+    history.push(1, 2, "spam", now - Duration::seconds(3));
+    history.push(1, 2, "SPAM", now - Duration::seconds(2));
+    history.push(1, 2, "spam", now);
+    history.push(1, 3, "spam", now);
+    assert_eq!(history.count_since(1, 2, now - Duration::seconds(5)), 3);
+    assert_eq!(history.duplicates(1, 2), 3);
+    assert_eq!(history.duplicates(1, 3), 1);
+    assert_eq!(history.duplicates(2, 2), 0);
+
+    assert_eq!(caps_percent("HELLO there!"), Some(50));
+    assert_eq!(caps_percent("OK"), None);
+    assert!(has_invite("join discord.gg/abc"));
+    assert!(!has_invite("discord is cool"));
+    assert_eq!(link_hosts("see <https://www.Example.com/a?b> and http://user@sub.test.org:80"), vec!["example.com", "sub.test.org"]);
+    let blocked = vec!["test.org".to_string()];
+    assert_eq!(blocked_domain("http://sub.test.org/x", &blocked), Some("sub.test.org".to_string()));
+    assert!(blocked_domain("http://nottest.org", &blocked).is_none());
+    assert!(is_zalgo("h\u{0301}\u{0302}\u{0303}i"));
+    assert!(!is_zalgo("café"));
+    assert_eq!(parse_ids("<@&123>, 456 <#789> abc"), vec![123, 456, 789]);
+}
+
+#[test]
+fn test_word_filter() {
+    assert_eq!(normalize("Sh1T, B\u{0430}D  w\u{200B}ord!"), "shit bad word");
+    assert_eq!(normalize("ｆｕｌｌ café $cam"), "full cafe scam");
+    assert_eq!(parse_pattern(" /b(a|4)d/ "), ("b(a|4)d".to_string(), true));
+    assert_eq!(parse_pattern("BAD"), ("bad".to_string(), false));
+    assert!(WordPattern::new("/b(a/", true).is_err());
+    assert!(WordPattern::new("!!", false).is_err());
+
+    let patterns = vec![
+        WordPattern::new("bad", false).unwrap(),
+        WordPattern::new("sc(a|4)m+", true).unwrap(),
+        WordPattern::new("free nitro", false).unwrap(),
+    ];
+    assert!(patterns[0].matches("this is B4D!"));
+    assert!(!patterns[0].matches("badge"));
+    assert!(patterns[1].matches("SCAMMM"));
+    assert_eq!(redact("so b@d, not a sc4m", &patterns), "so *** not a ***");
+    assert_eq!(redact("b a d is fine", &patterns), "b a d is fine");
+    // no single word matches the phrase
+    assert_eq!(redact("get FREE n1tro now", &patterns), "***");
+}
+
+#[test]
+fn test_join_history() {
+    let now = Utc::now();
+    let mut history = JoinHistory::default();
+    assert!(history.push(1, 2, now - Duration::seconds(30), 3, Duration::seconds(10)).is_none());
+    assert!(history.push(1, 3, now - Duration::seconds(5), 3, Duration::seconds(10)).is_none());
+    assert!(history.push(2, 4, now, 3, Duration::seconds(10)).is_none());
+    match history.push(1, 5, now, 2, Duration::seconds(10)) {
+        Some(Raid::Started(wave)) => assert_eq!(wave, vec![3, 5]),
+        _ => panic!("raid should have started"),
+    }
+    assert!(match history.push(1, 6, now + Duration::seconds(8), 2, Duration::seconds(10)) {
+        Some(Raid::Continues) => true,
+        _ => false,
+    });
+    // raid is over once nobody joins within the raid time
+    assert!(history.push(1, 7, now + Duration::seconds(30), 2, Duration::seconds(10)).is_none());
+    assert!(history.push(1, 8, now, 0, Duration::seconds(10)).is_none());
+}
+
+#[test]
+fn test_sticky_roles() {
+    let saved = vec!["1".to_string(), "2".to_string(), "3".to_string(), "oops".to_string()];
+    assert_eq!(restored_roles(&saved, &[1, 2, 3], &[]), vec![1, 2, 3]);
+    // deleted roles are not given back
+    assert_eq!(restored_roles(&saved, &[1, 3], &[]), vec![1, 3]);
+    // mute role and excluded roles are skipped
+    assert_eq!(restored_roles(&saved, &[1, 2, 3], &[2, 3]), vec![1]);
+    assert!(restored_roles(&[], &[1, 2, 3], &[]).is_empty());
+}
+
+fn test_server_info() -> ServerInfo {
+    ServerInfo {
+        server: Some(Server {
+            id: 1,
+            guildid: "10".to_string(),
+            prefix: ".".to_string(),
+            enabledmodules: Vec::new(),
+            last_case_id: 0,
+            last_ticket_number: 0,
+        }),
+        special_entities: Some(vec![SpecialEntity {
+            id: 1,
+            server_id: 1,
+            entity_type: SpecialEntityType::MuteRole as i32,
+            entity_id: "5".to_string(),
+        }]),
+        ..ServerInfo::default()
+    }
+}
+
+fn test_ticket(opener_id: u64, subject: Option<&str>) -> Ticket {
+    Ticket {
+        id: 1,
+        server_id: 1,
+        number: 1,
+        channel_id: "5".to_string(),
+        opener_id: opener_id.to_string(),
+        claimed_by: None,
+        status: TicketStatus::Open as i32,
+        subject: subject.map(|s| s.to_string()),
+        creation_date: Utc::now().naive_utc() - Duration::hours(2),
+        solved_date: None,
+        closed_date: None,
+        type_id: None,
+    }
+}
+
+fn test_ticket_type() -> TicketType {
+    TicketType {
+        id: 2,
+        server_id: 1,
+        name: "billing".to_string(),
+        emoji: "1️⃣".to_string(),
+        category_id: "6".to_string(),
+        support_role_ids: vec!["20".to_string()],
+        name_pattern: DEFAULT_NAME_PATTERN.to_string(),
+        questions: vec!["What is your order number?".to_string()],
+        welcome: Some("Hello {user}, billing team is on the way.".to_string()),
+    }
+}
+
+fn test_temp_operation(target_id: &str) -> TempOperation {
+    TempOperation {
+        id: 1,
+        server_id: 1,
+        action_type: 0,
+        target_id: target_id.to_string(),
+        end_date: Utc::now().naive_utc(),
+        attempts: 0,
+        last_error: None,
+    }
+}
+
+#[test]
+fn test_expired_punishments() {
+    let info = test_server_info();
+    let muted = FakeGateway {
+        members: vec![MemberInfo { id: 7, name: "muted".to_string(), roles: vec![5] }],
+        roles: vec![RoleInfo { id: 5, name: "Muted".to_string() }],
+        ..FakeGateway::default()
+    };
+    assert!(MuteCommand.unmute(&muted, &info, &test_temp_operation("7")).is_ok());
+    assert!(BanCommand.unban(&muted, &info, &test_temp_operation("8")).is_ok());
+    assert_eq!(muted.calls(), vec![Call::RemoveRole(10, 7, 5), Call::Unban(10, 8)]);
+
+    // role was already taken away or does not exist anymore
+    let unmuted = FakeGateway {
+        members: vec![MemberInfo { id: 7, name: "unmuted".to_string(), roles: Vec::new() }],
+        roles: vec![RoleInfo { id: 5, name: "Muted".to_string() }],
+        ..FakeGateway::default()
+    };
+    assert!(MuteCommand.unmute(&unmuted, &info, &test_temp_operation("7")).is_ok());
+    assert!(MuteCommand.unmute(&FakeGateway::default(), &info, &test_temp_operation("7")).is_ok());
+    assert!(unmuted.calls().is_empty());
+
+    // failures are returned so the scheduler can retry them
+    muted.failing.set(true);
+    assert!(MuteCommand.unmute(&muted, &info, &test_temp_operation("7")).is_err());
+    assert!(BanCommand.unban(&muted, &info, &test_temp_operation("8")).is_err());
+
+    // broken rows are failures too, instead of panics that would stop the scheduler
+    assert!(MuteCommand.unmute(&muted, &info, &test_temp_operation("seven")).is_err());
+    assert!(BanCommand.unban(&unmuted, &info, &test_temp_operation("")).is_err());
+    assert!(BanCommand.unban(&unmuted, &ServerInfo::default(), &test_temp_operation("8")).is_err());
+    assert!(unmuted.calls().is_empty());
+}
+
+#[test]
+fn test_ticket_flow() {
+    let gateway = FakeGateway::default();
+    let channel_id = NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(7, None), &[], &default_ticket_type(3), ".").unwrap();
+    assert_eq!(gateway.channel(channel_id).unwrap().name, "ticket-1");
+    assert_eq!(gateway.embeds(channel_id)[0].title, "Ticket has been created!");
+    assert!(NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(7, None), &[], &default_ticket_type(3), ".").is_err());
+
+    assert!(SolvedTicketCommand.mark_solved(&gateway, channel_id, None, UserId(2), 4, Duration::hours(1)).is_err());
+    let (owner, _) = SolvedTicketCommand.mark_solved(&gateway, channel_id, None, UserId(2), 3, Duration::hours(1)).unwrap();
+    assert_eq!(owner, UserId(7));
+    assert_eq!(gateway.embeds(channel_id)[1].title, "Marked as solved!");
+    assert!(SolvedTicketCommand.mark_solved(&gateway, channel_id, None, UserId(2), 3, Duration::hours(1)).is_err());
+
+    SolvedTicketCommand.close(&gateway, &ServerInfo::default(), &test_temp_operation(&channel_id.to_string())).unwrap();
+    assert!(gateway.channel(channel_id).is_none());
+    assert_eq!(gateway.calls().last(), Some(&Call::DeleteChannel(channel_id)));
+}
+
+#[test]
+fn test_case_log() {
+    let mut case = Action {
+        id: 1,
+        server_id: 1,
+        action_type: ActionType::Warn as i32,
+        issuer: "2".to_string(),
+        target: Some("7".to_string()),
+        message: "User test has been warned".to_string(),
+        creation_date: Utc::now().naive_utc(),
+        case_id: Some(12),
+        reason: None,
+        log_channel_id: None,
+        log_message_id: None,
+        expired: false
+    };
+    let embed = case_embed(&case);
+    assert_eq!(embed.title, "Case #12 | Warn");
+    assert_eq!(embed.description, "User test has been warned\n**Reason:** *not given*\n**Moderator:** <@2>");
+
+    // cases that never made it to mod logs have nothing to edit
+    let gateway = FakeGateway::default();
+    update_case_log(&gateway, &case, false);
+    assert!(gateway.calls().is_empty());
+
+    case.reason = Some("spam".to_string());
+    case.log_channel_id = Some("3".to_string());
+    case.log_message_id = Some("4".to_string());
+    update_case_log(&gateway, &case, true);
+    match &gateway.calls()[..] {
+        [Call::EditEmbed(3, 4, embed)] => {
+            assert_eq!(embed.title, "Case #12 | Warn (deleted)");
+            assert!(embed.description.starts_with("~~User test has been warned\n**Reason:** spam"));
+        },
+        calls => panic!("unexpected calls: {:?}", calls)
+    }
+}
+
+fn test_message(days_ago: i64, author: &str, bot: bool) -> MessageInfo {
+    let millis = (Utc::now() - Duration::days(days_ago)).timestamp_millis() as u64 - 1_420_070_400_000;
+    MessageInfo {
+        // keeps ids unique for messages sent at the same time
+        id: (millis << 22) + days_ago as u64,
+        author_id: if bot { 1 } else { 2 },
+        author_name: author.to_string(),
+        bot,
+        content: String::new(),
+        attachments: Vec::new(),
+        embeds: Vec::new(),
+    }
+}
+
+#[test]
+fn test_purge() {
+    let message = test_message(0, "user", false);
+    assert!((Utc::now().naive_utc() - message_date(message.id)) < Duration::seconds(1));
+
+    let gateway = FakeGateway {
+        messages: RefCell::new(vec![
+            test_message(1, "bot", true),
+            test_message(2, "user", false),
+            test_message(3, "bot", true),
+            test_message(20, "bot", true),
+            test_message(30, "bot", true),
+        ]),
+        ..FakeGateway::default()
+    };
+    let now = Utc::now().naive_utc();
+    let deleted = PurgeCommand.purge(&gateway, 5, u64::max_value(), 3, &PurgeFilter::Bots, now).unwrap();
+    assert_eq!(deleted.len(), 3);
+    assert_eq!(purge_breakdown(&deleted), "**bot:** 3");
+    // messages older than 14 days can't be bulk deleted
+    assert_eq!(gateway.calls(), vec![
+        Call::DeleteMessages(5, vec![deleted[0].id, deleted[1].id]),
+        Call::DeleteMessage(5, deleted[2].id),
+    ]);
+
+    let deleted = PurgeCommand.purge(&gateway, 5, u64::max_value(), 10, &PurgeFilter::After(deleted[2].id), now).unwrap();
+    assert_eq!(purge_breakdown(&deleted), "**user:** 1");
+    assert_eq!(gateway.messages.borrow().len(), 1);
+}
+
+#[test]
+fn test_lockdown_channels() {
+    let everyone = PermissionOverwriteType::Role(RoleId(10));
+    let channel = |id: u64, kind: ChannelType, overwrites: Vec<PermissionOverwrite>| ChannelInfo {
+        id,
+        name: "channel".to_string(),
+        kind,
+        category_id: None,
+        overwrites,
+    };
+    let overwrite = PermissionOverwrite { allow: Permissions::READ_MESSAGES, deny: Permissions::EMBED_LINKS, kind: everyone };
+    let gateway = FakeGateway {
+        channels: RefCell::new(vec![
+            channel(1, ChannelType::Text, vec![overwrite.to_owned()]),
+            channel(2, ChannelType::Text, Vec::new()),
+            channel(3, ChannelType::Voice, Vec::new()),
+        ]),
+        ..FakeGateway::default()
+    };
+
+    let locked = lock_channels(&gateway, &test_server_info(), 10).unwrap();
+    assert_eq!(locked.len(), 2);
+    let first = &gateway.channel(1).unwrap().overwrites[0];
+    assert_eq!(first.allow, Permissions::READ_MESSAGES);
+    assert_eq!(first.deny, Permissions::EMBED_LINKS | Permissions::SEND_MESSAGES);
+    assert_eq!(gateway.channel(2).unwrap().overwrites[0].deny, Permissions::SEND_MESSAGES);
+    assert!(gateway.channel(3).unwrap().overwrites.is_empty());
+
+    let locked: Vec<LockedChannel> = locked.into_iter()
+        .map(|ch| LockedChannel { id: 1, server_id: ch.server_id, channel_id: ch.channel_id, allow: ch.allow, deny: ch.deny })
+        .collect();
+    assert_eq!(restore_channels(&gateway, 10, &locked), 2);
+    let first = &gateway.channel(1).unwrap().overwrites[0];
+    assert_eq!((first.allow, first.deny), (overwrite.allow, overwrite.deny));
+    assert!(gateway.channel(2).unwrap().overwrites.is_empty());
+    assert!(gateway.calls().contains(&Call::RemovePermission(2, everyone)));
+
+    gateway.failing.set(true);
+    assert!(lock_channels(&gateway, &test_server_info(), 10).is_err());
+}
+
+#[test]
+fn test_lockdown_args() {
+    let routes = LockdownCommand::command().args().unwrap();
+    // there is no usage route, so the command runs without arguments too
+    assert!(parse_args(&routes, &args_from_content(".lockdown", false)).unwrap().is_none());
+    assert!(routes.iter().all(|r| !r.name.is_empty()));
+
+    let args = parse_args(&routes, &args_from_content(".lockdown raid incoming", false)).unwrap().unwrap();
+    assert!(!args.has("time"));
+    assert_eq!(args.get_str("reason").unwrap(), "raid incoming");
+
+    let args = parse_args(&routes, &args_from_content(".lockdown 30m raid incoming", false)).unwrap().unwrap();
+    assert_eq!(args.get_duration("time").unwrap(), Duration::minutes(30));
+    assert_eq!(args.get_str("reason").unwrap(), "raid incoming");
+}
+
+#[test]
+fn test_lock_args() {
+    let routes = LockCommand::command().args().unwrap();
+    let args = parse_args(&routes, &args_from_content(".lock spam wave", false)).unwrap().unwrap();
+    assert!(!args.has("channel"));
+    assert!(!args.has("time"));
+    assert_eq!(args.get_str("reason").unwrap(), "spam wave");
+
+    let args = parse_args(&routes, &args_from_content(".lock 30m spam wave", false)).unwrap().unwrap();
+    assert!(!args.has("channel"));
+    assert_eq!(args.get_duration("time").unwrap(), Duration::minutes(30));
+
+    // channels are given only as mentions or IDs
+    let args = parse_args(&routes, &args_from_content(".lock <#246604909451935745> 1h spam", false)).unwrap().unwrap();
+    assert_eq!(args.get_str("channel").unwrap(), "<#246604909451935745>");
+    assert_eq!(args.get_duration("time").unwrap(), Duration::hours(1));
+    assert_eq!(args.get_str("reason").unwrap(), "spam");
+    let args = parse_args(&routes, &args_from_content(".lock 246604909451935745", false)).unwrap().unwrap();
+    assert_eq!(args.get_str("channel").unwrap(), "246604909451935745");
+    assert!(!args.has("time"));
+}
+
+#[test]
+fn test_slowmode() {
+    assert_eq!(parse_slowmode("off"), Ok(0));
+    assert_eq!(parse_slowmode("30"), Ok(30));
+    assert!(parse_slowmode("21601").is_err());
+    assert!(parse_slowmode("-1").is_err());
+
+    let gateway = FakeGateway::default();
+    gateway.create_channel(10, &NewChannel {
+        name: "general".to_string(),
+        topic: String::new(),
+        category_id: None,
+        overwrites: Vec::new(),
+    }).unwrap();
+    let channel_id = gateway.channels(10)[0].id;
+
+    SlowmodeCommand.expire(&gateway, &ServerInfo::default(), &test_temp_operation(&channel_id.to_string())).unwrap();
+    assert_eq!(gateway.calls().last(), Some(&Call::SetSlowmode(channel_id, 0)));
+    // deleted channels are skipped
+    gateway.delete_channel(channel_id).unwrap();
+    SlowmodeCommand.expire(&gateway, &ServerInfo::default(), &test_temp_operation(&channel_id.to_string())).unwrap();
+    assert_eq!(gateway.calls().last(), Some(&Call::DeleteChannel(channel_id)));
+}
+
+#[test]
+fn test_dm_notifications() {
+    let values = [("action", Some("banned")), ("reason", None), ("appeal", Some("Appeal here"))];
+    assert_eq!(fill_notice("You have been {action}.\n**Reason:** {reason}\n{appeal}", &values), "You have been banned.\nAppeal here");
+
+    let mut info = test_server_info();
+    let gateway = FakeGateway { guild_name: Some("Guild".to_string()), ..FakeGateway::default() };
+    let notice = Notice { title: "Ban!", action: "banned", reason: Some("spam"), duration: None, moderator_id: 2 };
+    // users are not messaged unless the command has it turned on
+    assert!(!NotifyCommand.notify(&gateway, &info, "ban", 10, 7, &notice));
+    assert!(gateway.calls().is_empty());
+
+    info.command_configs = Some(vec![DBCommandConfig {
+        id: 1,
+        server_id: 1,
+        command_name: "ban".to_string(),
+        config_name: "dm-user".to_string(),
+        value: "true".to_string(),
+    }]);
+    assert!(NotifyCommand.notify(&gateway, &info, "ban", 10, 7, &notice));
+    match gateway.calls().as_slice() {
+        [Call::SendDm(7, embed)] => assert_eq!(embed.description, "You have been banned on **Guild**.\n**Reason:** spam"),
+        calls => panic!("Unexpected calls: {:?}", calls),
+    }
+
+    // closed direct messages
+    gateway.failing.set(true);
+    assert!(!NotifyCommand.notify(&gateway, &info, "ban", 10, 7, &notice));
+}
+
+#[test]
+fn test_ticket_transcripts() {
+    let now = Utc::now().naive_utc();
+    assert_eq!(message_date(date_id(now)).timestamp_millis(), now.timestamp_millis());
+
+    let mut question = test_message(2, "author", false);
+    question.content = "<b>help</b>\nplease".to_string();
+    question.attachments = vec!["https://cdn.example.com/screen.png".to_string()];
+    let mut answer = test_message(1, "support", true);
+    answer.embeds = vec![Embed::new("Answer", "Restart it", 0xff0000)];
+    let gateway = FakeGateway {
+        messages: RefCell::new(vec![answer, question]),
+        ..FakeGateway::default()
+    };
+
+    let messages = channel_history(&gateway, 5, now).unwrap();
+    let text = text_transcript("ticket-1", &messages);
+    assert!(text.contains("author (2): <b>help</b>\n    please\n    Attachment: https://cdn.example.com/screen.png\n"));
+    assert!(text.contains("support (1): \n    Embed: Answer | Restart it\n"));
+    let html = html_transcript("ticket-1", &messages);
+    assert!(html.contains("&lt;b&gt;help&lt;/b&gt;"));
+    assert!(html.contains("border-color: #ff0000"));
+    // the oldest message goes first
+    assert!(html.find("help").unwrap() < html.find("Restart it").unwrap());
+
+    let channel_id = NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(7, None), &[], &default_ticket_type(3), ".").unwrap();
+    let config = |name: &str, value: &str| DBCommandConfig {
+        id: 1,
+        server_id: 1,
+        command_name: "solved".to_string(),
+        config_name: name.to_string(),
+        value: value.to_string(),
+    };
+    let info = ServerInfo {
+        command_configs: Some(vec![config("transcript-channel", "99"), config("dm-transcript", "true")]),
+        ..ServerInfo::default()
+    };
+    SolvedTicketCommand.close(&gateway, &info, &test_temp_operation(&channel_id.to_string())).unwrap();
+
+    let files = vec!["ticket-1.html".to_string(), "ticket-1.txt".to_string()];
+    let calls = gateway.calls();
+    assert_eq!(&calls[calls.len() - 3..], &[
+        Call::DeleteChannel(channel_id),
+        Call::SendFiles(99, files.clone()),
+        Call::SendFiles(7, files),
+    ]);
+    // channel that is already gone is skipped
+    assert!(SolvedTicketCommand.close(&gateway, &info, &test_temp_operation(&channel_id.to_string())).is_ok());
+}
+
+#[test]
+fn test_tickets_list() {
+    let mut ticket = test_ticket(7, Some("Can't log in"));
+    assert!(ticket_matches(&ticket, None, "2"));
+    assert!(ticket_matches(&ticket, Some("open"), "2"));
+    assert!(!ticket_matches(&ticket, Some("solved"), "2"));
+    assert!(ticket_matches(&ticket, Some("mine"), "7"));
+    assert!(!ticket_matches(&ticket, Some("mine"), "2"));
+    assert_eq!(ticket_line(&ticket, ticket.creation_date + Duration::hours(2)), "**#1** <#5> by <@7>, opened 2h ago - Can't log in");
+
+    ticket.status = TicketStatus::Solved as i32;
+    ticket.claimed_by = Some("2".to_string());
+    assert!(ticket_matches(&ticket, Some("solved"), "2"));
+    assert!(ticket_matches(&ticket, Some("mine"), "2"));
+    assert_eq!(ticket_line(&ticket, ticket.creation_date + Duration::hours(2)), "**#1** <#5> by <@7>, opened 2h ago, solved, claimed by <@2> - Can't log in");
+}
+
+#[test]
+fn test_ticket_participants() {
+    let gateway = FakeGateway::default();
+    let mut ticket = test_ticket(7, Some("Can't log in"));
+    let channel_id = NewTicketCommand.open_ticket(&gateway, 10, &ticket, &[], &default_ticket_type(3), ".").unwrap();
+    ticket.channel_id = channel_id.to_string();
+    let has_access = |user_id: u64| gateway.channel(channel_id).unwrap().overwrites.iter()
+        .any(|o| o.kind == PermissionOverwriteType::Member(UserId(user_id)));
+
+    assert!(add_participant(&gateway, channel_id, 8).is_ok());
+    assert!(add_participant(&gateway, channel_id, 8).is_err());
+    assert!(has_access(8));
+    assert!(remove_participant(&gateway, &ticket, 7).is_err());
+
+    let claimed = set_claim(&gateway, &ticket, Some(2)).unwrap();
+    assert_eq!(claimed.claimed_by, Some("2".to_string()));
+    assert!(has_access(2));
+    assert!(ticket_topic(&claimed).ends_with(". Subject: Can't log in. Claimed by: <@2>"));
+    assert_eq!(gateway.calls().last(), Some(&Call::SetTopic(channel_id, ticket_topic(&claimed))));
+    assert!(set_claim(&gateway, &claimed, Some(7)).is_err());
+
+    // the previous claimer loses the access, but added users keep it
+    let unclaimed = set_claim(&gateway, &claimed, None).unwrap();
+    assert_eq!(unclaimed.claimed_by, None);
+    assert!(!has_access(2));
+    assert!(has_access(8));
+
+    // added user is neither the owner of the ticket nor blocked from opening one
+    let (owner, _) = SolvedTicketCommand.mark_solved(&gateway, channel_id, Some(UserId(7)), UserId(2), 3, Duration::hours(1)).unwrap();
+    assert_eq!(owner, UserId(7));
+    assert!(NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(8, None), &[ticket.clone()], &default_ticket_type(3), ".").is_ok());
+    assert!(NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(7, None), &[ticket.clone()], &default_ticket_type(3), ".").is_err());
+
+    assert!(remove_participant(&gateway, &ticket, 8).is_ok());
+    assert!(!has_access(8));
+    assert!(remove_participant(&gateway, &ticket, 8).is_err());
+}
+
+#[test]
+fn test_ticket_panel() {
+    let gateway = FakeGateway::default();
+    let ticket_types = vec![default_ticket_type(3), test_ticket_type()];
+    let panel_id = NewTicketCommand.post_panel(&gateway, 4, ".", &ticket_types).unwrap();
+    assert_eq!(gateway.embeds(4)[0].title, "Tickets");
+    assert!(gateway.embeds(4)[0].description.contains("🎫 **ticket**\n1️⃣ **billing**"));
+    assert!(gateway.embeds(4)[0].description.contains("`.new [type] [subject]`"));
+    assert_eq!(gateway.calls()[1..].to_vec(), vec![
+        Call::AddReaction(4, panel_id, PANEL_EMOJI.to_string()),
+        Call::AddReaction(4, panel_id, "1️⃣".to_string()),
+    ]);
+
+    gateway.failing.set(true);
+    assert!(NewTicketCommand.post_panel(&gateway, 4, ".", &ticket_types).is_err());
+}
+
+#[test]
+fn test_ticket_types() {
+    let billing = test_ticket_type();
+    let ticket_types = vec![billing.clone()];
+    assert!(valid_type_name("report-user"));
+    assert!(!valid_type_name("report a user"));
+    assert!(!valid_type_name(""));
+    assert_eq!(next_emoji(&[]), Some("1️⃣"));
+    assert_eq!(next_emoji(&ticket_types), Some("2️⃣"));
+    assert_eq!(channel_name(DEFAULT_NAME_PATTERN, 12, "billing"), "billing-12");
+    assert_eq!(channel_name("Help {number} {type}", 3, "billing"), "help-3-billing");
+    assert_eq!(parse_questions("What happened? | | When?"), vec!["What happened?".to_string(), "When?".to_string()]);
+
+    let (ticket_type, subject) = pick_ticket_type(&ticket_types, Some("Billing  charged twice".to_string()));
+    assert_eq!((ticket_type.map(|t| t.id), subject), (Some(2), Some("charged twice".to_string())));
+    let (ticket_type, subject) = pick_ticket_type(&ticket_types, Some("billing".to_string()));
+    assert_eq!((ticket_type.map(|t| t.id), subject), (Some(2), None));
+    let (ticket_type, subject) = pick_ticket_type(&ticket_types, Some("my billing".to_string()));
+    assert_eq!((ticket_type.map(|t| t.id), subject), (None, Some("my billing".to_string())));
+    assert!(pick_ticket_type(&ticket_types, None).0.is_none());
+
+    // only the opener, support roles and the bot can see the ticket
+    let gateway = FakeGateway { bot_id: 99, ..FakeGateway::default() };
+    let channel_id = NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(7, None), &[], &billing, ".").unwrap();
+    let channel = gateway.channel(channel_id).unwrap();
+    assert_eq!((channel.name.as_str(), channel.category_id), ("billing-1", Some(6)));
+    assert_eq!(channel.overwrites.iter().map(|o| o.kind).collect::<Vec<PermissionOverwriteType>>(), vec![
+        PermissionOverwriteType::Member(UserId(7)),
+        PermissionOverwriteType::Role(RoleId(20)),
+        PermissionOverwriteType::Member(UserId(99)),
+        PermissionOverwriteType::Role(RoleId(10)),
+    ]);
+    let welcome = gateway.embeds(channel_id)[0].description.to_owned();
+    assert!(welcome.starts_with("Hello <@7>, billing team is on the way. Type `.solved`"));
+    assert!(welcome.ends_with("**Please answer these questions:**\n1. What is your order number?"));
+
+    // a ticket of another type can be opened at the same time
+    assert!(NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(7, None), &[], &billing, ".").is_err());
+    assert!(NewTicketCommand.open_ticket(&gateway, 10, &test_ticket(7, None), &[], &default_ticket_type(3), ".").is_ok());
+}
@@ -1,36 +1,54 @@
 use serenity::model::id::GuildId;
-use crate::database::models::{Role, Server, NewRole, NewServer, NewDBCommand, DBCommand, NewAction, NewTempOperation, NewSpecialEntity, SpecialEntityType, SpecialEntity, Action};
+use crate::database::models::{Role, Server, NewRole, NewServer, NewDBCommand, DBCommand, CommandAlias, NewCommandAlias, DBCommandConfig, NewDBCommandConfig, NewAction, TempOperation, NewTempOperation, NewSpecialEntity, SpecialEntityType, SpecialEntity, Action, Tag, NewTag, WarnThreshold, NewWarnThreshold, FilteredWord, NewFilteredWord, LockedChannel, NewLockedChannel, StickyRoles, NewStickyRoles, NewTicketTranscript, Ticket, NewTicket, TicketStatus, TicketType, NewTicketType};
 use crate::database::get_db_con;
+use crate::scheduler;
 use diesel::{RunQueryDsl, QueryDsl, BelongingToDsl, TextExpressionMethods, ExpressionMethods};
 use crate::database::schema::servers::columns::guildid;
-use crate::database::schema::{servers, roles, commands, actions, temp_operations, special_entities};
-use chrono::{DateTime, Utc};
+use crate::database::schema::{servers, roles, commands, command_aliases, command_configs, actions, temp_operations, special_entities, tags, warn_thresholds, filtered_words, locked_channels, sticky_roles, ticket_transcripts, ticket_types, tickets};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use crate::database::schema::actions::columns::{action_type, target};
 
+#[derive(Default)]
 pub struct ServerInfo {
     pub server: Option<Server>,
     pub disabled_commands: Option<Vec<DBCommand>>,
+    pub aliases: Option<Vec<CommandAlias>>,
+    pub command_configs: Option<Vec<DBCommandConfig>>,
     pub roles: Option<Vec<Role>>,
-    pub special_entities: Option<Vec<SpecialEntity>>
+    pub special_entities: Option<Vec<SpecialEntity>>,
+    pub tags: Option<Vec<Tag>>,
+    pub filtered_words: Option<Vec<FilteredWord>>
 }
 
 impl ServerInfo {
     pub fn new(guild_id: Option<GuildId>) -> ServerInfo {
         let server = get_db_server(guild_id);
         let mut disabled_commands = None;
+        let mut aliases = None;
+        let mut command_configs = None;
         let mut roles = None;
         let mut special_entities = None;
+        let mut tags = None;
+        let mut filtered_words = None;
         if let Some(s) = server.to_owned() {
             disabled_commands = get_db_commands(&s);
+            aliases = get_db_aliases(&s);
+            command_configs = get_db_command_configs(&s);
             roles = get_db_roles(&s);
             special_entities = get_special_entities(&s);
+            tags = get_db_tags(&s);
+            filtered_words = get_db_filtered_words(&s);
         }
 
         ServerInfo {
             server,
             disabled_commands,
+            aliases,
+            command_configs,
             roles,
-            special_entities
+            special_entities,
+            tags,
+            filtered_words
         }
     }
 }
@@ -169,6 +187,447 @@ pub fn get_db_commands(server: &Server) -> Option<Vec<DBCommand>> {
     }
 }
 
+pub fn command_disabled(info: &ServerInfo, command_name: &str, channel_id: &str) -> bool {
+    match &info.disabled_commands {
+        Some(commands) => commands.iter()
+            .any(|c| c.command_name == command_name && c.disabled_channels.iter().any(|ch| ch == channel_id)),
+        None => false
+    }
+}
+
+pub fn get_db_aliases(server: &Server) -> Option<Vec<CommandAlias>> {
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let query = CommandAlias::belonging_to(server).load::<CommandAlias>(&db);
+
+    if let Ok(result) = query {
+        return Some(result)
+    } else {
+        None
+    }
+}
+
+pub fn create_db_alias(info: &ServerInfo, alias: String, command_name: String) {
+    let new_alias = NewCommandAlias {
+        server_id: info.server.clone().unwrap().id,
+        alias,
+        command_name
+    };
+
+    diesel::insert_into(command_aliases::table)
+        .values(&new_alias)
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Error occurred while inserting new alias");
+}
+
+pub fn delete_db_alias(info: &ServerInfo, alias: &str) -> bool {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return false
+    };
+
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let query = CommandAlias::belonging_to(server).filter(command_aliases::columns::alias.eq(alias));
+
+    match diesel::delete(query).execute(&db) {
+        Ok(deleted) => deleted > 0,
+        Err(_) => false
+    }
+}
+
+pub fn get_db_command_configs(server: &Server) -> Option<Vec<DBCommandConfig>> {
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let query = DBCommandConfig::belonging_to(server).load::<DBCommandConfig>(&db);
+
+    if let Ok(result) = query {
+        return Some(result)
+    } else {
+        None
+    }
+}
+
+pub fn get_command_config(info: &ServerInfo, command_name: &str, config_name: &str) -> Option<String> {
+    match &info.command_configs {
+        Some(configs) => configs.iter()
+            .find(|c| c.command_name == command_name && c.config_name == config_name)
+            .map(|c| c.value.to_owned()),
+        None => None
+    }
+}
+
+pub fn set_command_config(info: &ServerInfo, command_name: &str, config_name: &str, value: String) {
+    let new_config = NewDBCommandConfig {
+        server_id: info.server.clone().unwrap().id,
+        command_name: command_name.to_string(),
+        config_name: config_name.to_string(),
+        value: value.to_owned()
+    };
+
+    let existing = match &info.command_configs {
+        Some(configs) => configs.iter().find(|c| c.command_name == command_name && c.config_name == config_name),
+        None => None
+    };
+
+    let db = &get_db_con().get().expect("Could not get db pool!");
+    match existing {
+        Some(c) => diesel::update(command_configs::dsl::command_configs.find(c.id))
+            .set(command_configs::value.eq(&value))
+            .execute(db)
+            .expect("Could not update the command config!"),
+        None => diesel::insert_into(command_configs::table)
+            .values(&new_config)
+            .execute(db)
+            .expect("Error occurred while inserting new command config")
+    };
+}
+
+pub fn delete_command_config(info: &ServerInfo, command_name: &str, config_name: &str) {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return
+    };
+
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let query = DBCommandConfig::belonging_to(server)
+        .filter(command_configs::columns::command_name.eq(command_name))
+        .filter(command_configs::columns::config_name.eq(config_name));
+
+    let _ = diesel::delete(query).execute(&db);
+}
+
+pub fn get_db_tags(server: &Server) -> Option<Vec<Tag>> {
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let query = Tag::belonging_to(server).load::<Tag>(&db);
+
+    if let Ok(result) = query {
+        return Some(result)
+    } else {
+        None
+    }
+}
+
+pub fn get_tag(info: &ServerInfo, name: &str) -> Option<Tag> {
+    match &info.tags {
+        Some(tags) => tags.iter().find(|t| t.name == name).cloned(),
+        None => None
+    }
+}
+
+pub fn create_db_tag(info: &ServerInfo, name: String, content: String, owner: String) {
+    let new_tag = NewTag {
+        server_id: info.server.clone().unwrap().id,
+        creation_date: Utc::now().naive_utc(),
+        name,
+        content,
+        owner
+    };
+
+    diesel::insert_into(tags::table)
+        .values(&new_tag)
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Error occurred while inserting new tag");
+}
+
+pub fn update_db_tag(tag: &Tag, content: &str) {
+    diesel::update(tags::dsl::tags.find(tag.id))
+        .set(tags::content.eq(content))
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Could not update the tag!");
+}
+
+pub fn increase_tag_uses(tag: &Tag) {
+    let _ = diesel::update(tags::dsl::tags.find(tag.id))
+        .set(tags::uses.eq(tags::uses + 1))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+// disabled channels of the tag are removed too, so a new tag with the same name starts clean
+pub fn delete_db_tag(info: &ServerInfo, tag: &Tag) {
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let _ = diesel::delete(tags::dsl::tags.find(tag.id)).execute(&db);
+
+    if let Some(server) = &info.server {
+        let query = DBCommand::belonging_to(server).filter(commands::columns::command_name.eq(&tag.name));
+        let _ = diesel::delete(query).execute(&db);
+    }
+}
+
+pub fn get_warn_thresholds(info: &ServerInfo) -> Vec<WarnThreshold> {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return Vec::new()
+    };
+
+    WarnThreshold::belonging_to(server)
+        .order(warn_thresholds::warn_level)
+        .load(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Could not load warn thresholds")
+}
+
+// there is only one threshold per level, so the old one is replaced
+pub fn set_warn_threshold(info: &ServerInfo, level: i32, action: String, duration: Option<String>) {
+    let server = info.server.clone().unwrap();
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let existing = WarnThreshold::belonging_to(&server).filter(warn_thresholds::warn_level.eq(level));
+    let _ = diesel::delete(existing).execute(&db);
+
+    let new_threshold = NewWarnThreshold {
+        server_id: server.id,
+        warn_level: level,
+        action,
+        duration
+    };
+
+    diesel::insert_into(warn_thresholds::table)
+        .values(&new_threshold)
+        .execute(&db)
+        .expect("Error occurred while inserting new warn threshold");
+}
+
+/// Returns false when there was no threshold for the level.
+pub fn delete_warn_threshold(info: &ServerInfo, level: i32) -> bool {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return false
+    };
+
+    let query = WarnThreshold::belonging_to(server).filter(warn_thresholds::warn_level.eq(level));
+    match diesel::delete(query).execute(&get_db_con().get().expect("Could not get db pool!")) {
+        Ok(deleted) => deleted > 0,
+        Err(_) => false
+    }
+}
+
+pub fn get_db_filtered_words(server: &Server) -> Option<Vec<FilteredWord>> {
+    let db = get_db_con().get().expect("Could not get db pool!");
+    FilteredWord::belonging_to(server)
+        .order(filtered_words::id)
+        .load::<FilteredWord>(&db)
+        .ok()
+}
+
+pub fn get_filtered_word(info: &ServerInfo, pattern: &str, is_regex: bool) -> Option<FilteredWord> {
+    match &info.filtered_words {
+        Some(words) => words.iter().find(|w| w.pattern == pattern && w.is_regex == is_regex).cloned(),
+        None => None
+    }
+}
+
+pub fn create_filtered_word(info: &ServerInfo, pattern: String, is_regex: bool) {
+    let new_word = NewFilteredWord {
+        server_id: info.server.clone().unwrap().id,
+        pattern,
+        is_regex
+    };
+
+    diesel::insert_into(filtered_words::table)
+        .values(&new_word)
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Error occurred while inserting new filtered word");
+}
+
+pub fn delete_filtered_word(word: &FilteredWord) {
+    let _ = diesel::delete(filtered_words::dsl::filtered_words.find(word.id))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+pub fn get_locked_channels(info: &ServerInfo) -> Vec<LockedChannel> {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return Vec::new()
+    };
+
+    LockedChannel::belonging_to(server)
+        .load(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Could not load locked channels")
+}
+
+pub fn create_locked_channels(channels: &[NewLockedChannel]) {
+    diesel::insert_into(locked_channels::table)
+        .values(channels)
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Error occurred while inserting locked channels");
+}
+
+pub fn delete_locked_channels(info: &ServerInfo) {
+    if let Some(server) = &info.server {
+        let _ = diesel::delete(LockedChannel::belonging_to(server))
+            .execute(&get_db_con().get().expect("Could not get db pool!"));
+    }
+}
+
+// there is only one snapshot per member, the one from the last leave
+pub fn set_sticky_roles(info: &ServerInfo, user_id: String, role_ids: Vec<String>) {
+    let server = info.server.clone().unwrap();
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let existing = StickyRoles::belonging_to(&server).filter(sticky_roles::user_id.eq(&user_id));
+    let _ = diesel::delete(existing).execute(&db);
+
+    let new_roles = NewStickyRoles {
+        server_id: server.id,
+        user_id,
+        role_ids
+    };
+
+    diesel::insert_into(sticky_roles::table)
+        .values(&new_roles)
+        .execute(&db)
+        .expect("Error occurred while inserting sticky roles");
+}
+
+/// Returns the roles saved when the member left and forgets them.
+pub fn take_sticky_roles(info: &ServerInfo, user_id: &str) -> Option<StickyRoles> {
+    let server = info.server.as_ref()?;
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let roles = StickyRoles::belonging_to(server)
+        .filter(sticky_roles::user_id.eq(user_id))
+        .first::<StickyRoles>(&db)
+        .ok()?;
+
+    let _ = diesel::delete(sticky_roles::table.find(roles.id)).execute(&db);
+    Some(roles)
+}
+
+pub fn create_ticket_transcript(transcript: &NewTicketTranscript) {
+    diesel::insert_into(ticket_transcripts::table)
+        .values(transcript)
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Error occurred while inserting ticket transcript");
+}
+
+/// Ticket with the next number of the server. Its channel is set once the channel is created.
+pub fn create_ticket(info: &ServerInfo, opener_id: String, type_id: Option<i32>, subject: Option<String>) -> Result<Ticket, String> {
+    let server = info.server.clone().unwrap();
+    let db = get_db_con().get().expect("Could not get db pool!");
+    // counter is bumped in one statement, so tickets opened at the same moment get different numbers
+    let number: i32 = diesel::update(servers::table.find(server.id))
+        .set(servers::last_ticket_number.eq(servers::last_ticket_number + 1))
+        .returning(servers::last_ticket_number)
+        .get_result(&db)
+        .map_err(|_| "Could not create the ticket. Try again!".to_string())?;
+
+    let new_ticket = NewTicket {
+        server_id: server.id,
+        number,
+        channel_id: String::new(),
+        opener_id,
+        status: TicketStatus::Open as i32,
+        subject,
+        creation_date: Utc::now().naive_utc(),
+        type_id
+    };
+
+    diesel::insert_into(tickets::table)
+        .values(&new_ticket)
+        .get_result(&db)
+        .map_err(|_| "Could not create the ticket. Try again!".to_string())
+}
+
+/// Ticket types of the server in the order they were created.
+pub fn get_ticket_types(info: &ServerInfo) -> Vec<TicketType> {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return Vec::new()
+    };
+
+    TicketType::belonging_to(server)
+        .order(ticket_types::id)
+        .load(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Could not load ticket types")
+}
+
+pub fn get_ticket_type(info: &ServerInfo, name: &str) -> Option<TicketType> {
+    get_ticket_types(info).into_iter().find(|t| t.name == name)
+}
+
+pub fn get_ticket_type_by_id(id: i32) -> Option<TicketType> {
+    ticket_types::table.find(id)
+        .first(&get_db_con().get().expect("Could not get db pool!"))
+        .ok()
+}
+
+pub fn create_ticket_type(ticket_type: &NewTicketType) {
+    diesel::insert_into(ticket_types::table)
+        .values(ticket_type)
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Error occurred while inserting new ticket type");
+}
+
+/// Saves settings of the ticket type. Its name, emoji and category stay the same.
+pub fn update_ticket_type(ticket_type: &TicketType) {
+    diesel::update(ticket_types::table.find(ticket_type.id))
+        .set((
+            ticket_types::support_role_ids.eq(&ticket_type.support_role_ids),
+            ticket_types::name_pattern.eq(&ticket_type.name_pattern),
+            ticket_types::questions.eq(&ticket_type.questions),
+            ticket_types::welcome.eq(&ticket_type.welcome)
+        ))
+        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Could not update the ticket type!");
+}
+
+// tickets of the type are kept, they just lose the type
+pub fn delete_ticket_type(ticket_type: &TicketType) {
+    let _ = diesel::delete(ticket_types::table.find(ticket_type.id))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+pub fn set_ticket_channel(ticket: &Ticket, channel_id: u64) {
+    let _ = diesel::update(tickets::table.find(ticket.id))
+        .set(tickets::channel_id.eq(channel_id.to_string()))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+pub fn delete_ticket(ticket: &Ticket) {
+    let _ = diesel::delete(tickets::table.find(ticket.id))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+pub fn get_ticket_by_channel(info: &ServerInfo, channel_id: u64) -> Option<Ticket> {
+    let server = info.server.as_ref()?;
+    Ticket::belonging_to(server)
+        .filter(tickets::channel_id.eq(channel_id.to_string()))
+        .first(&get_db_con().get().expect("Could not get db pool!"))
+        .ok()
+}
+
+/// Tickets that are not closed yet, the newest first.
+pub fn get_active_tickets(info: &ServerInfo) -> Vec<Ticket> {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return Vec::new()
+    };
+
+    Ticket::belonging_to(server)
+        .filter(tickets::status.ne(TicketStatus::Closed as i32))
+        .order(tickets::number.desc())
+        .load(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Could not load tickets")
+}
+
+pub fn set_ticket_claim(ticket: &Ticket, user_id: Option<u64>) {
+    let _ = diesel::update(tickets::table.find(ticket.id))
+        .set(tickets::claimed_by.eq(user_id.map(|id| id.to_string())))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+// reopened ticket is not solved anymore
+pub fn set_ticket_status(ticket: &Ticket, status: TicketStatus) {
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let target = tickets::table.find(ticket.id);
+    let now = Some(Utc::now().naive_utc());
+    let _ = match status {
+        TicketStatus::Open => diesel::update(target)
+            .set((tickets::status.eq(status as i32), tickets::solved_date.eq(None::<NaiveDateTime>)))
+            .execute(&db),
+        TicketStatus::Solved => diesel::update(target)
+            .set((tickets::status.eq(status as i32), tickets::solved_date.eq(now)))
+            .execute(&db),
+        TicketStatus::Closed => diesel::update(target)
+            .set((tickets::status.eq(status as i32), tickets::closed_date.eq(now)))
+            .execute(&db),
+    };
+}
+
 #[allow(dead_code)]
 #[derive(Copy, Clone)]
 pub enum ActionType {
@@ -183,7 +642,16 @@ pub enum ActionType {
     ChannelUnLock = 9,
     NewTicket = 10,
     SolvedTicket = 11,
-    UnSolvedTicket = 12
+    UnSolvedTicket = 12,
+    Purge = 13,
+    Lockdown = 14,
+    UnLockdown = 15,
+    Slowmode = 16,
+    ClaimTicket = 17,
+    UnClaimTicket = 18,
+    AssignTicket = 19,
+    AddTicketUser = 20,
+    RemoveTicketUser = 21
 }
 
 pub fn get_user_warn_lvl(info: &ServerInfo, user_id: &str) -> i64 {
@@ -193,21 +661,35 @@ pub fn get_user_warn_lvl(info: &ServerInfo, user_id: &str) -> i64 {
     };
 
     let db = get_db_con().get().expect("Could not get db pool!");
-    let warns: i64 = Action::belonging_to(server)
+    // reduced warns are expired right away, see `reduce_warn`
+    Action::belonging_to(server)
         .filter(action_type.eq(ActionType::Warn as i32))
         .filter(target.like(user_id))
+        .filter(actions::expired.eq(false))
         .count()
         .get_result(&db)
-        .expect("Could not load warn actions");
+        .expect("Could not load warn actions")
+}
+
+/// Expires the newest warn of the user that is still active, so a reduction can't outlive the warn it cancelled.
+/// Returns the cancelled warn, `None` when the user has no active warns.
+pub fn reduce_warn(info: &ServerInfo, user_id: &str) -> Option<Action> {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return None
+    };
 
-    let reduced_warns: i64 = Action::belonging_to(server)
-        .filter(action_type.eq(ActionType::ReducedWarn as i32))
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let warn = Action::belonging_to(server)
+        .filter(action_type.eq(ActionType::Warn as i32))
         .filter(target.like(user_id))
-        .count()
-        .get_result(&db)
-        .expect("Could not load reduced actions");
+        .filter(actions::expired.eq(false))
+        .order(actions::creation_date.desc())
+        .first::<Action>(&db)
+        .ok()?;
 
-    warns - reduced_warns
+    expire_action(warn.id);
+    Some(warn)
 }
 
 pub fn get_actions_by_kind(info: &ServerInfo, user_id: String, kinds: Vec<ActionType>,) -> Option<Vec<Action>> {
@@ -245,7 +727,9 @@ pub fn create_action(info: &ServerInfo, issuer: String, target_id: Option<String
         creation_date: Utc::now().naive_utc(),
         target: target_id,
         issuer,
-        message
+        message,
+        case_id: None,
+        reason: None
     };
 
     diesel::insert_into(actions::table)
@@ -254,6 +738,74 @@ pub fn create_action(info: &ServerInfo, issuer: String, target_id: Option<String
         .expect("Error occurred while inserting new action");
 }
 
+/// Moderation action with the next case number of the server. `message` should not contain the reason.
+pub fn create_case(info: &ServerInfo, issuer: String, target_id: Option<String>, action_kind: ActionType, message: String, reason: Option<String>) -> Action {
+    let server = info.server.clone().unwrap();
+    let db = get_db_con().get().expect("Could not get db pool!");
+    // counter is bumped in one statement, so concurrent actions get different numbers and numbers of deleted
+    // cases are not given out again
+    let case_id: i32 = diesel::update(servers::table.find(server.id))
+        .set(servers::last_case_id.eq(servers::last_case_id + 1))
+        .returning(servers::last_case_id)
+        .get_result(&db)
+        .expect("Could not get the next case number");
+
+    let new_action = NewAction {
+        server_id: server.id,
+        action_type: action_kind as i32,
+        creation_date: Utc::now().naive_utc(),
+        target: target_id,
+        issuer,
+        message,
+        case_id: Some(case_id),
+        reason
+    };
+
+    diesel::insert_into(actions::table)
+        .values(&new_action)
+        .get_result(&db)
+        .expect("Error occurred while inserting new case")
+}
+
+pub fn get_case(info: &ServerInfo, case: i32) -> Option<Action> {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return None
+    };
+
+    Action::belonging_to(server)
+        .filter(actions::case_id.eq(case))
+        .first(&get_db_con().get().expect("Could not get db pool!"))
+        .ok()
+}
+
+pub fn update_case_reason(case: &Action, reason: &str) -> Action {
+    diesel::update(actions::table.find(case.id))
+        .set(actions::reason.eq(reason))
+        .get_result(&get_db_con().get().expect("Could not get db pool!"))
+        .expect("Could not update the case!")
+}
+
+pub fn set_case_log_message(case: &Action, channel_id: u64, message_id: u64) {
+    let _ = diesel::update(actions::table.find(case.id))
+        .set((
+            actions::log_channel_id.eq(channel_id.to_string()),
+            actions::log_message_id.eq(message_id.to_string())
+        ))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+pub fn expire_action(action_id: i32) {
+    let _ = diesel::update(actions::table.find(action_id))
+        .set(actions::expired.eq(true))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
+pub fn delete_case(case: &Action) {
+    let _ = diesel::delete(actions::table.find(case.id))
+        .execute(&get_db_con().get().expect("Could not get db pool!"));
+}
+
 pub fn create_temp_operation(info: &ServerInfo, target_id: String, end_date: DateTime<Utc>, action_kind: ActionType) {
     let new_entry = NewTempOperation {
         server_id: info.server.clone().unwrap().id,
@@ -262,10 +814,55 @@ pub fn create_temp_operation(info: &ServerInfo, target_id: String, end_date: Dat
         target_id,
     };
 
-    diesel::insert_into(temp_operations::table)
+    let op = diesel::insert_into(temp_operations::table)
         .values(&new_entry)
-        .execute(&get_db_con().get().expect("Could not get db pool!"))
+        .get_result::<TempOperation>(&get_db_con().get().expect("Could not get db pool!"))
         .expect("Error occurred while inserting new temp operation");
+
+    scheduler::schedule(&op);
+}
+
+/// User is muted when their last mute wasn't followed by an unmute. Temp mutes are over once their time has passed,
+/// even when the user couldn't be unmuted because they left the server.
+pub fn is_user_muted(info: &ServerInfo, user_id: &str) -> bool {
+    let server = match &info.server {
+        Some(s) => s,
+        None => return false
+    };
+
+    let db = get_db_con().get().expect("Could not get db pool!");
+    let last = Action::belonging_to(server)
+        .filter(action_type.eq_any(vec![ActionType::Mute as i32, ActionType::UnMute as i32]))
+        .filter(target.eq(user_id))
+        .order(actions::creation_date.desc())
+        .first::<Action>(&db);
+    let mute = match last {
+        Ok(a) if a.action_type == ActionType::Mute as i32 => a,
+        _ => return false
+    };
+
+    // temp operations of earlier mutes are deleted on every new mute, the ones left by older versions are skipped
+    // since they ended before the last mute
+    let temp_mute = TempOperation::belonging_to(server)
+        .filter(temp_operations::target_id.eq(user_id))
+        .filter(temp_operations::action_type.eq(ActionType::Mute as i32))
+        .filter(temp_operations::end_date.ge(mute.creation_date))
+        .order(temp_operations::id.desc())
+        .first::<TempOperation>(&db);
+    match temp_mute {
+        Ok(op) => op.end_date > Utc::now().naive_utc(),
+        Err(_) => true
+    }
+}
+
+// operation ended early by hand shouldn't run again later
+pub fn delete_temp_operations(info: &ServerInfo, target_id: &str, action_kind: ActionType) {
+    if let Some(server) = &info.server {
+        let query = TempOperation::belonging_to(server)
+            .filter(temp_operations::target_id.eq(target_id))
+            .filter(temp_operations::action_type.eq(action_kind as i32));
+        let _ = diesel::delete(query).execute(&get_db_con().get().expect("Could not get db pool!"));
+    }
 }
 
 pub fn get_special_entities(server: &Server) -> Option<Vec<SpecialEntity>> {
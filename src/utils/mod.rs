@@ -1,4 +1,6 @@
 use serenity::model::channel::Message;
+use crate::command::EMBED_ERROR_COLOR;
+use crate::gateway::{DiscordGateway, Embed};
 use chrono::{DateTime, Utc, Duration};
 
 pub mod object_finding;
@@ -10,31 +12,97 @@ pub fn check_if_dev(msg: &Message) -> bool {
     msg.author.id.to_string() == "246604909451935745"
 }
 
-pub fn get_time(mut time_msg: &str) -> Result<DateTime<Utc>, String> {
+// used by moderation commands with `dm-user` setting turned on
+pub fn send_dm(gateway: &dyn DiscordGateway, user_id: u64, title: &str, content: &str) -> bool {
+    gateway.send_dm(user_id, &Embed::new(title, content, EMBED_ERROR_COLOR)).is_ok()
+}
+
+// Levenshtein distance counted in chars
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push((prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1));
+        }
+        prev = current;
+    }
+
+    prev[b.len()]
+}
+
+pub fn get_time(time_msg: &str) -> Result<DateTime<Utc>, String> {
+    Ok(Utc::now() + get_duration(time_msg)?)
+}
+
+/// Whether the text is written like a time, e.g. `5m` or `-5m`, even if it's not a valid one.
+pub fn is_time_format(time_msg: &str) -> bool {
+    let time_msg = time_msg.trim();
+    time_msg.len() > 1
+        && time_msg.is_ascii()
+        && time_msg[..time_msg.len() - 1].parse::<i64>().is_ok()
+        && ["s", "m", "h", "d"].contains(&&time_msg[time_msg.len() - 1..])
+}
+
+pub fn get_duration(mut time_msg: &str) -> Result<Duration, String> {
     time_msg = time_msg.trim();
     if time_msg.is_empty() {
         return Err("Provided `time` is empty!".to_string())
     }
 
-    if time_msg.len() > 1 {
-        let time = match time_msg[..time_msg.len()-1].parse::<i64>() {
+    // every valid time is ASCII, so cutting off the last byte can't split a char
+    if time_msg.len() > 1 && time_msg.is_ascii() {
+        let (number, unit) = time_msg.split_at(time_msg.len() - 1);
+        let time = match number.parse::<i64>() {
             Ok(num) => num,
             Err(_) => return Err("`time` value is not an integer!".to_string())
         };
 
-        let last_char = time_msg.chars().last().unwrap();
-        return match last_char {
-            's' => Ok(Utc::now() + Duration::seconds(time)),
-            'm' => Ok(Utc::now() + Duration::minutes(time)),
-            'h' => Ok(Utc::now() + Duration::hours(time)),
-            'd' => Ok(Utc::now() + Duration::days(time)),
-            _ => {
-                return Err("Invalid `time` has been provided!".to_string())
-            }
+        let unit_seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return Err("Invalid `time` has been provided!".to_string())
+        };
+
+        // `Duration::seconds` panics past `i64::MAX` milliseconds and adding the duration to a date panics
+        // past the year 262143, so both are checked before the time is used
+        let max_seconds = Duration::max_value().num_seconds();
+        match time.checked_mul(unit_seconds) {
+            // already due operations would run right away
+            Some(seconds) if seconds <= 0 => Err("Provided `time` has to be longer than 0!".to_string()),
+            Some(seconds) if seconds <= max_seconds => {
+                let duration = Duration::seconds(seconds);
+                match Utc::now().checked_add_signed(duration) {
+                    Some(_) => Ok(duration),
+                    None => Err("Provided `time` is too long!".to_string())
+                }
+            },
+            _ => Err("Provided `time` is too long!".to_string())
         }
+    } else {
+        Err("Invalid `time` has been provided!".to_string())
+    }
+}
 
+// formats duration like `1h 5m 3s`, rounding up to full seconds
+pub fn format_duration(duration: Duration) -> String {
+    let mut seconds = (duration.num_milliseconds().max(0) + 999) / 1000;
+    let mut parts = Vec::new();
+    for (unit, len) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)].iter() {
+        if seconds >= *len {
+            parts.push(format!("{}{}", seconds / len, unit));
+            seconds %= len;
+        }
+    }
 
+    if parts.is_empty() {
+        String::from("0s")
     } else {
-        return Err("Invalid `time` has been provided!".to_string())
+        parts.join(" ")
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use crate::command::EMBED_QUESTION_COLOR;
+use crate::command::{EMBED_QUESTION_COLOR, ParsedArgs, get_args, args_offset};
 use serenity::prelude::Context;
 use serenity::model::channel::{Message, GuildChannel, ChannelType};
 use serenity::model::guild;
@@ -134,7 +134,28 @@ pub fn find_object<T>(ctx: &Context, msg: &Message, objects: Vec<T>, args: &Vec<
     Ok(0)
 }
 
-pub fn get_role_from_id(ctx: &Context, msg: &Message, mut args: Vec<String>, a_index: usize) -> Result<Option<guild::Role>, String> {
+/// Role given as `name` argument. Mentions and IDs are already converted by `parse_args`, anything else is searched by name.
+pub fn get_role_from_arg(ctx: &Context, msg: &Message, args: &ParsedArgs, name: &str) -> Result<Option<guild::Role>, String> {
+    let role_id = match args.get_role(name) {
+        Some(id) => id,
+        None => return get_role_from_id(ctx, msg, args.index(name).unwrap())
+    };
+
+    match ctx.http.get_guild_roles(msg.guild_id.unwrap().0) {
+        Ok(roles) => match roles.into_iter().find(|r| r.id == role_id) {
+            Some(r) => Ok(Some(r)),
+            None => Err("Could not find requested role!".to_string())
+        },
+        Err(_) => Err("Could not retrieve guild roles!".to_string())
+    }
+}
+
+// `a_index` is the index of the command argument (as returned by `get_args(msg, false)`)
+pub fn get_role_from_id(ctx: &Context, msg: &Message, a_index: usize) -> Result<Option<guild::Role>, String> {
+    find_role(ctx, msg, get_args(msg.to_owned(), true), args_offset(msg) + a_index)
+}
+
+fn find_role(ctx: &Context, msg: &Message, mut args: Vec<String>, a_index: usize) -> Result<Option<guild::Role>, String> {
     let mut tmp_id = args[a_index].to_owned();
     if msg.mention_roles.len() != 0 {
         tmp_id = msg.mention_roles[0].to_string();
@@ -154,12 +175,32 @@ pub fn get_role_from_id(ctx: &Context, msg: &Message, mut args: Vec<String>, a_i
     let found_role = find_object(ctx, msg, g_roles, &args, a_index,FindType::Role)?;
     if found_role != 0 {
         args[a_index] = found_role.to_string();
-        return get_role_from_id(ctx, msg, args, a_index)
+        return find_role(ctx, msg, args, a_index)
     }
     Ok(None)
 }
 
-pub fn get_channel_from_id(ctx: &Context, msg: &Message, mut args: Vec<String>, a_index: usize) -> Result<Option<GuildChannel>, String> {
+/// Text channel given as `name` argument. Mentions and IDs are already converted by `parse_args`, anything else is searched by name.
+pub fn get_channel_from_arg(ctx: &Context, msg: &Message, args: &ParsedArgs, name: &str) -> Result<Option<GuildChannel>, String> {
+    let channel_id = match args.get_channel(name) {
+        Some(id) => id,
+        None => return get_channel_from_id(ctx, msg, args.index(name).unwrap())
+    };
+
+    match ctx.http.get_channels(msg.guild_id.unwrap().0) {
+        Ok(channels) => match channels.into_iter().find(|c| c.id == channel_id && c.kind == ChannelType::Text) {
+            Some(c) => Ok(Some(c)),
+            None => Err("Could not find requested channel!".to_string())
+        },
+        Err(_) => Err("Could not retrieve guild channels!".to_string())
+    }
+}
+
+pub fn get_channel_from_id(ctx: &Context, msg: &Message, a_index: usize) -> Result<Option<GuildChannel>, String> {
+    find_channel(ctx, msg, get_args(msg.to_owned(), true), args_offset(msg) + a_index)
+}
+
+fn find_channel(ctx: &Context, msg: &Message, mut args: Vec<String>, a_index: usize) -> Result<Option<GuildChannel>, String> {
     let mut tmp_id = args[a_index].to_owned();
     match &msg.mention_channels {
         Some(mch) => if mch.len() != 0 {
@@ -185,12 +226,16 @@ pub fn get_channel_from_id(ctx: &Context, msg: &Message, mut args: Vec<String>,
     let found_channel = find_object(ctx, msg, channels, &args, a_index, FindType::Channel)?;
     if found_channel != 0 {
         args[a_index] = found_channel.to_string();
-        return get_channel_from_id(ctx, msg, args, a_index)
+        return find_channel(ctx, msg, args, a_index)
     }
     Ok(None)
 }
 
-pub fn get_member_from_id(ctx: &Context, msg: &Message, mut args: Vec<String>, a_index: usize) -> Result<Option<guild::Member>, String> {
+pub fn get_member_from_id(ctx: &Context, msg: &Message, a_index: usize) -> Result<Option<guild::Member>, String> {
+    find_member(ctx, msg, get_args(msg.to_owned(), true), args_offset(msg) + a_index)
+}
+
+fn find_member(ctx: &Context, msg: &Message, mut args: Vec<String>, a_index: usize) -> Result<Option<guild::Member>, String> {
     let tmp_id =  if msg.mentions.len() > 0 {
         msg.mentions[0].id.to_string()
     } else {
@@ -227,7 +272,7 @@ pub fn get_member_from_id(ctx: &Context, msg: &Message, mut args: Vec<String>, a
     let found_user = find_object(ctx, msg, members, &args, a_index, FindType::User)?;
     if found_user != 0 {
         args[a_index] = found_user.to_string();
-        return get_member_from_id(ctx, msg, args, a_index)
+        return find_member(ctx, msg, args, a_index)
     }
     Ok(None)
 }
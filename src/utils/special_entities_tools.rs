@@ -1,23 +1,69 @@
-use crate::utils::db::{ServerInfo, get_special_entity_by_type};
-use serenity::prelude::Context;
-use crate::database::models::SpecialEntityType;
-use serenity::model::id::ChannelId;
+use crate::utils::db::{ServerInfo, ActionType, get_special_entity_by_type, set_case_log_message};
+use crate::database::models::{Action, SpecialEntityType};
+use crate::gateway::{DiscordGateway, Embed};
 use crate::command::EMBED_REGULAR_COLOR;
 
-pub fn send_to_mod_logs(ctx: &Context, info: &ServerInfo, title: &str, content: &str) {
+pub fn send_to_mod_logs(gateway: &dyn DiscordGateway, info: &ServerInfo, title: &str, content: &str) {
     let channel_id = match get_special_entity_by_type(info, SpecialEntityType::ModLogsChannel) {
         Some(ch) => ch.entity_id,
         None => return
     };
 
-    let _ = ChannelId::from(channel_id.parse::<u64>().unwrap())
-        .send_message(&ctx.http, |m| {
-            m.embed(|e| {
-                e.title(title);
-                e.description(content);
-                e.color(EMBED_REGULAR_COLOR);
-                e
-            });
-            m
-        });
-}
\ No newline at end of file
+    let _ = gateway.send_embed(channel_id.parse::<u64>().unwrap(), &Embed::new(title, content, EMBED_REGULAR_COLOR));
+}
+
+/// Sends the case to mod logs and remembers the message, so it can be edited when the case changes.
+pub fn log_case(gateway: &dyn DiscordGateway, info: &ServerInfo, case: &Action) {
+    let channel_id = match get_special_entity_by_type(info, SpecialEntityType::ModLogsChannel) {
+        Some(ch) => ch.entity_id.parse::<u64>().unwrap(),
+        None => return
+    };
+
+    if let Ok(message_id) = gateway.send_embed(channel_id, &case_embed(case)) {
+        set_case_log_message(case, channel_id, message_id);
+    }
+}
+
+/// Updates mod logs message of the case. Deleted cases are only marked as such.
+pub fn update_case_log(gateway: &dyn DiscordGateway, case: &Action, deleted: bool) {
+    let (channel_id, message_id) = match (&case.log_channel_id, &case.log_message_id) {
+        (Some(ch), Some(m)) => (ch.parse::<u64>().unwrap(), m.parse::<u64>().unwrap()),
+        _ => return
+    };
+
+    let mut embed = case_embed(case);
+    if deleted {
+        embed.title.push_str(" (deleted)");
+        embed.description = format!("~~{}~~", embed.description);
+    }
+    let _ = gateway.edit_embed(channel_id, message_id, &embed);
+}
+
+pub fn case_embed(case: &Action) -> Embed {
+    Embed::new(
+        &format!("Case #{} | {}", case.case_id.unwrap_or_default(), case_kind_name(case.action_type)),
+        &case_description(case),
+        EMBED_REGULAR_COLOR
+    )
+}
+
+pub fn case_description(case: &Action) -> String {
+    format!(
+        "{}\n**Reason:** {}\n**Moderator:** <@{}>",
+        case.message,
+        case.reason.as_ref().map(|r| r.as_str()).unwrap_or("*not given*"),
+        case.issuer
+    )
+}
+
+fn case_kind_name(kind: i32) -> &'static str {
+    match kind {
+        k if k == ActionType::Ban as i32 => "Ban",
+        k if k == ActionType::UnBan as i32 => "Un-Ban",
+        k if k == ActionType::Kick as i32 => "Kick",
+        k if k == ActionType::Mute as i32 => "Mute",
+        k if k == ActionType::UnMute as i32 => "Un-Mute",
+        k if k == ActionType::Warn as i32 => "Warn",
+        _ => "Action",
+    }
+}